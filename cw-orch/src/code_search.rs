@@ -0,0 +1,95 @@
+//! Lookup of the most recent code with a checksum, shared by the environments that page through the `Codes` query.
+
+/// Number of codes fetched per page when looking up a code by checksum
+pub(crate) const CODES_PAGE_SIZE: u64 = 100;
+/// Most recent codes looked through when looking up a code by checksum
+pub const MAX_CODES_SCANNED: u64 = 1_000;
+
+/// Search through the pages of the `Codes` query, newest first.
+///
+/// Request the page at [`CodeSearch::next_page`] with [`CODES_PAGE_SIZE`] codes in reverse order,
+/// and pass it to [`CodeSearch::page`] until a code is found or there are no more pages.
+pub(crate) struct CodeSearch<'a> {
+    checksum: &'a str,
+    creator: Option<&'a str>,
+    key: Option<Vec<u8>>,
+    scanned: u64,
+}
+
+impl<'a> CodeSearch<'a> {
+    /// Search for the hex encoded checksum, optionally restricted to codes uploaded by `creator`
+    pub(crate) fn new(checksum: &'a str, creator: Option<&'a str>) -> Self {
+        Self {
+            checksum,
+            creator,
+            key: Some(vec![]),
+            scanned: 0,
+        }
+    }
+
+    /// Pagination key of the next page, `None` once all pages or [`MAX_CODES_SCANNED`] codes were looked through
+    pub(crate) fn next_page(&mut self) -> Option<Vec<u8>> {
+        let key = self.key.take()?;
+        if self.scanned >= MAX_CODES_SCANNED {
+            log::warn!(
+                "no code with checksum {} in the {} most recent codes, older codes are not searched",
+                self.checksum,
+                MAX_CODES_SCANNED
+            );
+            return None;
+        }
+        Some(key)
+    }
+
+    /// Look through a page of `(code id, creator, checksum)` and the key of the next page.
+    /// Returns the code id of the first matching code.
+    pub(crate) fn page<'c>(
+        &mut self,
+        codes: impl IntoIterator<Item = (u64, &'c str, &'c [u8])>,
+        next_key: Option<Vec<u8>>,
+    ) -> Option<u64> {
+        self.scanned += CODES_PAGE_SIZE;
+        self.key = next_key.filter(|key| !key.is_empty());
+        codes
+            .into_iter()
+            .find(|(_, creator, checksum)| {
+                hex::encode(checksum).eq_ignore_ascii_case(self.checksum)
+                    && self.creator.map_or(true, |expected| *creator == expected)
+            })
+            .map(|(code_id, _, _)| code_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_after_max_codes() {
+        let mut search = CodeSearch::new("0a0b", None);
+        let mut pages = 0;
+        while let Some(key) = search.next_page() {
+            pages += 1;
+            let found = search.page(
+                [(1, "creator", [0x0c].as_slice())],
+                Some(vec![key.len() as u8 + 1]),
+            );
+            assert_eq!(found, None);
+        }
+        assert_eq!(pages, MAX_CODES_SCANNED / CODES_PAGE_SIZE);
+    }
+
+    #[test]
+    fn finds_code_of_creator() {
+        let mut search = CodeSearch::new("0A0B", Some("creator"));
+        let key = search.next_page().unwrap();
+        assert!(key.is_empty());
+        let codes = [
+            (3, "other", [0x0a, 0x0b].as_slice()),
+            (2, "creator", [0x0a, 0x0b].as_slice()),
+        ];
+        assert_eq!(search.page(codes, None), Some(2));
+        // there is no next page
+        assert_eq!(search.next_page(), None);
+    }
+}
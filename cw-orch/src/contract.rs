@@ -4,7 +4,7 @@ use crate::{
     error::CwOrchError,
     index_response::IndexResponse,
     prelude::{CwEnv, Uploadable},
-    state::StateInterface,
+    state::{DeploymentAction, DeploymentRecord, StateInterface},
 };
 use cosmwasm_std::{Addr, Coin};
 use serde::{de::DeserializeOwned, Serialize};
@@ -56,12 +56,32 @@ impl<Chain: CwEnv + Clone> Contract<Chain> {
         log::info!("Uploading {}", self.id);
         let resp = self.chain.upload(source).map_err(Into::into)?;
         let code_id = resp.uploaded_code_id()?;
-        self.set_code_id(code_id);
+        self.chain.state().try_set_code_id(&self.id, code_id)?;
+        self.record(DeploymentRecord {
+            code_id: Some(code_id),
+            // older chains don't emit the checksum on upload
+            checksum: resp
+                .uploaded_checksum()
+                .or_else(|| self.chain.upload_checksum(source)),
+            tx_hash: resp.tx_hash(),
+            ..DeploymentRecord::new(DeploymentAction::Upload)
+        })?;
         log::info!("uploaded {} with code id {}", self.id, code_id);
         log::debug!("Upload response: {:?}", resp);
         Ok(resp)
     }
 
+    /// Register code that is already on chain for the contract instead of uploading it
+    pub fn reuse_code(&self, code_id: u64, checksum: impl Into<String>) -> Result<(), CwOrchError> {
+        log::info!("reusing code id {} for {}", code_id, self.id);
+        self.chain.state().try_set_code_id(&self.id, code_id)?;
+        self.record(DeploymentRecord {
+            code_id: Some(code_id),
+            checksum: Some(checksum.into()),
+            ..DeploymentRecord::new(DeploymentAction::Reuse)
+        })
+    }
+
     /// Executes an operation on the contract
     pub fn execute<E: Serialize + Debug>(
         &self,
@@ -85,19 +105,22 @@ impl<Chain: CwEnv + Clone> Contract<Chain> {
     ) -> Result<TxResponse<Chain>, CwOrchError> {
         log::info!("Instantiating {} with msg {:#?}", self.id, msg);
 
+        let code_id = self.code_id()?;
         let resp = self
             .chain
-            .instantiate(
-                self.code_id()?,
-                msg,
-                Some(&self.id),
-                admin,
-                coins.unwrap_or(&[]),
-            )
+            .instantiate(code_id, msg, Some(&self.id), admin, coins.unwrap_or(&[]))
             .map_err(Into::into)?;
         let contract_address = resp.instantiated_contract_address()?;
 
-        self.set_address(&contract_address);
+        self.chain
+            .state()
+            .try_set_address(&self.id, &contract_address)?;
+        self.record(DeploymentRecord {
+            code_id: Some(code_id),
+            address: Some(contract_address.to_string()),
+            tx_hash: resp.tx_hash(),
+            ..DeploymentRecord::new(DeploymentAction::Instantiate)
+        })?;
 
         log::info!("Instantiated {} with address {}", self.id, contract_address);
 
@@ -127,9 +150,27 @@ impl<Chain: CwEnv + Clone> Contract<Chain> {
         new_code_id: u64,
     ) -> Result<TxResponse<Chain>, CwOrchError> {
         log::info!("Migrating {:?} to code_id {}", self.id, new_code_id);
-        self.chain
-            .migrate(migrate_msg, new_code_id, &self.address()?)
-            .map_err(Into::into)
+        let address = self.address()?;
+        let resp = self
+            .chain
+            .migrate(migrate_msg, new_code_id, &address)
+            .map_err(Into::into)?;
+        self.record(DeploymentRecord {
+            code_id: Some(new_code_id),
+            address: Some(address.to_string()),
+            tx_hash: resp.tx_hash(),
+            ..DeploymentRecord::new(DeploymentAction::Migrate)
+        })?;
+        Ok(resp)
+    }
+
+    /// Add a record, sent by the chain's sender, to the deployment history of the contract
+    fn record(&self, record: DeploymentRecord) -> Result<(), CwOrchError> {
+        let record = DeploymentRecord {
+            sender: Some(self.chain.sender().to_string()),
+            ..record
+        };
+        self.chain.state().try_record_deployment(&self.id, record)
     }
 
     // State interfaces
@@ -171,4 +212,14 @@ impl<Chain: CwEnv + Clone> Contract<Chain> {
     pub fn set_default_code_id(&mut self, code_id: u64) {
         self.default_code_id = Some(code_id);
     }
+
+    /// Returns the deployment history of the contract, oldest first
+    pub fn history(&self) -> Result<Vec<DeploymentRecord>, CwOrchError> {
+        self.chain.state().get_history(&self.id)
+    }
+
+    /// Restores the code id and address of the entry at `index` of the contract's history
+    pub fn rollback(&self, index: usize) -> Result<DeploymentRecord, CwOrchError> {
+        self.chain.state().rollback(&self.id, index)
+    }
 }
@@ -1,10 +1,18 @@
-use std::rc::Rc;
+use std::{rc::Rc, sync::Arc};
 
 use ibc_chain_registry::chain::ChainData;
 
-use crate::prelude::{DaemonAsync, DaemonBuilder};
+use crate::prelude::{DaemonAsync, DaemonBuilder, WasmLimits};
 
-use super::{error::DaemonError, sender::Sender, state::DaemonState};
+use super::{
+    channel::{ChannelOptions, GrpcEndpointConfig, GrpcPolicy, Transport},
+    error::DaemonError,
+    gas_price::GasPriceSource,
+    pending_tx::ConfirmationPolicy,
+    sender::{Sender, SenderOptions},
+    state::DaemonState,
+    state_backend::StateBackend,
+};
 
 /// The default deployment id if none is provided
 pub const DEFAULT_DEPLOYMENT: &str = "default";
@@ -29,6 +37,14 @@ pub struct DaemonAsyncBuilder {
     pub(crate) deployment_id: Option<String>,
     /// Wallet mnemonic
     pub(crate) mnemonic: Option<String>,
+    /// Fee payment options of the sender
+    pub(crate) sender_options: SenderOptions,
+    /// Transport and endpoint policy used to connect to the chain
+    pub(crate) channel_options: ChannelOptions,
+    /// Storage of the deployment state
+    pub(crate) state_backend: Option<Arc<dyn StateBackend>>,
+    /// Limits wasm files are validated against before upload
+    pub(crate) wasm_limits: WasmLimits,
 }
 
 impl DaemonAsyncBuilder {
@@ -54,6 +70,110 @@ impl DaemonAsyncBuilder {
         self
     }
 
+    /// Pay the tx fees with a fee allowance granted to the sender by `granter`.
+    /// Allows running scripts from unfunded wallets.
+    pub fn fee_granter(&mut self, granter: impl ToString) -> &mut Self {
+        self.sender_options.fee_granter = Some(granter.to_string());
+        self
+    }
+
+    /// Let the account of this mnemonic pay the tx fees.
+    /// The payer signs every tx next to the sender.
+    pub fn fee_payer_mnemonic(&mut self, mnemonic: impl ToString) -> &mut Self {
+        self.sender_options.fee_payer = Some(mnemonic.to_string());
+        self
+    }
+
+    /// Set the denom used to pay tx fees.
+    /// Defaults to the chain's main fee token. Can be any denom accepted by the chain, like an IBC denom.
+    pub fn fee_denom(&mut self, denom: impl ToString) -> &mut Self {
+        self.sender_options.fee_denom = Some(denom.to_string());
+        self
+    }
+
+    /// Set the gas price for the fee denom.
+    /// Defaults to the gas price configured in the chain info for that denom.
+    pub fn gas_price(&mut self, gas_price: f64) -> &mut Self {
+        self.sender_options.gas_price = Some(gas_price);
+        self
+    }
+
+    /// Set where the gas price of txs is queried from.
+    /// Defaults to [`GasPriceSource::Static`], which uses the gas price of the chain info or [`Self::gas_price`].
+    /// That static gas price is also used when the source has no price for the fee denom or can't be queried.
+    pub fn gas_price_source(&mut self, source: GasPriceSource) -> &mut Self {
+        self.sender_options.gas_price_source = source;
+        self
+    }
+
+    /// Set the multiplier applied to the simulated gas of a tx.
+    /// Defaults to 1.3
+    pub fn gas_adjustment(&mut self, gas_adjustment: f64) -> &mut Self {
+        self.sender_options.gas_adjustment = Some(gas_adjustment);
+        self
+    }
+
+    /// Set the maximum fee amount a single tx is allowed to pay.
+    /// Txs that would pay more are not broadcasted.
+    pub fn max_fee(&mut self, max_fee: u128) -> &mut Self {
+        self.sender_options.max_fee = Some(max_fee);
+        self
+    }
+
+    /// Set when a broadcasted tx is considered confirmed.
+    /// Defaults to inclusion in a block without timeout.
+    pub fn confirmation_policy(&mut self, policy: ConfirmationPolicy) -> &mut Self {
+        self.sender_options.confirmation_policy = policy;
+        self
+    }
+
+    /// Set how the gRPC endpoints of the chain are health-checked and how idempotent queries are retried.
+    /// The daemon fails over to the next fastest endpoint when a query fails because of the connection.
+    pub fn grpc_policy(&mut self, policy: GrpcPolicy) -> &mut Self {
+        self.channel_options.policy = policy;
+        self
+    }
+
+    /// Set the protocol used to talk to the nodes of the chain.
+    /// Defaults to [`Transport::Grpc`], use [`Transport::Rest`] for providers that only expose the REST (LCD) endpoint.
+    pub fn transport(&mut self, transport: Transport) -> &mut Self {
+        self.channel_options.transport = transport;
+        self
+    }
+
+    /// Set the connection settings of every gRPC endpoint, like the API key headers, CA certificate, timeouts and concurrency limit.
+    /// Applied to the tx service and to all the queriers.
+    pub fn grpc_config(&mut self, config: GrpcEndpointConfig) -> &mut Self {
+        self.channel_options.grpc_config = config;
+        self
+    }
+
+    /// Set the connection settings of the gRPC endpoint at `address`, replacing the ones set with [`Self::grpc_config`] for that endpoint.
+    pub fn grpc_endpoint_config(
+        &mut self,
+        address: impl Into<String>,
+        config: GrpcEndpointConfig,
+    ) -> &mut Self {
+        self.channel_options
+            .grpc_endpoint_configs
+            .insert(address.into(), config);
+        self
+    }
+
+    /// Set where the deployment state (code ids and addresses) is stored.
+    /// Defaults to the JSON file at the `STATE_FILE` env variable.
+    pub fn state_backend(&mut self, backend: impl StateBackend + 'static) -> &mut Self {
+        self.state_backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Set the size limit and supported capabilities of the chain, which wasm files are validated against before upload.
+    /// Defaults to the wasmd size limit, without capability check.
+    pub fn wasm_limits(&mut self, limits: WasmLimits) -> &mut Self {
+        self.wasm_limits = limits;
+        self
+    }
+
     /// Build a daemon
     pub async fn build(&self) -> Result<DaemonAsync, DaemonError> {
         let chain = self
@@ -64,12 +184,21 @@ impl DaemonAsyncBuilder {
             .deployment_id
             .clone()
             .unwrap_or(DEFAULT_DEPLOYMENT.to_string());
-        let state = Rc::new(DaemonState::new(chain, deployment_id).await?);
+        let mut state = DaemonState::new_with_backend(
+            chain,
+            deployment_id,
+            self.channel_options.clone(),
+            self.state_backend.clone(),
+        )
+        .await?;
+        state.wasm_limits = self.wasm_limits.clone();
+        let state = Rc::new(state);
         // if mnemonic provided, use it. Else use env variables to retrieve mnemonic
+        let sender_options = self.sender_options.clone();
         let sender = if let Some(mnemonic) = &self.mnemonic {
-            Sender::from_mnemonic(&state, mnemonic)?
+            Sender::from_mnemonic_with_options(&state, mnemonic, sender_options)?
         } else {
-            Sender::new(&state)?
+            Sender::new_with_options(&state, sender_options)?
         };
         let daemon = DaemonAsync {
             state,
@@ -85,6 +214,10 @@ impl From<DaemonBuilder> for DaemonAsyncBuilder {
             chain: value.chain,
             deployment_id: value.deployment_id,
             mnemonic: value.mnemonic,
+            sender_options: value.sender_options,
+            channel_options: value.channel_options,
+            state_backend: value.state_backend,
+            wasm_limits: value.wasm_limits,
         }
     }
 }
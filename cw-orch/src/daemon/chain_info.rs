@@ -1,34 +1,50 @@
 use serde::{Deserialize, Serialize};
 
-use ibc_chain_registry::chain::{Apis, ChainData as RegistryChainInfo, FeeToken, FeeTokens, Grpc};
+use ibc_chain_registry::chain::{
+    Apis, ChainData as RegistryChainInfo, FeeToken, FeeTokens, Grpc, Rest,
+};
 
 #[allow(clippy::from_over_into)]
 impl Into<RegistryChainInfo> for ChainInfo<'_> {
     fn into(self) -> RegistryChainInfo {
+        ChainInfoOwned::from(self).into()
+    }
+}
+
+impl From<ChainInfoOwned> for RegistryChainInfo {
+    fn from(value: ChainInfoOwned) -> Self {
         RegistryChainInfo {
-            chain_name: self.network_info.id.to_string(),
-            chain_id: self.chain_id.to_string().into(),
-            bech32_prefix: self.network_info.pub_address_prefix.into(),
+            chain_name: value.network_info.id,
+            chain_id: value.chain_id.into(),
+            bech32_prefix: value.network_info.pub_address_prefix,
             fees: FeeTokens {
                 fee_tokens: vec![FeeToken {
-                    fixed_min_gas_price: self.gas_price,
-                    denom: self.gas_denom.to_string(),
+                    fixed_min_gas_price: value.gas_price,
+                    denom: value.gas_denom,
                     ..Default::default()
                 }],
             },
-            network_type: self.kind.to_string(),
+            network_type: value.kind.to_string(),
             apis: Apis {
-                grpc: self
+                grpc: value
                     .grpc_urls
-                    .iter()
-                    .map(|url| Grpc {
-                        address: url.to_string(),
+                    .into_iter()
+                    .map(|address| Grpc {
+                        address,
+                        ..Default::default()
+                    })
+                    .collect(),
+                rest: value
+                    .lcd_url
+                    .into_iter()
+                    .map(|address| Rest {
+                        address,
                         ..Default::default()
                     })
                     .collect(),
                 ..Default::default()
             },
-            slip44: self.network_info.coin_type,
+            slip44: value.network_info.coin_type,
             ..Default::default()
         }
     }
@@ -47,7 +63,7 @@ pub struct ChainInfo<'a> {
     pub gas_price: f64,
     /// gRPC urls, used to attempt connection
     pub grpc_urls: &'a [&'a str],
-    /// Optional urls for custom functionality
+    /// REST (LCD) url, used when connecting with [`Transport::Rest`](super::Transport::Rest)
     pub lcd_url: Option<&'a str>,
     /// Optional urls for custom functionality
     pub fcd_url: Option<&'a str>,
@@ -57,6 +73,85 @@ pub struct ChainInfo<'a> {
     pub kind: ChainKind,
 }
 
+/// Owned version of [`ChainInfo`], used to define networks at runtime or in a networks file.
+///
+/// ## Example
+/// ```toml
+/// chain_id = "devnet-1"
+/// gas_denom = "ustake"
+/// gas_price = 0.025
+/// grpc_urls = ["http://localhost:9090"]
+/// kind = "local"
+///
+/// [network_info]
+/// id = "devnet"
+/// pub_address_prefix = "wasm"
+/// coin_type = 118
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ChainInfoOwned {
+    /// Identifier for the network ex. columbus-2
+    pub chain_id: String,
+    /// Denom used to pay the gas
+    pub gas_denom: String,
+    /// gas price
+    pub gas_price: f64,
+    /// gRPC urls, used to attempt connection
+    pub grpc_urls: Vec<String>,
+    /// REST (LCD) url, used when connecting with [`Transport::Rest`](super::Transport::Rest)
+    #[serde(default)]
+    pub lcd_url: Option<String>,
+    /// Optional urls for custom functionality
+    #[serde(default)]
+    pub fcd_url: Option<String>,
+    /// Underlying network details (coin type, address prefix, etc)
+    pub network_info: NetworkInfoOwned,
+    /// Chain kind, (local, testnet, mainnet)
+    pub kind: ChainKind,
+}
+
+impl From<ChainInfo<'_>> for ChainInfoOwned {
+    fn from(value: ChainInfo<'_>) -> Self {
+        ChainInfoOwned {
+            chain_id: value.chain_id.to_string(),
+            gas_denom: value.gas_denom.to_string(),
+            gas_price: value.gas_price,
+            grpc_urls: value.grpc_urls.iter().map(|url| url.to_string()).collect(),
+            lcd_url: value.lcd_url.map(ToString::to_string),
+            fcd_url: value.fcd_url.map(ToString::to_string),
+            network_info: value.network_info.into(),
+            kind: value.kind,
+        }
+    }
+}
+
+/// Owned version of [`NetworkInfo`]
+#[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct NetworkInfoOwned {
+    /// network identifier (ex. juno, terra, osmosis, etc)
+    pub id: String,
+    /// address prefix
+    pub pub_address_prefix: String,
+    /// coin type for key derivation
+    #[serde(default = "default_coin_type")]
+    pub coin_type: u32,
+}
+
+impl From<NetworkInfo<'_>> for NetworkInfoOwned {
+    fn from(value: NetworkInfo<'_>) -> Self {
+        NetworkInfoOwned {
+            id: value.id.to_string(),
+            pub_address_prefix: value.pub_address_prefix.to_string(),
+            coin_type: value.coin_type,
+        }
+    }
+}
+
+/// Coin type of the cosmos hub, used by most chains
+fn default_coin_type() -> u32 {
+    118
+}
+
 /// Information about the underlying network, used for key derivation
 #[derive(Clone, Debug, Serialize, Default)]
 pub struct NetworkInfo<'a> {
@@ -72,10 +167,13 @@ pub struct NetworkInfo<'a> {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ChainKind {
     /// A local chain, used for development
+    #[serde(alias = "local")]
     Local,
     /// A mainnet chain
+    #[serde(alias = "mainnet")]
     Mainnet,
     /// A testnet chain
+    #[serde(alias = "testnet")]
     Testnet,
 }
 
@@ -1,93 +1,571 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
 use cosmrs::proto::cosmos::base::tendermint::v1beta1::{
     service_client::ServiceClient, GetNodeInfoRequest,
 };
 use ibc_chain_registry::chain::Grpc;
 use ibc_relayer_types::core::ics24_host::identifier::ChainId;
-use tonic::transport::{Channel, ClientTlsConfig};
+use tonic::{
+    body::BoxBody,
+    codegen::{
+        http::{self, HeaderMap, HeaderName, HeaderValue},
+        Body as _, BoxFuture, Bytes, StdError,
+    },
+    metadata::MetadataMap,
+    transport::{Body, Certificate, Channel, ClientTlsConfig, Endpoint},
+    Code, Request, Status,
+};
+use tower::{Service, ServiceExt};
+
+use super::{error::DaemonError, rest::RestGateway};
+
+/// Broadcasting is the only call that isn't safe to send twice
+const BROADCAST_TX_PATH: &str = "/cosmos.tx.v1beta1.Service/BroadcastTx";
+
+/// Policy used to select gRPC endpoints and to retry queries on them.
+#[derive(Clone, Debug)]
+pub struct GrpcPolicy {
+    /// Maximum time an endpoint can take to answer the health check before it's considered unhealthy
+    pub health_check_timeout: Duration,
+    /// How many times an idempotent call is retried when it fails because of the connection
+    pub max_retries: u32,
+    /// Wait time before the first retry, doubled on every next retry
+    pub initial_backoff: Duration,
+    /// Maximum wait time between two retries
+    pub max_backoff: Duration,
+}
+
+impl Default for GrpcPolicy {
+    fn default() -> Self {
+        Self {
+            health_check_timeout: Duration::from_secs(10),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+impl GrpcPolicy {
+    /// Wait time before retry number `attempt` (starting at 0)
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+/// Protocol used to talk to the nodes of a chain
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Transport {
+    /// Connect to the gRPC endpoints of the chain
+    #[default]
+    Grpc,
+    /// Connect to the REST (LCD) endpoints of the chain.
+    /// gRPC calls are bridged to the Cosmos REST gateway, see [`RestChannel`](super::rest::RestChannel).
+    Rest,
+}
+
+/// Options used to connect to the nodes of a chain
+#[derive(Clone, Debug, Default)]
+pub struct ChannelOptions {
+    /// Protocol used to talk to the nodes
+    pub transport: Transport,
+    /// Endpoint selection and retry policy
+    pub policy: GrpcPolicy,
+    /// Connection settings applied to every gRPC endpoint
+    pub grpc_config: GrpcEndpointConfig,
+    /// Connection settings for specific gRPC endpoints, by address. Replaces `grpc_config` for that endpoint.
+    pub grpc_endpoint_configs: HashMap<String, GrpcEndpointConfig>,
+}
+
+impl ChannelOptions {
+    /// Connection settings of the gRPC endpoint at `address`
+    pub fn grpc_config_for(&self, address: &str) -> &GrpcEndpointConfig {
+        self.grpc_endpoint_configs
+            .get(address)
+            .unwrap_or(&self.grpc_config)
+    }
+}
+
+/// Connection settings of a gRPC endpoint.
+/// Applied to the tx service and to every querier that uses the daemon's channel.
+///
+/// ## Example
+/// ```
+/// use cw_orch::daemon::GrpcEndpointConfig;
+/// use std::time::Duration;
+///
+/// let config = GrpcEndpointConfig {
+///     timeout: Some(Duration::from_secs(30)),
+///     concurrency_limit: Some(8),
+///     ..Default::default()
+/// }
+/// .header("x-api-key", "my-secret-key");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GrpcEndpointConfig {
+    /// Metadata added to every request, like the API key of a node provider
+    pub headers: Vec<(String, String)>,
+    /// Whether to connect with TLS.
+    /// If `None`, TLS is used when a CA certificate or domain is set, or when the plain connection fails on an "https" or port 443 url.
+    pub tls: Option<bool>,
+    /// PEM encoded CA certificate to verify the endpoint with, instead of the system's root certificates
+    pub ca_certificate: Option<Vec<u8>>,
+    /// Domain to verify the endpoint's certificate against, defaults to the host of the url
+    pub tls_domain: Option<String>,
+    /// Timeout of each request
+    pub timeout: Option<Duration>,
+    /// Timeout for establishing the connection
+    pub connect_timeout: Option<Duration>,
+    /// Maximum amount of requests that are sent to the endpoint concurrently
+    pub concurrency_limit: Option<usize>,
+}
+
+impl GrpcEndpointConfig {
+    /// Add metadata that is sent with every request
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Whether TLS is requested explicitly, instead of guessed from the url
+    fn requires_tls(&self) -> bool {
+        self.tls
+            .unwrap_or(self.ca_certificate.is_some() || self.tls_domain.is_some())
+    }
+
+    fn header_map(&self) -> Result<HeaderMap, DaemonError> {
+        self.headers
+            .iter()
+            .map(|(name, value)| {
+                let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+                    DaemonError::InvalidGrpcConfig(format!("header name {name}: {e}"))
+                })?;
+                let value = HeaderValue::from_str(value).map_err(|e| {
+                    DaemonError::InvalidGrpcConfig(format!("header value of {name}: {e}"))
+                })?;
+                Ok((name, value))
+            })
+            .collect()
+    }
+
+    fn endpoint(&self, address: &str) -> Result<Endpoint, DaemonError> {
+        let mut endpoint = Endpoint::from_shared(address.to_string())?;
+        if let Some(timeout) = self.timeout {
+            endpoint = endpoint.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            endpoint = endpoint.connect_timeout(timeout);
+        }
+        if let Some(limit) = self.concurrency_limit {
+            endpoint = endpoint.concurrency_limit(limit);
+        }
+        Ok(endpoint)
+    }
 
-use super::error::DaemonError;
+    fn tls_config(&self) -> ClientTlsConfig {
+        let mut tls = ClientTlsConfig::new();
+        if let Some(ca_certificate) = &self.ca_certificate {
+            tls = tls.ca_certificate(Certificate::from_pem(ca_certificate));
+        }
+        if let Some(domain) = &self.tls_domain {
+            tls = tls.domain_name(domain);
+        }
+        tls
+    }
+}
 
 /// A helper for constructing a gRPC channel
 pub struct GrpcChannel {}
 
 impl GrpcChannel {
     /// Connect to any of the provided gRPC endpoints
-    pub async fn connect(grpc: &[Grpc], chain_id: &ChainId) -> Result<Channel, DaemonError> {
-        let mut successful_connections = vec![];
+    pub async fn connect(grpc: &[Grpc], chain_id: &ChainId) -> Result<DaemonChannel, DaemonError> {
+        Self::connect_with_policy(grpc, chain_id, GrpcPolicy::default())
+            .await
+            .map(|endpoints| endpoints.channel())
+    }
+
+    /// Health-check all the provided gRPC endpoints and rank the healthy ones by latency.
+    /// The channels of the returned [`GrpcEndpoints`] send their calls to the fastest endpoint and fail over to the next one on connection errors.
+    pub async fn connect_with_policy(
+        grpc: &[Grpc],
+        chain_id: &ChainId,
+        policy: GrpcPolicy,
+    ) -> Result<GrpcEndpoints, DaemonError> {
+        let options = ChannelOptions {
+            policy,
+            ..Default::default()
+        };
+        Self::connect_with_options(grpc, chain_id, &options).await
+    }
+
+    /// Same as [`GrpcChannel::connect_with_policy`], applying the connection settings of the options to every endpoint.
+    pub async fn connect_with_options(
+        grpc: &[Grpc],
+        chain_id: &ChainId,
+        options: &ChannelOptions,
+    ) -> Result<GrpcEndpoints, DaemonError> {
+        let policy = &options.policy;
+        let mut healthy = vec![];
 
         for Grpc { address, .. } in grpc.iter() {
             log::info!("Trying to connect to endpoint: {}", address);
 
-            // get grpc endpoint
-            let endpoint = Channel::builder(address.clone().try_into().unwrap());
+            let config = options.grpc_config_for(address);
+            let headers = config.header_map()?;
 
-            // try to connect to grpc endpoint
-            let maybe_client = ServiceClient::connect(endpoint.clone()).await;
+            let start = Instant::now();
+            let check = tokio::time::timeout(
+                policy.health_check_timeout,
+                Self::health_check(address, chain_id, config, &headers),
+            )
+            .await;
 
-            // connection succeeded
-            let mut client = if maybe_client.is_ok() {
-                maybe_client?
-            } else {
-                log::warn!(
-                    "Cannot connect to gRPC endpoint: {}, {:?}",
+            match check {
+                Ok(Some(channel)) => healthy.push(RankedEndpoint {
+                    address: address.clone(),
+                    latency: start.elapsed(),
+                    transport: EndpointTransport::Grpc(channel),
+                    headers,
+                }),
+                Ok(None) => continue,
+                Err(_) => log::warn!(
+                    "gRPC endpoint {} did not respond within {:?}",
                     address,
-                    maybe_client.unwrap_err()
-                );
+                    policy.health_check_timeout
+                ),
+            }
+        }
 
-                // try HTTPS approach
-                // https://github.com/hyperium/tonic/issues/363#issuecomment-638545965
-                if !(address.contains("https") || address.contains("443")) {
-                    continue;
-                };
+        // we could not get any succesful connections
+        if healthy.is_empty() {
+            return Err(DaemonError::CannotConnectGRPC);
+        }
 
-                log::info!("Attempting to connect with TLS");
+        healthy.sort_by_key(|endpoint| endpoint.latency);
+        log::info!(
+            "Connected to gRPC endpoint {} ({:?})",
+            healthy[0].address,
+            healthy[0].latency
+        );
 
-                // re attempt to connect
-                let endpoint = endpoint.clone().tls_config(ClientTlsConfig::new())?;
-                let maybe_client = ServiceClient::connect(endpoint.clone()).await;
+        Ok(GrpcEndpoints::new(healthy, policy.clone()))
+    }
 
-                // connection still fails
-                if maybe_client.is_err() {
-                    log::warn!(
-                        "Cannot connect to gRPC endpoint: {}, {:?}",
-                        address,
-                        maybe_client.unwrap_err()
-                    );
-                    continue;
-                };
+    /// Returns the channel to the node at `address` if it is reachable and serves the expected chain.
+    async fn health_check(
+        address: &str,
+        chain_id: &ChainId,
+        config: &GrpcEndpointConfig,
+        headers: &HeaderMap,
+    ) -> Option<Channel> {
+        // get grpc endpoint
+        let endpoint = match config.endpoint(address) {
+            Ok(endpoint) => endpoint,
+            Err(e) => {
+                log::warn!("Invalid gRPC endpoint: {}, {:?}", address, e);
+                return None;
+            }
+        };
 
-                maybe_client?
-            };
+        // try to connect to grpc endpoint
+        let channel = if config.requires_tls() {
+            let endpoint = endpoint.tls_config(config.tls_config()).ok()?;
+            Self::try_connect(address, &endpoint).await?
+        } else {
+            match Self::try_connect(address, &endpoint).await {
+                Some(channel) => channel,
+                None => {
+                    // try HTTPS approach
+                    // https://github.com/hyperium/tonic/issues/363#issuecomment-638545965
+                    if config.tls == Some(false)
+                        || !(address.contains("https") || address.contains("443"))
+                    {
+                        return None;
+                    };
 
-            // get client information for verification down below
-            let node_info = client
-                .get_node_info(GetNodeInfoRequest {})
-                .await?
-                .into_inner();
-
-            // local juno does not return a proper ChainId with epoch format
-            if ChainId::is_epoch_format(&node_info.default_node_info.as_ref().unwrap().network) {
-                // verify we are connected to the spected network
-                if node_info.default_node_info.as_ref().unwrap().network != chain_id.as_str() {
-                    log::error!(
-                        "Network mismatch: connection:{} != config:{}",
-                        node_info.default_node_info.as_ref().unwrap().network,
-                        chain_id.as_str()
-                    );
-                    continue;
+                    log::info!("Attempting to connect with TLS");
+
+                    // re attempt to connect
+                    let endpoint = endpoint.tls_config(config.tls_config()).ok()?;
+                    Self::try_connect(address, &endpoint).await?
                 }
             }
+        };
+
+        let headers = headers.clone();
+        let mut client =
+            ServiceClient::with_interceptor(channel.clone(), move |mut req: Request<()>| {
+                add_metadata(&mut req, &headers);
+                Ok(req)
+            });
+
+        // get client information for verification down below
+        let node_info = match client.get_node_info(GetNodeInfoRequest {}).await {
+            Ok(resp) => resp.into_inner(),
+            Err(e) => {
+                log::warn!("Cannot query node info of {}, {:?}", address, e);
+                return None;
+            }
+        };
+        let network = node_info.default_node_info?.network;
 
-            // add endpoint to succesful connections
-            successful_connections.push(endpoint.connect().await?)
+        // local juno does not return a proper ChainId with epoch format
+        // verify we are connected to the spected network
+        if ChainId::is_epoch_format(&network) && network != chain_id.as_str() {
+            log::error!(
+                "Network mismatch: connection:{} != config:{}",
+                network,
+                chain_id.as_str()
+            );
+            return None;
         }
 
-        // we could not get any succesful connections
-        if successful_connections.is_empty() {
-            return Err(DaemonError::CannotConnectGRPC);
+        Some(channel)
+    }
+
+    async fn try_connect(address: &str, endpoint: &Endpoint) -> Option<Channel> {
+        match endpoint.connect().await {
+            Ok(channel) => Some(channel),
+            Err(e) => {
+                log::warn!("Cannot connect to gRPC endpoint: {}, {:?}", address, e);
+                None
+            }
         }
+    }
+}
+
+fn add_metadata(req: &mut Request<()>, headers: &HeaderMap) {
+    let mut all = std::mem::replace(req.metadata_mut(), MetadataMap::new()).into_headers();
+    all.extend(headers.clone());
+    *req.metadata_mut() = MetadataMap::from_headers(all);
+}
 
-        Ok(successful_connections.pop().unwrap())
+/// A healthy gRPC endpoint
+#[derive(Clone, Debug)]
+pub struct RankedEndpoint {
+    /// Address of the endpoint
+    pub address: String,
+    /// Time it took to answer the health check
+    pub latency: Duration,
+    /// How calls reach the endpoint
+    pub(crate) transport: EndpointTransport,
+    /// Metadata added to the requests to this endpoint
+    pub(crate) headers: HeaderMap,
+}
+
+/// How calls reach an endpoint
+#[derive(Clone, Debug)]
+pub(crate) enum EndpointTransport {
+    /// gRPC connection to the node
+    Grpc(Channel),
+    /// Calls are served from the node's REST gateway
+    Rest(Arc<RestGateway>),
+}
+
+/// Healthy gRPC endpoints of a chain, ranked by latency.
+///
+/// The [`DaemonChannel`]s returned by [`GrpcEndpoints::channel`] send their calls to the active endpoint
+/// and fail over to the next endpoint when a call fails because of the connection.
+#[derive(Clone, Debug)]
+pub struct GrpcEndpoints(Arc<EndpointsInner>);
+
+#[derive(Debug)]
+struct EndpointsInner {
+    ranked: Vec<RankedEndpoint>,
+    active: AtomicUsize,
+    policy: GrpcPolicy,
+}
+
+impl GrpcEndpoints {
+    pub(crate) fn new(ranked: Vec<RankedEndpoint>, policy: GrpcPolicy) -> Self {
+        Self(Arc::new(EndpointsInner {
+            ranked,
+            active: AtomicUsize::new(0),
+            policy,
+        }))
+    }
+
+    /// Channel to the active endpoint
+    pub fn channel(&self) -> DaemonChannel {
+        DaemonChannel(self.clone())
+    }
+
+    /// Healthy endpoints, fastest first
+    pub fn ranked(&self) -> &[RankedEndpoint] {
+        &self.0.ranked
+    }
+
+    /// The endpoint requests are currently sent to
+    pub fn active(&self) -> &RankedEndpoint {
+        &self.0.ranked[self.0.active.load(Ordering::SeqCst)]
+    }
+
+    /// The policy used for retries
+    pub fn policy(&self) -> &GrpcPolicy {
+        &self.0.policy
+    }
+
+    /// Switch from the `failed` endpoint to the next one in the ranking.
+    /// Does nothing if another request already switched away from `failed`.
+    fn failover(&self, failed: usize) {
+        let inner = &self.0;
+        if inner.ranked.len() < 2 {
+            return;
+        }
+        let next = (failed + 1) % inner.ranked.len();
+        if inner
+            .active
+            .compare_exchange(failed, next, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            log::warn!(
+                "Failing over from endpoint {} to {}",
+                inner.ranked[failed].address,
+                inner.ranked[next].address
+            );
+        }
+    }
+
+    /// Send the call to the active endpoint.
+    /// Idempotent calls that fail because of the connection are retried with backoff, every failure makes the channel fail over to the next endpoint.
+    async fn send(&self, req: http::Request<BoxBody>) -> Result<http::Response<BoxBody>, StdError> {
+        let retry = req.uri().path() != BROADCAST_TX_PATH;
+        let (parts, mut body) = req.into_parts();
+        // unary calls are small, buffering them allows sending them again
+        let mut buffered = Vec::new();
+        while let Some(chunk) = body.data().await {
+            buffered.extend_from_slice(&chunk?);
+        }
+        let body = Bytes::from(buffered);
+
+        let mut attempt = 0;
+        loop {
+            let active = self.0.active.load(Ordering::SeqCst);
+            let endpoint = &self.0.ranked[active];
+
+            let mut request = http::Request::new(
+                Body::from(body.clone())
+                    .map_err(|e| Status::from_error(Box::new(e)))
+                    .boxed_unsync(),
+            );
+            *request.method_mut() = parts.method.clone();
+            *request.uri_mut() = parts.uri.clone();
+            *request.version_mut() = parts.version;
+            *request.headers_mut() = parts.headers.clone();
+            request.headers_mut().extend(endpoint.headers.clone());
+
+            let result = match &endpoint.transport {
+                EndpointTransport::Grpc(channel) => {
+                    let mut channel = channel.clone();
+                    match channel.ready().await {
+                        Ok(channel) => channel.call(request).await.map(|response| {
+                            response.map(|body| {
+                                body.map_err(|e| Status::from_error(Box::new(e)))
+                                    .boxed_unsync()
+                            })
+                        }),
+                        Err(e) => Err(e),
+                    }
+                }
+                EndpointTransport::Rest(gateway) => Ok(gateway.serve(request).await),
+            };
+            let error = match &result {
+                Ok(response) => unavailable_status(response),
+                Err(e) => Some(Status::unavailable(e.to_string())),
+            };
+            let Some(error) = error else {
+                return Ok(result?);
+            };
+
+            self.failover(active);
+            if !retry || attempt >= self.0.policy.max_retries {
+                return Err(Box::new(error));
+            }
+            let backoff = self.0.policy.backoff(attempt);
+            log::warn!(
+                "Call {} to endpoint {} failed: {}, retrying in {:?}",
+                parts.uri.path(),
+                endpoint.address,
+                error.message(),
+                backoff
+            );
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Status of a response that was rejected because the node is unavailable
+fn unavailable_status<B>(response: &http::Response<B>) -> Option<Status> {
+    let code = response
+        .headers()
+        .get("grpc-status")
+        .and_then(|code| code.to_str().ok()?.parse::<i32>().ok())
+        .map(Code::from_i32)?;
+    matches!(code, Code::Unavailable | Code::DeadlineExceeded).then(|| {
+        let message = response
+            .headers()
+            .get("grpc-message")
+            .and_then(|message| message.to_str().ok())
+            .unwrap_or_default();
+        Status::new(code, message)
+    })
+}
+
+/// Channel to the nodes of a chain, used by the queriers and to send txs.
+///
+/// Calls go to the active endpoint of its [`GrpcEndpoints`].
+/// Calls that fail because of the connection are retried on the next endpoint, as configured by the [`GrpcPolicy`].
+/// Broadcasts are never sent twice, but the next call goes to the next endpoint.
+///
+/// An existing tonic [`Channel`] can be wrapped with `DaemonChannel::from(channel)`.
+#[derive(Clone, Debug)]
+pub struct DaemonChannel(GrpcEndpoints);
+
+impl DaemonChannel {
+    /// Endpoints the channel sends its calls to
+    pub fn endpoints(&self) -> &GrpcEndpoints {
+        &self.0
+    }
+}
+
+/// Channel to a single endpoint, without failover
+impl From<Channel> for DaemonChannel {
+    fn from(channel: Channel) -> Self {
+        let endpoint = RankedEndpoint {
+            address: String::new(),
+            latency: Duration::ZERO,
+            transport: EndpointTransport::Grpc(channel),
+            headers: HeaderMap::new(),
+        };
+        GrpcEndpoints::new(vec![endpoint], GrpcPolicy::default()).channel()
+    }
+}
+
+impl Service<http::Request<BoxBody>> for DaemonChannel {
+    type Response = http::Response<BoxBody>;
+    type Error = StdError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // readiness of the endpoint is awaited per call, as the active endpoint can change in between
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<BoxBody>) -> Self::Future {
+        let endpoints = self.0.clone();
+        Box::pin(async move { endpoints.send(req).await })
     }
 }
 
@@ -97,8 +575,37 @@ mod tests {
         This test asserts breaking issues around the GRPC connection
     */
 
-    use crate::prelude::DaemonAsync;
+    use super::{ChannelOptions, GrpcChannel, GrpcEndpointConfig, GrpcPolicy};
+    use crate::{
+        daemon::{
+            error::DaemonError,
+            queriers::{DaemonQuerier, Node},
+        },
+        prelude::DaemonAsync,
+    };
+    use cosmrs::proto::cosmos::base::tendermint::v1beta1::{
+        GetNodeInfoRequest, GetNodeInfoResponse,
+    };
+    use ibc_chain_registry::chain::Grpc;
+    use ibc_relayer_types::core::ics24_host::identifier::ChainId;
     use speculoos::prelude::*;
+    use std::{
+        convert::Infallible,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+        time::Duration,
+    };
+    use tonic::{
+        body::BoxBody,
+        codec::ProstCodec,
+        codegen::{empty_body, http, BoxFuture, Service},
+        server::{Grpc as GrpcServer, NamedService, UnaryService},
+        transport::{Body, Server},
+        Status,
+    };
 
     #[tokio::test]
     async fn no_connection() {
@@ -135,4 +642,252 @@ mod tests {
             .that(&build_res.err().unwrap().to_string())
             .is_equal_to(String::from("The list of grpc endpoints is empty"))
     }
+
+    /*
+        Failover tests against in-process gRPC nodes that only serve `GetNodeInfo`
+    */
+
+    const GET_NODE_INFO_PATH: &str = "/cosmos.base.tendermint.v1beta1.Service/GetNodeInfo";
+    const API_KEY_HEADER: &str = "x-api-key";
+
+    #[derive(Clone)]
+    struct StandInNode {
+        network: String,
+        delay: Duration,
+        control: Arc<NodeControl>,
+        api_key: Option<&'static str>,
+    }
+
+    /// Switches a stand-in node off and counts the calls it served
+    #[derive(Default)]
+    struct NodeControl {
+        down: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    impl NamedService for StandInNode {
+        const NAME: &'static str = "cosmos.base.tendermint.v1beta1.Service";
+    }
+
+    impl Service<http::Request<Body>> for StandInNode {
+        type Response = http::Response<BoxBody>;
+        type Error = Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: http::Request<Body>) -> Self::Future {
+            let node_info = NodeInfoService(self.clone());
+            Box::pin(async move {
+                if req.uri().path() != GET_NODE_INFO_PATH {
+                    // unimplemented
+                    return Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap());
+                }
+                if let Some(api_key) = node_info.0.api_key {
+                    if req.headers().get(API_KEY_HEADER).map(|key| key.as_bytes())
+                        != Some(api_key.as_bytes())
+                    {
+                        return Ok(Status::unauthenticated("missing api key").to_http());
+                    }
+                }
+                let mut grpc = GrpcServer::new(ProstCodec::default());
+                Ok(grpc.unary(node_info, req).await)
+            })
+        }
+    }
+
+    struct NodeInfoService(StandInNode);
+
+    impl UnaryService<GetNodeInfoRequest> for NodeInfoService {
+        type Response = GetNodeInfoResponse;
+        type Future = BoxFuture<tonic::Response<Self::Response>, Status>;
+
+        fn call(&mut self, _request: tonic::Request<GetNodeInfoRequest>) -> Self::Future {
+            let node = self.0.clone();
+            Box::pin(async move {
+                tokio::time::sleep(node.delay).await;
+                node.control.calls.fetch_add(1, Ordering::SeqCst);
+                if node.control.down.load(Ordering::SeqCst) {
+                    return Err(Status::unavailable("node is down"));
+                }
+                let mut response = GetNodeInfoResponse {
+                    default_node_info: Some(Default::default()),
+                    ..Default::default()
+                };
+                if let Some(info) = response.default_node_info.as_mut() {
+                    info.network = node.network;
+                }
+                Ok(tonic::Response::new(response))
+            })
+        }
+    }
+
+    /// Serves a stand-in node on a free local port, returns its address and control
+    async fn spawn_node(network: &str, delay: Duration) -> (Grpc, Arc<NodeControl>) {
+        spawn_node_with_api_key(network, delay, None).await
+    }
+
+    /// Serves a stand-in node that rejects requests without the `api_key` header
+    async fn spawn_node_with_api_key(
+        network: &str,
+        delay: Duration,
+        api_key: Option<&'static str>,
+    ) -> (Grpc, Arc<NodeControl>) {
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let control = Arc::new(NodeControl::default());
+        let node = StandInNode {
+            network: network.to_string(),
+            delay,
+            control: control.clone(),
+            api_key,
+        };
+        tokio::spawn(Server::builder().add_service(node).serve(addr));
+        // give the server some time to bind
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let grpc = Grpc {
+            address: format!("http://{addr}"),
+            provider: None,
+        };
+        (grpc, control)
+    }
+
+    fn chain_id() -> ChainId {
+        ChainId::new("standin".to_string(), 1)
+    }
+
+    #[tokio::test]
+    async fn ranks_endpoints_by_latency() {
+        let (slow, _) = spawn_node("standin-1", Duration::from_millis(300)).await;
+        let (fast, _) = spawn_node("standin-1", Duration::ZERO).await;
+        let (other_chain, _) = spawn_node("other-1", Duration::ZERO).await;
+        let unreachable = Grpc {
+            address: "http://127.0.0.1:1".to_string(),
+            provider: None,
+        };
+
+        let endpoints = GrpcChannel::connect_with_policy(
+            &[slow.clone(), other_chain, unreachable, fast.clone()],
+            &chain_id(),
+            GrpcPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        let ranked: Vec<_> = endpoints
+            .ranked()
+            .iter()
+            .map(|endpoint| endpoint.address.clone())
+            .collect();
+        asserting!("only healthy endpoints of the chain are kept, fastest first")
+            .that(&ranked)
+            .is_equal_to(vec![fast.address.clone(), slow.address]);
+        assert_eq!(endpoints.active().address, fast.address);
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_next_endpoint() {
+        let (first, first_control) = spawn_node("standin-1", Duration::ZERO).await;
+        let (second, _) = spawn_node("standin-1", Duration::from_millis(100)).await;
+
+        let policy = GrpcPolicy {
+            initial_backoff: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let endpoints =
+            GrpcChannel::connect_with_policy(&[first.clone(), second.clone()], &chain_id(), policy)
+                .await
+                .unwrap();
+        assert_eq!(endpoints.active().address, first.address);
+
+        // first node goes down
+        first_control.down.store(true, Ordering::SeqCst);
+
+        // a plain querier call is retried on the next endpoint
+        let info = Node::new(endpoints.channel()).info().await.unwrap();
+
+        assert_eq!(info.default_node_info.unwrap().network, "standin-1");
+        assert_eq!(endpoints.active().address, second.address);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (node, control) = spawn_node("standin-1", Duration::ZERO).await;
+
+        let policy = GrpcPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let endpoints = GrpcChannel::connect_with_policy(&[node], &chain_id(), policy)
+            .await
+            .unwrap();
+        control.down.store(true, Ordering::SeqCst);
+        let health_checks = control.calls.load(Ordering::SeqCst);
+
+        let res = Node::new(endpoints.channel()).info().await;
+
+        assert!(res.unwrap_err().is_transport_error());
+        assert_eq!(control.calls.load(Ordering::SeqCst) - health_checks, 3);
+    }
+
+    #[tokio::test]
+    async fn sends_configured_headers() {
+        let (node, _) = spawn_node_with_api_key("standin-1", Duration::ZERO, Some("secret")).await;
+
+        // without the header the node can't be health-checked
+        let res =
+            GrpcChannel::connect_with_policy(&[node.clone()], &chain_id(), GrpcPolicy::default())
+                .await;
+        assert!(matches!(res, Err(DaemonError::CannotConnectGRPC)));
+
+        let mut options = ChannelOptions::default();
+        options.grpc_endpoint_configs.insert(
+            node.address.clone(),
+            GrpcEndpointConfig {
+                timeout: Some(Duration::from_secs(5)),
+                concurrency_limit: Some(4),
+                ..Default::default()
+            }
+            .header(API_KEY_HEADER, "secret"),
+        );
+        let endpoints = GrpcChannel::connect_with_options(&[node], &chain_id(), &options)
+            .await
+            .unwrap();
+
+        // queries through the daemon's channel carry the header as well
+        let info = Node::new(endpoints.channel()).info().await.unwrap();
+        assert_eq!(info.default_node_info.unwrap().network, "standin-1");
+    }
+
+    #[test]
+    fn invalid_header_is_rejected() {
+        let config = GrpcEndpointConfig::default().header("x-api-key", "line\nbreak");
+        assert!(matches!(
+            config.header_map(),
+            Err(DaemonError::InvalidGrpcConfig(_))
+        ));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let policy = GrpcPolicy {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(4));
+        assert_eq!(policy.backoff(10), Duration::from_secs(5));
+    }
 }
@@ -2,19 +2,25 @@ use super::{
     builder::DaemonAsyncBuilder,
     cosmos_modules,
     error::DaemonError,
+    pending_tx::PendingTx,
+    proto::cosmwasm as cosmwasm_proto,
     queriers::{DaemonQuerier, Node},
-    sender::Wallet,
+    sender::{encode_any, Wallet},
     state::DaemonState,
     tx_resp::CosmTxResponse,
+    DaemonChannel,
 };
 use crate::{
-    prelude::{queriers::CosmWasm, IndexResponse, Uploadable},
-    state::ChainState,
+    environment::ContractInfo,
+    error::CwOrchError,
+    prelude::{queriers::CosmWasm, AccessConfig, IndexResponse, Uploadable, WasmLimits, WasmPath},
+    state::{ChainState, DeploymentAction, DeploymentRecord, StateInterface},
 };
 use cosmrs::{
     cosmwasm::{MsgExecuteContract, MsgInstantiateContract, MsgMigrateContract},
     tendermint::Time,
-    AccountId, Denom,
+    tx::Msg,
+    AccountId, Any, Denom,
 };
 use cosmwasm_std::{Addr, Coin};
 use flate2::{write, Compression};
@@ -28,8 +34,6 @@ use std::{
     time::Duration,
 };
 
-use tonic::transport::Channel;
-
 #[derive(Clone)]
 /**
     Represents a blockchain node.
@@ -75,7 +79,7 @@ impl DaemonAsync {
     }
 
     /// Get the channel configured for this DaemonAsync.
-    pub fn channel(&self) -> Channel {
+    pub fn channel(&self) -> DaemonChannel {
         self.state().grpc_channel.clone()
     }
 }
@@ -150,9 +154,10 @@ impl DaemonAsync {
                 address: contract_address.to_string(),
                 query_data: serde_json::to_vec(&query_msg)?,
             })
-            .await?;
+            .await?
+            .into_inner();
 
-        Ok(from_str(from_utf8(&resp.into_inner().data).unwrap())?)
+        Ok(from_str(from_utf8(&resp.data).unwrap())?)
     }
 
     /// Migration a contract.
@@ -222,27 +227,31 @@ impl DaemonAsync {
         })
     }
 
-    /// Upload a contract to the chain.
+    /// Upload a contract to the chain, with the [`instantiate_permission`](Uploadable::instantiate_permission) of the contract.
     pub async fn upload(
         &self,
         uploadable: &impl Uploadable,
     ) -> Result<CosmTxResponse, DaemonError> {
-        let sender = &self.sender;
-        let wasm_path = uploadable.wasm();
-
-        log::debug!("Uploading file at {:?}", wasm_path);
+        self.upload_with_permission(uploadable, uploadable.instantiate_permission().as_ref())
+            .await
+    }
 
-        let file_contents = std::fs::read(wasm_path.path())?;
-        let mut e = write::GzEncoder::new(Vec::new(), Compression::default());
-        e.write_all(&file_contents)?;
-        let wasm_byte_code = e.finish()?;
-        let store_msg = cosmrs::cosmwasm::MsgStoreCode {
-            sender: sender.pub_addr()?,
-            wasm_byte_code,
-            instantiate_permission: None,
-        };
+    /// Upload a contract to the chain, only instantiable as allowed by `permission`.
+    /// `None` uses the default permission of the chain.
+    pub async fn upload_with_permission(
+        &self,
+        uploadable: &impl Uploadable,
+        permission: Option<&AccessConfig>,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        let sender = &self.sender;
+        let store_msg = store_code_msg(
+            sender.pub_addr_str()?,
+            &uploadable.wasm(),
+            permission,
+            &self.state.wasm_limits,
+        )?;
 
-        let result = sender.commit_tx(vec![store_msg], None).await?;
+        let result = sender.commit_tx_any(vec![store_msg], None).await?;
 
         log::info!("Uploaded: {:?}", result.txhash);
 
@@ -256,10 +265,238 @@ impl DaemonAsync {
         Ok(result)
     }
 
+    /// Change who can instantiate an uploaded code. Only the uploader of the code can do this.
+    pub async fn update_instantiate_config(
+        &self,
+        code_id: u64,
+        permission: &AccessConfig,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        let msg = cosmwasm_proto::MsgUpdateInstantiateConfig {
+            sender: self.sender.pub_addr_str()?,
+            code_id,
+            new_instantiate_permission: Some(permission.into()),
+        };
+        self.sender
+            .commit_tx_any(
+                vec![encode_any(
+                    cosmwasm_proto::MSG_UPDATE_INSTANTIATE_CONFIG_TYPE_URL,
+                    msg,
+                )],
+                None,
+            )
+            .await
+    }
+
+    /// Who can instantiate the code stored under the code id.
+    pub async fn instantiate_permission(&self, code_id: u64) -> Result<AccessConfig, DaemonError> {
+        self.query_client::<CosmWasm>()
+            .instantiate_permission(code_id)
+            .await
+    }
+
+    /// Hex encoded checksum of the code stored under the code id.
+    pub async fn code_checksum(&self, code_id: u64) -> Result<String, DaemonError> {
+        self.query_client::<CosmWasm>().code_id_hash(code_id).await
+    }
+
+    /// Info of the contract at the address.
+    pub async fn contract_info(&self, address: &Addr) -> Result<ContractInfo, DaemonError> {
+        let info = self
+            .query_client::<CosmWasm>()
+            .contract_info(address)
+            .await?;
+        Ok(ContractInfo {
+            code_id: info.code_id,
+            creator: Addr::unchecked(info.creator),
+            admin: (!info.admin.is_empty()).then(|| Addr::unchecked(info.admin)),
+        })
+    }
+
+    /// Most recent code id with the hex encoded checksum, optionally restricted to codes uploaded by `uploader`.
+    pub async fn find_code(
+        &self,
+        checksum: &str,
+        uploader: Option<&Addr>,
+    ) -> Result<Option<u64>, DaemonError> {
+        self.query_client::<CosmWasm>()
+            .code_id_by_checksum(checksum, uploader.map(Addr::as_str))
+            .await
+    }
+
+    /// Returns whether the checksum of the WASM file matches the checksum of the code id registered for the contract id.
+    pub async fn latest_is_uploaded(
+        &self,
+        contract_id: &str,
+        wasm: &WasmPath,
+    ) -> Result<bool, CwOrchError> {
+        let Ok(code_id) = self.state.get_code_id(contract_id) else {
+            return Ok(false);
+        };
+        let on_chain_hash = self.code_checksum(code_id).await?;
+        Ok(wasm.checksum()? == on_chain_hash)
+    }
+
+    /// Returns whether the contract runs the code id registered for the contract id.
+    pub async fn is_running_latest(&self, contract_id: &str) -> Result<bool, CwOrchError> {
+        let Ok(code_id) = self.state.get_code_id(contract_id) else {
+            return Ok(false);
+        };
+        let info = self
+            .contract_info(&self.state.get_address(contract_id)?)
+            .await?;
+        Ok(code_id == info.code_id)
+    }
+
+    /// Only upload the contract if its checksum does not match the code id registered for the contract id.
+    /// The code id of a new upload is registered for the contract id and the upload recorded in its history.
+    pub async fn upload_if_needed(
+        &self,
+        contract_id: &str,
+        uploadable: &impl Uploadable,
+    ) -> Result<Option<CosmTxResponse>, CwOrchError> {
+        let wasm = uploadable.wasm();
+        if self.latest_is_uploaded(contract_id, &wasm).await? {
+            return Ok(None);
+        }
+        let result = self.upload(uploadable).await?;
+        let code_id = result.uploaded_code_id()?;
+        let mut state = self.state();
+        state.try_set_code_id(contract_id, code_id)?;
+        state.try_record_deployment(
+            contract_id,
+            DeploymentRecord {
+                code_id: Some(code_id),
+                // older chains don't emit the checksum on upload
+                checksum: result.uploaded_checksum().or_else(|| wasm.checksum().ok()),
+                tx_hash: result.tx_hash(),
+                sender: Some(self.sender().to_string()),
+                ..DeploymentRecord::new(DeploymentAction::Upload)
+            },
+        )?;
+        Ok(Some(result))
+    }
+
+    /// Only migrate the contract if it doesn't run the code id registered for the contract id yet.
+    pub async fn migrate_if_needed<M: Serialize + Debug>(
+        &self,
+        contract_id: &str,
+        migrate_msg: &M,
+    ) -> Result<Option<CosmTxResponse>, CwOrchError> {
+        if self.is_running_latest(contract_id).await? {
+            log::info!("{} is already running the latest code", contract_id);
+            return Ok(None);
+        }
+        let code_id = self.state.get_code_id(contract_id)?;
+        let address = self.state.get_address(contract_id)?;
+        Ok(Some(self.migrate(migrate_msg, code_id, &address).await?))
+    }
+
+    /// Uploads the contract if needed, then migrates it if it doesn't run the latest code.
+    pub async fn upload_and_migrate_if_needed<M: Serialize + Debug>(
+        &self,
+        contract_id: &str,
+        uploadable: &impl Uploadable,
+        migrate_msg: &M,
+    ) -> Result<Option<Vec<CosmTxResponse>>, CwOrchError> {
+        let mut txs = Vec::with_capacity(2);
+
+        if let Some(tx) = self.upload_if_needed(contract_id, uploadable).await? {
+            txs.push(tx);
+        };
+
+        if let Some(tx) = self.migrate_if_needed(contract_id, migrate_msg).await? {
+            txs.push(tx);
+        };
+
+        if txs.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(txs))
+        }
+    }
+
     /// Set the sender to use with this DaemonAsync to be the given wallet
     pub fn set_sender(&mut self, sender: &Wallet) {
         self.sender = sender.clone();
     }
+
+    /// Broadcast a tx without waiting for it to be included in a block.
+    /// See [`Sender::submit_tx`](super::sender::Sender::submit_tx).
+    pub async fn submit_tx<T: Msg>(
+        &self,
+        msgs: Vec<T>,
+        memo: Option<&str>,
+    ) -> Result<PendingTx, DaemonError> {
+        self.sender.submit_tx(msgs, memo).await
+    }
+
+    /// Same as [`DaemonAsync::submit_tx`] for messages that are already encoded as [`Any`].
+    pub async fn submit_tx_any(
+        &self,
+        msgs: Vec<Any>,
+        memo: Option<&str>,
+    ) -> Result<PendingTx, DaemonError> {
+        self.sender.submit_tx_any(msgs, memo).await
+    }
+
+    /// Grant a basic fee allowance from the sender to the grantee.
+    pub async fn grant_basic_allowance(
+        &self,
+        grantee: &Addr,
+        spend_limit: Option<Vec<Coin>>,
+        expiration: Option<Time>,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        self.sender
+            .grant_basic_allowance(grantee.as_str(), spend_limit, expiration)
+            .await
+    }
+
+    /// Grant a periodic fee allowance from the sender to the grantee.
+    pub async fn grant_periodic_allowance(
+        &self,
+        grantee: &Addr,
+        spend_limit: Option<Vec<Coin>>,
+        expiration: Option<Time>,
+        period: Duration,
+        period_spend_limit: Vec<Coin>,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        self.sender
+            .grant_periodic_allowance(
+                grantee.as_str(),
+                spend_limit,
+                expiration,
+                period,
+                period_spend_limit,
+            )
+            .await
+    }
+
+    /// Revoke the fee allowance granted by the sender to the grantee.
+    pub async fn revoke_allowance(&self, grantee: &Addr) -> Result<CosmTxResponse, DaemonError> {
+        self.sender.revoke_allowance(grantee.as_str()).await
+    }
+}
+
+/// `MsgStoreCode` uploading the gzipped wasm file, once it is validated against the limits of the chain
+pub(crate) fn store_code_msg(
+    sender: String,
+    wasm_path: &WasmPath,
+    permission: Option<&AccessConfig>,
+    limits: &WasmLimits,
+) -> Result<Any, DaemonError> {
+    log::debug!("Uploading file at {:?}", wasm_path);
+    wasm_path.verify_checksum()?;
+    wasm_path.validate(limits)?;
+
+    let file_contents = std::fs::read(wasm_path.path())?;
+    let mut e = write::GzEncoder::new(Vec::new(), Compression::default());
+    e.write_all(&file_contents)?;
+    let msg = cosmwasm_proto::MsgStoreCode {
+        sender,
+        wasm_byte_code: e.finish()?,
+        instantiate_permission: permission.map(Into::into),
+    };
+    Ok(encode_any(cosmwasm_proto::MSG_STORE_CODE_TYPE_URL, msg))
 }
 
 pub(crate) fn parse_cw_coins(
@@ -275,3 +512,15 @@ pub(crate) fn parse_cw_coins(
         })
         .collect::<Result<Vec<_>, DaemonError>>()
 }
+
+pub(crate) fn parse_cw_coins_proto(
+    coins: &[cosmwasm_std::Coin],
+) -> Vec<cosmos_modules::base::Coin> {
+    coins
+        .iter()
+        .map(|coin| cosmos_modules::base::Coin {
+            amount: coin.amount.to_string(),
+            denom: coin.denom.clone(),
+        })
+        .collect()
+}
@@ -1,7 +1,11 @@
 #![allow(missing_docs)]
 
+use std::time::Duration;
+
 use thiserror::Error;
 
+use super::state_export::StateConflict;
+
 #[derive(Error, Debug)]
 pub enum DaemonError {
     #[error("Reqwest HTTP(s) Error")]
@@ -24,6 +28,13 @@ pub enum DaemonError {
     TransportError(#[from] ::tonic::transport::Error),
     #[error(transparent)]
     TendermintError(#[from] ::cosmrs::tendermint::Error),
+    #[error(transparent)]
+    JoinError(#[from] ::tokio::task::JoinError),
+    #[error(transparent)]
+    TomlError(#[from] ::toml::de::Error),
+    #[cfg(feature = "sqlite")]
+    #[error(transparent)]
+    Sqlite(#[from] ::rusqlite::Error),
     #[error("Bech32 Decode Error")]
     Bech32DecodeErr,
     #[error("Bech32 Decode Error: Key Failed prefix {0} or length {1} Wanted:{2}/{3}")]
@@ -96,6 +107,12 @@ pub enum DaemonError {
     TxFailed { code: usize, reason: String },
     #[error("The list of grpc endpoints is empty")]
     GRPCListIsEmpty,
+    #[error("Can not connect to any rest endpoint that was provided.")]
+    CannotConnectREST,
+    #[error("The list of rest endpoints is empty")]
+    RestListIsEmpty,
+    #[error("invalid gRPC endpoint configuration: {0}")]
+    InvalidGrpcConfig(String),
     #[error("no wasm path provided for contract.")]
     MissingWasmPath,
     #[error("daemon builder missing {0}")]
@@ -104,10 +121,61 @@ pub enum DaemonError {
     IbcError(String),
     #[error("insufficient fee, check gas price: {0}")]
     InsufficientFee(String),
+    #[error("tx fee of {fee}{denom} exceeds the max fee of {max_fee}{denom}")]
+    MaxFeeExceeded {
+        fee: u128,
+        max_fee: u128,
+        denom: String,
+    },
+    #[error("tx {hash} not confirmed within {timeout:?}")]
+    TxTimeout { hash: String, timeout: Duration },
+    #[error("stopped waiting for tx {0}")]
+    TxWaitCancelled(String),
+    #[error("network {0} not found")]
+    NetworkNotFound(String),
+    #[error("chain registry error: {0}")]
+    ChainRegistry(String),
+    #[error(transparent)]
+    StateFile(#[from] crate::state_file::StateFileError),
+    #[error(transparent)]
+    Artifact(#[from] crate::paths::ArtifactError),
+    #[error("invalid state entry {key}: {value}")]
+    InvalidStateEntry { key: String, value: String },
+    #[error("conflicting state entries: {}", join_conflicts(.0))]
+    StateConflict(Vec<StateConflict>),
+    #[error("the deployment of {from} has code ids, which don't exist on {to}")]
+    CrossChainImport { from: String, to: String },
+    #[error("{0} is reserved by the daemon state and can't be used as deployment id")]
+    ReservedDeploymentId(String),
+    #[error("proposal {proposal_id} did not pass, its status is {status}")]
+    ProposalNotPassed { proposal_id: u64, status: String },
+    #[error("proposal {proposal_id} did not pass within {timeout:?}")]
+    ProposalTimeout { proposal_id: u64, timeout: Duration },
+}
+
+fn join_conflicts(conflicts: &[StateConflict]) -> String {
+    conflicts
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl DaemonError {
     pub fn ibc_err(msg: impl ToString) -> Self {
         Self::IbcError(msg.to_string())
     }
+
+    /// Whether the error is caused by the connection to the node rather than by the request itself.
+    /// Requests that failed with such an error can safely be retried on another endpoint.
+    pub fn is_transport_error(&self) -> bool {
+        match self {
+            Self::TransportError(_) => true,
+            Self::Status(status) => matches!(
+                status.code(),
+                ::tonic::Code::Unavailable | ::tonic::Code::DeadlineExceeded
+            ),
+            _ => false,
+        }
+    }
 }
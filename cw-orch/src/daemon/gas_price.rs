@@ -0,0 +1,230 @@
+//! Gas price oracles that query the current gas price from on-chain fee markets.
+
+use super::{
+    error::DaemonError,
+    proto::{feemarket, osmosis},
+    queriers::{raw_query, DaemonQuerier, Node},
+    DaemonChannel,
+};
+
+/// Number of decimals of a `LegacyDec`
+const DEC_PRECISION: i32 = 18;
+
+/// Source of the gas price used to calculate the fee of a tx.
+///
+/// When the source has no price for the fee denom, or the query fails,
+/// the static gas price of the daemon configuration is used instead.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum GasPriceSource {
+    /// Use the static gas price of the daemon configuration
+    #[default]
+    Static,
+    /// Query the EIP-1559 base fee of the Osmosis `txfees` module.
+    /// Only applies when paying fees in the chain's base denom.
+    OsmosisTxFees,
+    /// Query the gas price of the `feemarket` module
+    FeeMarket,
+    /// Use the minimum gas price configured on the connected node
+    NodeMinGasPrice,
+}
+
+impl GasPriceSource {
+    /// Query the current gas price for `denom`.
+    /// Returns `None` if this source doesn't provide a price for the denom.
+    pub async fn gas_price(
+        &self,
+        channel: DaemonChannel,
+        denom: &str,
+    ) -> Result<Option<f64>, DaemonError> {
+        match self {
+            GasPriceSource::Static => Ok(None),
+            GasPriceSource::OsmosisTxFees => {
+                let base_denom: osmosis::QueryBaseDenomResponse = raw_query(
+                    channel.clone(),
+                    osmosis::BASE_DENOM_PATH,
+                    osmosis::QueryBaseDenomRequest {},
+                )
+                .await?;
+                if base_denom.base_denom != denom {
+                    return Ok(None);
+                }
+                let base_fee: osmosis::QueryEipBaseFeeResponse = raw_query(
+                    channel,
+                    osmosis::EIP_BASE_FEE_PATH,
+                    osmosis::QueryEipBaseFeeRequest {},
+                )
+                .await?;
+                Ok(parse_dec(&base_fee.base_fee))
+            }
+            GasPriceSource::FeeMarket => {
+                let resp: feemarket::GasPriceResponse = raw_query(
+                    channel,
+                    feemarket::GAS_PRICE_PATH,
+                    feemarket::GasPriceRequest {
+                        denom: denom.to_string(),
+                    },
+                )
+                .await?;
+                Ok(resp.price.and_then(|price| parse_dec(&price.amount)))
+            }
+            GasPriceSource::NodeMinGasPrice => {
+                let min_gas_prices = Node::new(channel).min_gas_prices().await?;
+                Ok(parse_min_gas_price(&min_gas_prices, denom))
+            }
+        }
+    }
+}
+
+/// Parses a `LegacyDec`.
+/// These are returned as integers with 18 decimals over gRPC but can also be formatted as a decimal.
+pub(crate) fn parse_dec(dec: &str) -> Option<f64> {
+    let dec = dec.trim();
+    if dec.contains('.') {
+        dec.parse().ok()
+    } else {
+        dec.parse::<u128>()
+            .ok()
+            .map(|atomics| atomics as f64 / 10f64.powi(DEC_PRECISION))
+    }
+}
+
+/// Finds the gas price of `denom` in a list of min gas prices, ex. `0.025ujuno,0.001uatom`
+pub(crate) fn parse_min_gas_price(min_gas_prices: &str, denom: &str) -> Option<f64> {
+    min_gas_prices.split(',').find_map(|coin| {
+        let coin = coin.trim();
+        let denom_start = coin.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        let (amount, coin_denom) = coin.split_at(denom_start);
+        if coin_denom == denom {
+            amount.parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::{
+        cosmos_modules::{
+            abci::GasInfo,
+            auth::QueryAccountRequest,
+            tx::{SimulateRequest, SimulateResponse},
+        },
+        stand_in::{account, StandInNode, FEE_DENOM},
+        SenderOptions, TxBuilder,
+    };
+
+    #[test]
+    fn parses_dec() {
+        assert_eq!(parse_dec("2500000000000000"), Some(0.0025));
+        assert_eq!(parse_dec("0.002500000000000000"), Some(0.0025));
+        assert_eq!(parse_dec("1000000000000000000"), Some(1.0));
+        assert_eq!(parse_dec(""), None);
+    }
+
+    #[test]
+    fn parses_min_gas_price() {
+        let min_gas_prices =
+            "0.025ujuno,0.001ibc/C4CFF46FD6DE35CA4CF4CE031E643C8FDC9BA4B99AE598E9B0ED98FE3A2319F9";
+        assert_eq!(parse_min_gas_price(min_gas_prices, "ujuno"), Some(0.025));
+        assert_eq!(
+            parse_min_gas_price(
+                min_gas_prices,
+                "ibc/C4CFF46FD6DE35CA4CF4CE031E643C8FDC9BA4B99AE598E9B0ED98FE3A2319F9"
+            ),
+            Some(0.001)
+        );
+        assert_eq!(parse_min_gas_price(min_gas_prices, "uatom"), None);
+        assert_eq!(parse_min_gas_price("", "ujuno"), None);
+    }
+
+    /// Node that serves the sender's account and simulates every tx at 100_000 gas
+    fn node() -> StandInNode {
+        StandInNode::new()
+            .unary(
+                "/cosmos.auth.v1beta1.Query/Account",
+                |request: QueryAccountRequest| Ok(account(request.address, 0)),
+            )
+            .unary(
+                "/cosmos.tx.v1beta1.Service/Simulate",
+                |_: SimulateRequest| {
+                    Ok(SimulateResponse {
+                        gas_info: Some(GasInfo {
+                            gas_wanted: 0,
+                            gas_used: 100_000,
+                        }),
+                        result: None,
+                    })
+                },
+            )
+    }
+
+    /// Fee amount of a tx built with the gas price of `source`
+    async fn fee_amount(node: StandInNode, source: GasPriceSource) -> u128 {
+        let sender = node
+            .sender(SenderOptions {
+                gas_price_source: source,
+                ..Default::default()
+            })
+            .await;
+        let body = TxBuilder::build_body_any(vec![], None, 0);
+        let raw = TxBuilder::new(body).build(&sender).await.unwrap();
+        let tx = cosmrs::Tx::from_bytes(&raw.to_bytes().unwrap()).unwrap();
+        tx.auth_info.fee.amount[0].amount
+    }
+
+    fn osmosis_node(base_denom: &'static str) -> StandInNode {
+        node()
+            .unary(
+                osmosis::BASE_DENOM_PATH,
+                move |_: osmosis::QueryBaseDenomRequest| {
+                    Ok(osmosis::QueryBaseDenomResponse {
+                        base_denom: base_denom.to_string(),
+                    })
+                },
+            )
+            .unary(
+                osmosis::EIP_BASE_FEE_PATH,
+                |_: osmosis::QueryEipBaseFeeRequest| {
+                    Ok(osmosis::QueryEipBaseFeeResponse {
+                        base_fee: "5000000000000000".to_string(),
+                    })
+                },
+            )
+    }
+
+    #[tokio::test]
+    async fn fee_uses_osmosis_base_fee() {
+        // 130_000 gas at 0.005
+        let fee = fee_amount(osmosis_node(FEE_DENOM), GasPriceSource::OsmosisTxFees).await;
+        assert_eq!(fee, 650);
+
+        // the base fee only applies to the base denom, the static 0.025 is used otherwise
+        let fee = fee_amount(osmosis_node("uosmo"), GasPriceSource::OsmosisTxFees).await;
+        assert_eq!(fee, 3250);
+    }
+
+    #[tokio::test]
+    async fn fee_uses_feemarket_gas_price() {
+        let node = node().unary(
+            feemarket::GAS_PRICE_PATH,
+            |request: feemarket::GasPriceRequest| {
+                Ok(feemarket::GasPriceResponse {
+                    price: Some(feemarket::DecCoin {
+                        denom: request.denom,
+                        amount: "0.010000000000000000".to_string(),
+                    }),
+                })
+            },
+        );
+        assert_eq!(fee_amount(node, GasPriceSource::FeeMarket).await, 1300);
+    }
+
+    #[tokio::test]
+    async fn fee_falls_back_to_static_gas_price() {
+        // the node doesn't serve the feemarket query
+        assert_eq!(fee_amount(node(), GasPriceSource::FeeMarket).await, 3250);
+        assert_eq!(fee_amount(node(), GasPriceSource::Static).await, 3250);
+    }
+}
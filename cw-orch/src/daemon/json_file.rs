@@ -1,48 +1,137 @@
-use serde_json::{from_reader, json, Value};
-use std::fs::{File, OpenOptions};
-
-pub fn write(filename: &String, chain_id: &String, network_id: &String, deploy_id: &String) {
-    // open file pointer set read/write permissions to true
-    // create it if it does not exists
-    // dont truncate it
-    let file = OpenOptions::new()
-        .create(true)
-        .read(true)
-        .write(true)
-        .truncate(false)
-        .open(filename)
-        .unwrap();
-
-    // return empty json object if file is empty
-    // return file content if not
-    let mut json: Value = if file.metadata().unwrap().len().eq(&0) {
-        json!({})
-    } else {
-        from_reader(file).unwrap()
-    };
-
-    // check and add network_id path if it's missing
-    if json.get(network_id).is_none() {
-        json[network_id] = json!({});
+use fs2::FileExt;
+use serde_json::{json, Value};
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use super::error::DaemonError;
+
+/// Distinguishes the temporary files of concurrent writes within a process
+static WRITE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Read the JSON file, an empty or missing file is read as an empty object.
+/// Files are replaced atomically by [`write`], so reading doesn't need the lock.
+pub fn read(filename: &Path) -> Result<Value, DaemonError> {
+    if !filename.exists() {
+        return Ok(json!({}));
     }
+    let content = fs::read(filename)?;
+    if content.is_empty() {
+        return Ok(json!({}));
+    }
+    Ok(serde_json::from_slice(&content)?)
+}
+
+/// Write the JSON value to the file, replacing its content.
+/// The value is written to a temporary file that is then renamed, so readers never see a partially written file.
+pub fn write(filename: &Path, json: &Value) -> Result<(), DaemonError> {
+    let tmp = sibling(
+        filename,
+        &format!(
+            "tmp-{}-{}",
+            process::id(),
+            WRITE_COUNTER.fetch_add(1, Ordering::SeqCst)
+        ),
+    );
 
-    // add deployment_id to chain_id path
-    if json[network_id].get(chain_id).is_none() {
-        json[network_id][chain_id] = json!({
-            deploy_id: {},
-            "code_ids": {}
-        });
+    let res = write_synced(&tmp, json).and_then(|_| fs::rename(&tmp, filename).map_err(Into::into));
+    if res.is_err() {
+        let _ = fs::remove_file(&tmp);
     }
+    res
+}
+
+fn write_synced(filename: &Path, json: &Value) -> Result<(), DaemonError> {
+    let mut file = File::create(filename)?;
+    file.write_all(serde_json::to_string_pretty(json)?.as_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
 
-    // write JSON data
-    // use File::create so we dont append data to the file
-    // but rather write all (because we have read the data before)
-    serde_json::to_writer_pretty(File::create(filename).unwrap(), &json).unwrap();
+/// Apply `update` to the latest content of the file while holding an exclusive lock on it.
+/// Processes and threads that update the same file are serialized, so none of their changes are lost.
+pub fn update(
+    filename: &Path,
+    update: impl FnOnce(&mut Value) -> Result<(), DaemonError>,
+) -> Result<(), DaemonError> {
+    let _lock = lock(filename)?;
+    let mut json = read(filename)?;
+    update(&mut json)?;
+    write(filename, &json)
 }
 
-pub fn read(filename: &String) -> Value {
-    let file =
-        File::open(filename).unwrap_or_else(|_| panic!("File should be present at {}", filename));
-    let json: serde_json::Value = from_reader(file).unwrap();
-    json
+/// Advisory lock on the file, released when the returned file is dropped.
+/// A separate lock file is used because the file itself is replaced on every write.
+fn lock(filename: &Path) -> Result<File, DaemonError> {
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(sibling(filename, "lock"))?;
+    lock_file.lock_exclusive()?;
+    Ok(lock_file)
+}
+
+/// `<filename>.<suffix>`, in the same directory so it can be renamed into place
+fn sibling(filename: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(filename.as_os_str());
+    name.push(format!(".{suffix}"));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, thread};
+
+    #[test]
+    fn concurrent_updates_are_merged() {
+        let path = env::temp_dir().join("cw-orch-concurrent-updates.json");
+        let _ = fs::remove_file(&path);
+
+        let writers: Vec<_> = (0..8)
+            .map(|writer| {
+                let path = path.clone();
+                thread::spawn(move || {
+                    for entry in 0..10 {
+                        update(&path, |json| {
+                            json[format!("writer-{writer}")][format!("entry-{entry}")] =
+                                json!(entry);
+                            Ok(())
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let json = read(&path).unwrap();
+        for writer in 0..8 {
+            assert_eq!(
+                json[format!("writer-{writer}")].as_object().unwrap().len(),
+                10
+            );
+        }
+
+        // no temporary files are left behind
+        let leftovers = fs::read_dir(env::temp_dir())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with("cw-orch-concurrent-updates.json.tmp")
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+    }
 }
@@ -7,14 +7,20 @@ mod chain_info;
 mod channel;
 mod core;
 mod error;
+mod gas_price;
 pub(crate) mod json_file;
+mod pending_tx;
+mod proposal;
 /// Proto types for different blockchains
 pub mod proto;
+mod rest;
 mod sender;
+#[cfg(test)]
+mod stand_in;
 mod state;
+mod state_backend;
+mod state_export;
 mod sync;
-/// Custom traits for DaemonAsync contracts
-mod traits;
 mod tx_resp;
 // expose these as mods as they can grow
 pub mod networks;
@@ -22,10 +28,24 @@ pub mod queriers;
 pub(crate) mod tx_builder;
 
 pub use self::{
-    builder::*, chain_info::*, channel::*, core::*, error::*, state::*, sync::*, traits::*,
+    builder::*,
+    chain_info::*,
+    channel::*,
+    core::*,
+    error::*,
+    gas_price::GasPriceSource,
+    pending_tx::{CancelHandle, ConfirmationPolicy, PendingTx},
+    proposal::{ProposalContent, VoteOption},
+    rest::RestChannel,
+    state::*,
+    state_backend::*,
+    state_export::*,
+    sync::*,
     tx_resp::*,
 };
-pub use sender::Wallet;
+pub use sender::{SenderOptions, Wallet};
+// the conditional helpers used to be daemon specific
+pub use crate::interface_traits::{ConditionalMigrate, ConditionalUpload};
 pub use tx_builder::TxBuilder;
 
 pub(crate) mod cosmos_modules {
@@ -2,57 +2,193 @@
 //! # Cosmos blockchain networks
 //! Contains information and helpers for different blockchain networks
 //! See [parse_network] to easily retrieve this static network information
+//! and [NetworkResolver] to resolve any network of the cosmos chain registry
 pub mod archway;
 pub mod injective;
 pub mod juno;
 pub mod kujira;
 pub mod neutron;
 pub mod osmosis;
+pub mod registry;
 pub mod sei;
 pub mod terra;
 
-pub use crate::daemon::chain_info::{ChainInfo, ChainKind, NetworkInfo};
+use std::{env, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+pub use crate::daemon::chain_info::{
+    ChainInfo, ChainInfoOwned, ChainKind, NetworkInfo, NetworkInfoOwned,
+};
+use crate::daemon::error::DaemonError;
 pub use archway::{ARCHWAY_1, CONSTANTINE_3};
 pub use injective::{INJECTIVE_1, INJECTIVE_888};
 pub use juno::{JUNO_1, LOCAL_JUNO, UNI_6};
 pub use kujira::HARPOON_4;
 pub use neutron::{LOCAL_NEUTRON, NEUTRON_1, PION_1};
 pub use osmosis::{LOCAL_OSMO, OSMO_5};
+pub use registry::NetworkResolver;
 pub use sei::{ATLANTIC_2, LOCAL_SEI, SEI_DEVNET_3};
 pub use terra::{LOCAL_TERRA, PHOENIX_1, PISCO_1};
 
-/// A helper function to retrieve a [`ChainInfo`] struct for a given chain-id.
+/// Networks bundled with cw-orch, these override the chain registry in [NetworkResolver]
+pub const SUPPORTED_NETWORKS: &[ChainInfo<'static>] = &[
+    UNI_6,
+    JUNO_1,
+    LOCAL_JUNO,
+    PISCO_1,
+    PHOENIX_1,
+    LOCAL_TERRA,
+    INJECTIVE_888,
+    CONSTANTINE_3,
+    ARCHWAY_1,
+    PION_1,
+    NEUTRON_1,
+    INJECTIVE_1,
+    HARPOON_4,
+    OSMO_5,
+    LOCAL_OSMO,
+    LOCAL_NEUTRON,
+];
+
+/// Env variable with the path of a TOML or JSON file that defines extra networks for [parse_network]
+pub const NETWORKS_FILE_ENV: &str = "CW_ORCH_NETWORKS";
+
+/// Networks defined in a networks file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NetworksFile {
+    networks: Vec<ChainInfoOwned>,
+}
+
+/// Load the networks defined in a TOML or JSON file.
+/// JSON is used for files with a `.json` extension, TOML otherwise.
+///
+/// ## Example
+/// ```toml
+/// [[networks]]
+/// chain_id = "devnet-1"
+/// gas_denom = "ustake"
+/// gas_price = 0.025
+/// grpc_urls = ["http://localhost:9090"]
+/// kind = "local"
+///
+/// [networks.network_info]
+/// id = "devnet"
+/// pub_address_prefix = "wasm"
+/// coin_type = 118
+/// ```
+pub fn load_networks(path: impl AsRef<Path>) -> Result<Vec<ChainInfoOwned>, DaemonError> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    let file: NetworksFile = if path.extension().map_or(false, |ext| ext == "json") {
+        serde_json::from_str(&content)?
+    } else {
+        toml::from_str(&content)?
+    };
+    Ok(file.networks)
+}
+
+/// Networks of the file at the `CW_ORCH_NETWORKS` env variable, if set
+fn user_networks() -> Result<Vec<ChainInfoOwned>, DaemonError> {
+    match env::var(NETWORKS_FILE_ENV) {
+        Ok(path) => load_networks(path),
+        Err(_) => Ok(vec![]),
+    }
+}
+
+/// A helper function to retrieve the network information of a given chain-id.
+/// Looks through the networks of the file at the `CW_ORCH_NETWORKS` env variable first, then through the [SUPPORTED_NETWORKS].
+/// Use [NetworkResolver] for chains that are in neither, it falls back to the cosmos chain registry.
 ///
 /// ## Example
 /// ```rust,no_run
-/// use cw_orch::prelude::networks::{parse_network, ChainInfo};
-/// let juno_mainnet: ChainInfo = parse_network("juno-1");
+/// use cw_orch::prelude::networks::{parse_network, ChainInfoOwned};
+/// let juno_mainnet: ChainInfoOwned = parse_network("juno-1").unwrap();
 /// ```
-/// ---
-/// supported chains are: UNI_6, JUNO_1, LOCAL_JUNO, PISCO_1, PHOENIX_1, LOCAL_TERRA, INJECTIVE_888, CONSTANTINE_1, BARYON_1, INJECTIVE_1, HARPOON_4, OSMO_4, LOCAL_OSMO
-pub fn parse_network(net_id: &str) -> ChainInfo {
-    let networks = vec![
-        UNI_6,
-        JUNO_1,
-        LOCAL_JUNO,
-        PISCO_1,
-        PHOENIX_1,
-        LOCAL_TERRA,
-        INJECTIVE_888,
-        CONSTANTINE_3,
-        ARCHWAY_1,
-        PION_1,
-        NEUTRON_1,
-        INJECTIVE_1,
-        HARPOON_4,
-        OSMO_5,
-        LOCAL_OSMO,
-        LOCAL_NEUTRON,
-    ];
-    for net in networks {
-        if net.chain_id == net_id {
-            return net;
-        }
+pub fn parse_network(net_id: &str) -> Result<ChainInfoOwned, DaemonError> {
+    find_network(net_id, user_networks()?)
+}
+
+/// User networks override the supported networks with the same chain id
+fn find_network(
+    net_id: &str,
+    user_networks: Vec<ChainInfoOwned>,
+) -> Result<ChainInfoOwned, DaemonError> {
+    user_networks
+        .into_iter()
+        .find(|net| net.chain_id == net_id)
+        .or_else(|| {
+            SUPPORTED_NETWORKS
+                .iter()
+                .find(|net| net.chain_id == net_id)
+                .cloned()
+                .map(Into::into)
+        })
+        .ok_or_else(|| DaemonError::NetworkNotFound(net_id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETWORKS_TOML: &str = r#"
+        [[networks]]
+        chain_id = "devnet-1"
+        gas_denom = "ustake"
+        gas_price = 0.025
+        grpc_urls = ["http://localhost:9090"]
+        kind = "local"
+
+        [networks.network_info]
+        id = "devnet"
+        pub_address_prefix = "wasm"
+    "#;
+
+    fn write_networks(name: &str, content: &str) -> std::path::PathBuf {
+        let path = env::temp_dir().join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_toml_networks() {
+        let path = write_networks("cw-orch-networks.toml", NETWORKS_TOML);
+        let networks = load_networks(path).unwrap();
+
+        assert_eq!(networks.len(), 1);
+        assert_eq!(networks[0].chain_id, "devnet-1");
+        assert_eq!(networks[0].kind, ChainKind::Local);
+        assert_eq!(networks[0].network_info.coin_type, 118);
+        assert_eq!(networks[0].lcd_url, None);
+    }
+
+    #[test]
+    fn loads_json_networks() {
+        let juno: ChainInfoOwned = JUNO_1.into();
+        let content = serde_json::to_string(&NetworksFile {
+            networks: vec![juno.clone()],
+        })
+        .unwrap();
+        let path = write_networks("cw-orch-networks.json", &content);
+
+        assert_eq!(load_networks(path).unwrap(), vec![juno]);
+    }
+
+    #[test]
+    fn user_networks_override_supported_networks() {
+        let mut local_juno: ChainInfoOwned = LOCAL_JUNO.into();
+        local_juno.grpc_urls = vec!["http://fork:9090".to_string()];
+
+        let found = find_network(LOCAL_JUNO.chain_id, vec![local_juno.clone()]).unwrap();
+        assert_eq!(found, local_juno);
+
+        let found = find_network("juno-1", vec![local_juno]).unwrap();
+        assert_eq!(found, JUNO_1.into());
+    }
+
+    #[test]
+    fn unknown_network() {
+        let res = find_network("unknown-1", vec![]);
+        assert!(matches!(res, Err(DaemonError::NetworkNotFound(id)) if id == "unknown-1"));
     }
-    panic!("Network not found: {}", net_id);
 }
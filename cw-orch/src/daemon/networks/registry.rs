@@ -0,0 +1,434 @@
+//! Resolve networks from the [cosmos chain registry](https://github.com/cosmos/chain-registry).
+//!
+//! Chains that are not bundled with cw-orch can be used without a new release:
+//! their data is fetched from the registry, cached on disk and can be used offline afterwards.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use ibc_chain_registry::{chain::ChainData, fetchable::Fetchable};
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+
+use super::{load_networks, ChainInfo, NETWORKS_FILE_ENV, SUPPORTED_NETWORKS};
+use crate::daemon::error::DaemonError;
+
+/// Env variable to set the directory where chain registry data is cached.
+/// Defaults to `~/.cw-orchestrator/chain-registry`
+pub const CHAIN_REGISTRY_CACHE_ENV: &str = "CHAIN_REGISTRY_CACHE";
+
+/// Resolves the [`ChainData`] of a network by chain name (ex. `juno`) or chain id (ex. `juno-1`).
+///
+/// Networks are looked up in order in:
+/// 1. The user networks file at the `CW_ORCH_NETWORKS` env variable (see [`load_networks`]), by chain id or network id.
+/// 2. The networks bundled with cw-orch (see [`SUPPORTED_NETWORKS`]), by chain id. These override the registry.
+/// 3. The on-disk cache of previously fetched chains.
+/// 4. The chain registry, unless offline. Fetched chains are written to the cache.
+/// 5. A vendored snapshot of the chain registry.
+///
+/// ## Example
+/// ```no_run
+/// # tokio_test::block_on(async {
+/// use cw_orch::prelude::{networks::NetworkResolver, DaemonAsync};
+///
+/// let stargaze = NetworkResolver::default().resolve("stargaze-1").await.unwrap();
+/// let daemon = DaemonAsync::builder().chain(stargaze).build().await.unwrap();
+/// # })
+/// ```
+#[derive(Clone, Debug)]
+pub struct NetworkResolver {
+    networks_file: Option<PathBuf>,
+    cache_dir: Option<PathBuf>,
+    snapshot_dir: Option<PathBuf>,
+    commit: Option<String>,
+    offline: bool,
+    refresh: bool,
+    bundled_networks: bool,
+}
+
+impl Default for NetworkResolver {
+    fn default() -> Self {
+        Self {
+            networks_file: env::var(NETWORKS_FILE_ENV).ok().map(Into::into),
+            cache_dir: Some(default_cache_dir()),
+            snapshot_dir: None,
+            commit: None,
+            offline: false,
+            refresh: false,
+            bundled_networks: true,
+        }
+    }
+}
+
+impl NetworkResolver {
+    /// Set the user networks file, see [`load_networks`]. Its networks override all other sources.
+    /// Defaults to the `CW_ORCH_NETWORKS` env variable.
+    pub fn networks_file(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.networks_file = Some(path.into());
+        self
+    }
+
+    /// Set the directory where fetched chains are cached.
+    /// Defaults to the `CHAIN_REGISTRY_CACHE` env variable or `~/.cw-orchestrator/chain-registry`
+    pub fn cache_dir(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Don't read or write the on-disk cache
+    pub fn no_cache(&mut self) -> &mut Self {
+        self.cache_dir = None;
+        self
+    }
+
+    /// Set a vendored snapshot of the chain registry to fall back to.
+    /// The directory has the layout of the registry repository: `<chain_name>/chain.json` and `testnets/<chain_name>/chain.json`.
+    pub fn snapshot_dir(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.snapshot_dir = Some(dir.into());
+        self
+    }
+
+    /// Set the commit or branch of the chain registry to fetch from.
+    /// Defaults to the registry's default branch.
+    pub fn commit(&mut self, commit: impl Into<String>) -> &mut Self {
+        self.commit = Some(commit.into());
+        self
+    }
+
+    /// Only use the bundled networks, the cache and the snapshot, without fetching the registry.
+    pub fn offline(&mut self, offline: bool) -> &mut Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Fetch the registry even if the chain is cached.
+    /// The cache is still used if the registry can't be reached.
+    pub fn refresh(&mut self, refresh: bool) -> &mut Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Whether the networks bundled with cw-orch override the registry. Defaults to `true`.
+    pub fn bundled_networks(&mut self, enabled: bool) -> &mut Self {
+        self.bundled_networks = enabled;
+        self
+    }
+
+    /// Resolve the network by chain name or chain id.
+    /// Registry chains without fee tokens are rejected, as no fees could be paid on them.
+    pub async fn resolve(&self, name_or_id: &str) -> Result<ChainData, DaemonError> {
+        if let Some(path) = &self.networks_file {
+            let user_network = load_networks(path)?.into_iter().find(|network| {
+                network.chain_id == name_or_id || network.network_info.id == name_or_id
+            });
+            if let Some(network) = user_network {
+                return Ok(network.into());
+            }
+        }
+
+        if self.bundled_networks {
+            if let Some(network) = bundled_network(name_or_id) {
+                return Ok(network.into());
+            }
+        }
+
+        let chain = self.resolve_registry(name_or_id).await?;
+        if chain.fees.fee_tokens.is_empty() {
+            return Err(DaemonError::ChainRegistry(format!(
+                "chain {} has no fee tokens",
+                chain.chain_id
+            )));
+        }
+        Ok(chain)
+    }
+
+    /// Resolve the network from the cache, the registry or the snapshot
+    async fn resolve_registry(&self, name_or_id: &str) -> Result<ChainData, DaemonError> {
+        if !self.refresh {
+            if let Some(chain) = self.cached(name_or_id)? {
+                return Ok(chain);
+            }
+        }
+
+        if !self.offline {
+            match self.fetch(name_or_id).await {
+                Ok(chain) => {
+                    self.write_cache(&chain)?;
+                    return Ok(chain);
+                }
+                Err(e) => log::warn!(
+                    "Could not fetch {} from the chain registry: {}",
+                    name_or_id,
+                    e
+                ),
+            }
+
+            if self.refresh {
+                if let Some(chain) = self.cached(name_or_id)? {
+                    return Ok(chain);
+                }
+            }
+        }
+
+        if let Some(chain) = self.snapshot(name_or_id)? {
+            return Ok(chain);
+        }
+
+        Err(DaemonError::NetworkNotFound(name_or_id.to_string()))
+    }
+
+    /// Fetch the chain from the registry, which is keyed by chain name.
+    /// For chain ids the name is derived from the id, ex. `stargaze-1` -> `stargaze`.
+    async fn fetch(&self, name_or_id: &str) -> Result<ChainData, DaemonError> {
+        let mut names = vec![name_or_id];
+        if ChainId::is_epoch_format(name_or_id) {
+            if let Some((name, _)) = name_or_id.rsplit_once('-') {
+                names.push(name);
+            }
+        }
+
+        for name in names {
+            match ChainData::fetch(name.to_string(), self.commit.clone()).await {
+                Ok(chain) if matches(&chain, name_or_id) => return Ok(chain),
+                Ok(chain) => log::debug!(
+                    "Registry chain {} has id {}, not {}",
+                    name,
+                    chain.chain_id,
+                    name_or_id
+                ),
+                Err(e) => log::debug!("Registry has no chain {}: {}", name, e),
+            }
+        }
+
+        Err(DaemonError::ChainRegistry(format!(
+            "no chain with name or id {name_or_id}"
+        )))
+    }
+
+    fn cached(&self, name_or_id: &str) -> Result<Option<ChainData>, DaemonError> {
+        let Some(dir) = &self.cache_dir else {
+            return Ok(None);
+        };
+        if !dir.exists() {
+            return Ok(None);
+        }
+
+        let direct = dir.join(format!("{name_or_id}.json"));
+        let files = json_files(dir)?;
+        find_chain(std::iter::once(direct).chain(files), name_or_id)
+    }
+
+    fn write_cache(&self, chain: &ChainData) -> Result<(), DaemonError> {
+        let Some(dir) = &self.cache_dir else {
+            return Ok(());
+        };
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.json", chain.chain_name));
+        fs::write(path, serde_json::to_string_pretty(chain)?)?;
+        Ok(())
+    }
+
+    fn snapshot(&self, name_or_id: &str) -> Result<Option<ChainData>, DaemonError> {
+        let Some(dir) = &self.snapshot_dir else {
+            return Ok(None);
+        };
+
+        let mut files = vec![
+            dir.join(name_or_id).join("chain.json"),
+            dir.join("testnets").join(name_or_id).join("chain.json"),
+        ];
+        for chains_dir in [dir.to_path_buf(), dir.join("testnets")] {
+            if !chains_dir.exists() {
+                continue;
+            }
+            for entry in fs::read_dir(chains_dir)? {
+                files.push(entry?.path().join("chain.json"));
+            }
+        }
+        find_chain(files.into_iter(), name_or_id)
+    }
+}
+
+/// Bundled network with the given chain id
+fn bundled_network(chain_id: &str) -> Option<ChainInfo<'static>> {
+    SUPPORTED_NETWORKS
+        .iter()
+        .find(|network| network.chain_id == chain_id)
+        .cloned()
+}
+
+fn matches(chain: &ChainData, name_or_id: &str) -> bool {
+    chain.chain_name == name_or_id || chain.chain_id.as_str() == name_or_id
+}
+
+/// First chain file that matches the name or id. Missing and invalid files are skipped.
+fn find_chain(
+    files: impl Iterator<Item = PathBuf>,
+    name_or_id: &str,
+) -> Result<Option<ChainData>, DaemonError> {
+    for file in files {
+        if !file.is_file() {
+            continue;
+        }
+        let chain: ChainData = match serde_json::from_slice(&fs::read(&file)?) {
+            Ok(chain) => chain,
+            Err(e) => {
+                log::warn!("Skipping invalid chain file {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        if matches(&chain, name_or_id) {
+            return Ok(Some(chain));
+        }
+    }
+    Ok(None)
+}
+
+fn json_files(dir: &Path) -> Result<Vec<PathBuf>, DaemonError> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn default_cache_dir() -> PathBuf {
+    if let Ok(dir) = env::var(CHAIN_REGISTRY_CACHE_ENV) {
+        return dir.into();
+    }
+    let home = env::var("HOME").unwrap_or(".".to_string());
+    Path::new(&home)
+        .join(".cw-orchestrator")
+        .join("chain-registry")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ibc_chain_registry::chain::{FeeToken, FeeTokens};
+    use serde_json::json;
+    use speculoos::prelude::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("cw-orch-registry-{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn stargaze() -> ChainData {
+        ChainData {
+            chain_name: "stargaze".to_string(),
+            chain_id: "stargaze-1".to_string().into(),
+            bech32_prefix: "stars".to_string(),
+            fees: FeeTokens {
+                fee_tokens: vec![FeeToken {
+                    denom: "ustars".to_string(),
+                    ..Default::default()
+                }],
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn user_networks_override_bundled_networks() {
+        let dir = test_dir("user-networks");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("networks.json");
+        let mut local_juno: super::super::ChainInfoOwned = super::super::LOCAL_JUNO.into();
+        local_juno.grpc_urls = vec!["http://fork:9090".to_string()];
+        fs::write(
+            &path,
+            serde_json::to_string(&json!({ "networks": [local_juno] })).unwrap(),
+        )
+        .unwrap();
+
+        let mut resolver = NetworkResolver::default();
+        resolver.networks_file(&path).no_cache().offline(true);
+
+        let by_id = resolver.resolve("testing").await.unwrap();
+        assert_eq!(by_id.apis.grpc[0].address, "http://fork:9090");
+        let by_name = resolver.resolve("juno").await;
+        assert_eq!(by_name.unwrap().chain_id.as_str(), "testing");
+    }
+
+    #[tokio::test]
+    async fn bundled_networks_override_registry() {
+        let chain = NetworkResolver::default()
+            .no_cache()
+            .offline(true)
+            .resolve("juno-1")
+            .await
+            .unwrap();
+
+        assert_eq!(chain.chain_name, "juno");
+        assert_eq!(chain.apis.grpc.len(), super::super::JUNO_1.grpc_urls.len());
+    }
+
+    #[tokio::test]
+    async fn resolves_from_snapshot_by_name_and_id() {
+        let snapshot = test_dir("snapshot");
+        fs::create_dir_all(snapshot.join("stargaze")).unwrap();
+        fs::write(
+            snapshot.join("stargaze").join("chain.json"),
+            serde_json::to_string(&stargaze()).unwrap(),
+        )
+        .unwrap();
+
+        let mut resolver = NetworkResolver::default();
+        resolver.no_cache().offline(true).snapshot_dir(&snapshot);
+
+        let by_name = resolver.resolve("stargaze").await.unwrap();
+        let by_id = resolver.resolve("stargaze-1").await.unwrap();
+
+        assert_eq!(by_name.chain_id.as_str(), "stargaze-1");
+        assert_eq!(by_id.chain_name, "stargaze");
+    }
+
+    #[tokio::test]
+    async fn cached_chains_resolve_offline() {
+        let cache = test_dir("cache");
+        let mut resolver = NetworkResolver::default();
+        resolver.cache_dir(&cache).offline(true);
+        resolver.write_cache(&stargaze()).unwrap();
+
+        let chain = resolver.resolve("stargaze-1").await.unwrap();
+
+        assert_eq!(chain.bech32_prefix, "stars");
+        assert!(cache.join("stargaze.json").is_file());
+    }
+
+    #[tokio::test]
+    async fn unknown_network_offline() {
+        let res = NetworkResolver::default()
+            .no_cache()
+            .offline(true)
+            .resolve("unknown-1")
+            .await;
+
+        asserting!("unknown network is not found")
+            .that(&res.unwrap_err().to_string())
+            .is_equal_to("network unknown-1 not found".to_string());
+    }
+
+    #[tokio::test]
+    async fn chains_without_fee_tokens_are_rejected() {
+        let cache = test_dir("no-fees");
+        let mut resolver = NetworkResolver::default();
+        resolver.cache_dir(&cache).offline(true);
+        resolver
+            .write_cache(&ChainData {
+                fees: FeeTokens { fee_tokens: vec![] },
+                ..stargaze()
+            })
+            .unwrap();
+
+        let res = resolver.resolve("stargaze-1").await;
+
+        assert!(matches!(res, Err(DaemonError::ChainRegistry(_))));
+    }
+}
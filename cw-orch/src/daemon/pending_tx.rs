@@ -0,0 +1,260 @@
+//! Handles to txs that are broadcasted but not yet confirmed.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::Notify;
+
+use super::{
+    error::DaemonError,
+    queriers::{DaemonQuerier, Node},
+    sender::check_tx_result,
+    tx_resp::CosmTxResponse,
+    DaemonChannel,
+};
+
+/// Lower bound of the derived block polling interval, the average block speed is rounded down to whole seconds
+const MIN_BLOCK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Decides when a broadcasted tx is considered confirmed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfirmationPolicy {
+    /// Amount of blocks that need to be added on top of the block that includes the tx.
+    /// `0` means the tx is confirmed as soon as it's included.
+    pub confirmations: u64,
+    /// Maximum time to wait for the confirmation, waits until the node lookup gives up if `None`.
+    pub timeout: Option<Duration>,
+    /// Interval at which the node is polled.
+    /// If `None`, the interval is derived from the average block time and increases with every attempt.
+    pub poll_interval: Option<Duration>,
+}
+
+/// Can be used to stop waiting for a [`PendingTx`], possibly from another task.
+#[derive(Clone, Debug, Default)]
+pub struct CancelHandle(Arc<CancelState>);
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelHandle {
+    /// Stop waiting for the tx. Does not remove the tx from the mempool!
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    /// Returns whether the wait was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the wait is cancelled
+    async fn cancelled(&self) {
+        loop {
+            // created before checking the flag so a concurrent cancel can't be missed
+            let notified = self.0.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// A tx that was accepted in the mempool but might not be included in a block yet.
+/// Returned right after broadcasting so other work can be done while the tx is being confirmed.
+///
+/// ## Example
+/// ```no_run
+/// # tokio_test::block_on(async {
+/// use cw_orch::prelude::{DaemonAsync, networks};
+/// use cosmrs::{bank::MsgSend, AccountId};
+/// use std::time::Duration;
+///
+/// let daemon = DaemonAsync::builder()
+///     .chain(networks::LOCAL_JUNO)
+///     .build()
+///     .await.unwrap();
+///
+/// let msg = MsgSend {
+///     from_address: daemon.sender().to_string().parse::<AccountId>().unwrap(),
+///     to_address: daemon.sender().to_string().parse::<AccountId>().unwrap(),
+///     amount: vec![],
+/// };
+/// let pending = daemon.sender.submit_tx(vec![msg], None).await.unwrap();
+/// println!("broadcasted tx {}", pending.hash());
+///
+/// // ... do other work ...
+///
+/// let tx = pending
+///     .with_confirmations(2)
+///     .with_timeout(Duration::from_secs(60))
+///     .wait()
+///     .await
+///     .unwrap();
+/// # })
+/// ```
+#[derive(Clone, Debug)]
+pub struct PendingTx {
+    hash: String,
+    channel: DaemonChannel,
+    policy: ConfirmationPolicy,
+    cancel: CancelHandle,
+}
+
+impl PendingTx {
+    /// Creates a handle for the tx with the given hash.
+    pub fn new(
+        hash: impl Into<String>,
+        channel: DaemonChannel,
+        policy: ConfirmationPolicy,
+    ) -> Self {
+        Self {
+            hash: hash.into(),
+            channel,
+            policy,
+            cancel: CancelHandle::default(),
+        }
+    }
+
+    /// Hash of the tx
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// Policy used to decide when the tx is confirmed
+    pub fn policy(&self) -> &ConfirmationPolicy {
+        &self.policy
+    }
+
+    /// Replace the confirmation policy of this tx
+    pub fn with_policy(mut self, policy: ConfirmationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Wait at most `timeout` for the confirmation
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.policy.timeout = Some(timeout);
+        self
+    }
+
+    /// Require `confirmations` blocks on top of the block including the tx
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.policy.confirmations = confirmations;
+        self
+    }
+
+    /// Handle to cancel waiting for this tx
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Wait until the tx is confirmed according to the confirmation policy.
+    /// Returns an error if the tx failed, the timeout is reached or the wait is cancelled.
+    pub async fn wait(&self) -> Result<CosmTxResponse, DaemonError> {
+        let confirmation = async {
+            match self.policy.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, self.wait_for_confirmation())
+                    .await
+                    .map_err(|_| DaemonError::TxTimeout {
+                        hash: self.hash.clone(),
+                        timeout,
+                    })?,
+                None => self.wait_for_confirmation().await,
+            }
+        };
+
+        let resp = tokio::select! {
+            resp = confirmation => resp?,
+            _ = self.cancel.cancelled() => return Err(DaemonError::TxWaitCancelled(self.hash.clone())),
+        };
+
+        check_tx_result(resp)
+    }
+
+    async fn wait_for_confirmation(&self) -> Result<CosmTxResponse, DaemonError> {
+        let node = Node::new(self.channel.clone());
+
+        let resp = match self.policy.poll_interval {
+            None => node.find_tx(self.hash.clone()).await?,
+            Some(poll_interval) => loop {
+                match node.tx(self.hash.clone()).await {
+                    Ok(resp) => break resp,
+                    Err(err) if is_not_found(&err) => {
+                        log::debug!("TX not found with error: {:?}", err);
+                        tokio::time::sleep(poll_interval).await;
+                    }
+                    Err(err) => return Err(err),
+                }
+            },
+        };
+
+        if self.policy.confirmations == 0 {
+            return Ok(resp);
+        }
+
+        // wait for the blocks on top of the inclusion block
+        let confirmed_height = resp.height + self.policy.confirmations;
+        let poll_interval = match self.policy.poll_interval {
+            Some(poll_interval) => poll_interval,
+            None => Duration::from_secs(node.average_block_speed(None).await?)
+                .max(MIN_BLOCK_POLL_INTERVAL),
+        };
+        while node.block_height().await? < confirmed_height {
+            tokio::time::sleep(poll_interval).await;
+        }
+
+        Ok(resp)
+    }
+}
+
+/// Whether the tx lookup failed because the tx isn't included yet, rather than because of the node
+fn is_not_found(err: &DaemonError) -> bool {
+    match err {
+        DaemonError::TXNotFound(..) => true,
+        DaemonError::Status(status) => {
+            status.code() == tonic::Code::NotFound || status.message().contains("not found")
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_missing_txs_are_retried() {
+        assert!(is_not_found(&DaemonError::TXNotFound("hash".into(), 1)));
+        assert!(is_not_found(&DaemonError::Status(
+            tonic::Status::not_found("tx not found: hash")
+        )));
+        assert!(!is_not_found(&DaemonError::Status(
+            tonic::Status::unavailable("connection refused")
+        )));
+    }
+
+    #[tokio::test]
+    async fn cancel_wakes_waiter() {
+        let handle = CancelHandle::default();
+        let waiter = handle.clone();
+
+        let task = tokio::spawn(async move { waiter.cancelled().await });
+        handle.cancel();
+
+        tokio::time::timeout(Duration::from_secs(1), task)
+            .await
+            .expect("cancellation was not observed")
+            .unwrap();
+        assert!(handle.is_cancelled());
+    }
+}
@@ -0,0 +1,409 @@
+//! Gov v1 proposals to store, instantiate and migrate code on chains where those actions are permissioned.
+
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use cosmrs::{AccountId, Any};
+use cosmwasm_std::{Addr, Coin};
+use serde::Serialize;
+
+use super::{
+    core::{parse_cw_coins_proto, store_code_msg},
+    error::DaemonError,
+    proto::gov as gov_proto,
+    queriers::{raw_query, GovProposalStatus, Node},
+    sender::encode_any,
+    tx_resp::CosmTxResponse,
+    DaemonAsync,
+};
+use crate::{
+    error::CwOrchError,
+    prelude::{AccessConfig, IndexResponse, Uploadable},
+    state::StateInterface,
+};
+
+/// Name of the gov module, its account is the authority of the messages in proposals
+const GOV_MODULE_NAME: &str = "gov";
+/// Cosmos SDK version that added the title and summary to gov v1 proposals
+const PROPOSAL_TITLE_SDK_VERSION: (u64, u64) = (0, 47);
+
+/// Title, summary and deposit of a proposal
+#[derive(Clone, Debug, Default)]
+pub struct ProposalContent {
+    /// Title of the proposal
+    pub title: String,
+    /// Summary of the proposal
+    pub summary: String,
+    /// Metadata of the proposal, usually a link to a JSON document
+    pub metadata: String,
+    /// Deposit sent with the proposal
+    pub deposit: Vec<Coin>,
+}
+
+impl ProposalContent {
+    /// Proposal with a title and a summary, without deposit
+    pub fn new(title: impl Into<String>, summary: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            summary: summary.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the deposit sent with the proposal
+    pub fn deposit(&mut self, deposit: Vec<Coin>) -> &mut Self {
+        self.deposit = deposit;
+        self
+    }
+
+    /// Set the metadata of the proposal
+    pub fn metadata(&mut self, metadata: impl Into<String>) -> &mut Self {
+        self.metadata = metadata.into();
+        self
+    }
+}
+
+/// Vote on a proposal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VoteOption {
+    #[allow(missing_docs)]
+    Yes = 1,
+    #[allow(missing_docs)]
+    Abstain = 2,
+    #[allow(missing_docs)]
+    No = 3,
+    #[allow(missing_docs)]
+    NoWithVeto = 4,
+}
+
+impl DaemonAsync {
+    /// Address of the gov module, which signs the messages of passed proposals.
+    pub fn gov_authority(&self) -> Result<Addr, DaemonError> {
+        module_address(&self.state.chain_data.bech32_prefix, GOV_MODULE_NAME)
+    }
+
+    /// Submit a gov v1 proposal executing `messages`, which must be signed by the [`gov_authority`](Self::gov_authority).
+    /// Chains before Cosmos SDK v0.47 reject the title and summary, only the metadata is submitted to them.
+    /// Returns the id of the proposal.
+    pub async fn submit_proposal(
+        &self,
+        messages: Vec<Any>,
+        content: &ProposalContent,
+    ) -> Result<u64, DaemonError> {
+        let sdk_version = self
+            .query_client::<Node>()
+            .info()
+            .await?
+            .application_version
+            .map(|version| version.cosmos_sdk_version)
+            .unwrap_or_default();
+        let with_title = supports_proposal_title(&sdk_version);
+        if !with_title {
+            log::warn!(
+                "Cosmos SDK {} doesn't support proposal titles and summaries, only the metadata is submitted",
+                sdk_version
+            );
+        }
+        let msg = submit_proposal_msg(messages, content, self.sender.pub_addr_str()?, with_title);
+        let result = self
+            .sender
+            .commit_tx_any(
+                vec![encode_any(gov_proto::MSG_SUBMIT_PROPOSAL_TYPE_URL, msg)],
+                None,
+            )
+            .await?;
+        let proposal_id = result
+            .event_attr_value("submit_proposal", "proposal_id")
+            .map_err(|e| DaemonError::StdErr(e.to_string()))?
+            .parse()?;
+        log::info!("Submitted proposal {}: {}", proposal_id, content.title);
+        Ok(proposal_id)
+    }
+
+    /// Propose to store the code of the contract, only instantiable as allowed by `permission`.
+    pub async fn propose_store_code(
+        &self,
+        uploadable: &impl Uploadable,
+        permission: Option<&AccessConfig>,
+        content: &ProposalContent,
+    ) -> Result<u64, DaemonError> {
+        let msg = store_code_msg(
+            self.gov_authority()?.to_string(),
+            &uploadable.wasm(),
+            permission,
+            &self.state.wasm_limits,
+        )?;
+        self.submit_proposal(vec![msg], content).await
+    }
+
+    /// Propose to instantiate a contract.
+    pub async fn propose_instantiate<I: Serialize>(
+        &self,
+        code_id: u64,
+        init_msg: &I,
+        label: Option<&str>,
+        admin: Option<&Addr>,
+        coins: &[Coin],
+        content: &ProposalContent,
+    ) -> Result<u64, DaemonError> {
+        let msg = gov_proto::MsgInstantiateContract {
+            sender: self.gov_authority()?.to_string(),
+            admin: admin
+                .map(|admin| AccountId::from_str(admin.as_str()).map(|a| a.to_string()))
+                .transpose()?
+                .unwrap_or_default(),
+            code_id,
+            label: label.unwrap_or("instantiate_contract").to_string(),
+            msg: serde_json::to_vec(init_msg)?,
+            funds: parse_cw_coins_proto(coins),
+        };
+        self.submit_proposal(
+            vec![encode_any(
+                gov_proto::MSG_INSTANTIATE_CONTRACT_TYPE_URL,
+                msg,
+            )],
+            content,
+        )
+        .await
+    }
+
+    /// Propose to migrate a contract, which must have the gov module as admin.
+    pub async fn propose_migrate<M: Serialize>(
+        &self,
+        migrate_msg: &M,
+        new_code_id: u64,
+        contract_address: &Addr,
+        content: &ProposalContent,
+    ) -> Result<u64, DaemonError> {
+        let msg = gov_proto::MsgMigrateContract {
+            sender: self.gov_authority()?.to_string(),
+            contract: AccountId::from_str(contract_address.as_str())?.to_string(),
+            code_id: new_code_id,
+            msg: serde_json::to_vec(migrate_msg)?,
+        };
+        self.submit_proposal(
+            vec![encode_any(gov_proto::MSG_MIGRATE_CONTRACT_TYPE_URL, msg)],
+            content,
+        )
+        .await
+    }
+
+    /// Add a deposit to a proposal.
+    pub async fn deposit(
+        &self,
+        proposal_id: u64,
+        amount: &[Coin],
+    ) -> Result<CosmTxResponse, DaemonError> {
+        let msg = gov_proto::MsgDeposit {
+            proposal_id,
+            depositor: self.sender.pub_addr_str()?,
+            amount: parse_cw_coins_proto(amount),
+        };
+        self.sender
+            .commit_tx_any(vec![encode_any(gov_proto::MSG_DEPOSIT_TYPE_URL, msg)], None)
+            .await
+    }
+
+    /// Vote on a proposal.
+    pub async fn vote(
+        &self,
+        proposal_id: u64,
+        option: VoteOption,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        let msg = gov_proto::MsgVote {
+            proposal_id,
+            voter: self.sender.pub_addr_str()?,
+            option: option as i32,
+            metadata: String::new(),
+        };
+        self.sender
+            .commit_tx_any(vec![encode_any(gov_proto::MSG_VOTE_TYPE_URL, msg)], None)
+            .await
+    }
+
+    /// Current status of a gov v1 proposal.
+    pub async fn proposal_status(
+        &self,
+        proposal_id: u64,
+    ) -> Result<GovProposalStatus, DaemonError> {
+        let resp: gov_proto::QueryProposalResponse = raw_query(
+            self.channel(),
+            gov_proto::QUERY_PROPOSAL_PATH,
+            gov_proto::QueryProposalRequest { proposal_id },
+        )
+        .await?;
+        let proposal = resp
+            .proposal
+            .ok_or_else(|| DaemonError::StdErr(format!("proposal {proposal_id} not found")))?;
+        Ok(GovProposalStatus::from_i32(proposal.status))
+    }
+
+    /// Wait, block by block, until the proposal passed.
+    /// Errors if the proposal is rejected or fails, or if it is still pending after `timeout`.
+    pub async fn wait_for_proposal(
+        &self,
+        proposal_id: u64,
+        timeout: Duration,
+    ) -> Result<(), DaemonError> {
+        let start = Instant::now();
+        loop {
+            let status = self.proposal_status(proposal_id).await?;
+            if status == GovProposalStatus::Passed {
+                log::info!("Proposal {} passed", proposal_id);
+                return Ok(());
+            }
+            if status.is_final() {
+                return Err(DaemonError::ProposalNotPassed {
+                    proposal_id,
+                    status: format!("{status:?}"),
+                });
+            }
+            if start.elapsed() >= timeout {
+                return Err(DaemonError::ProposalTimeout {
+                    proposal_id,
+                    timeout,
+                });
+            }
+            log::debug!("Proposal {} is in status {:?}", proposal_id, status);
+            self.next_block().await?;
+        }
+    }
+
+    /// Register the code stored by a passed store-code proposal for the contract id.
+    /// The code is looked up by the checksum of the contract's wasm among the codes stored by the gov module.
+    pub async fn resolve_proposal_code_id(
+        &self,
+        contract_id: &str,
+        uploadable: &impl Uploadable,
+    ) -> Result<u64, CwOrchError> {
+        let checksum = uploadable.wasm().checksum()?;
+        let code_id = self
+            .find_code(&checksum, Some(&self.gov_authority()?))
+            .await?
+            .ok_or_else(|| {
+                DaemonError::StdErr(format!(
+                    "no code with checksum {checksum} stored by governance"
+                ))
+            })?;
+        self.state().try_set_code_id(contract_id, code_id)?;
+        log::info!("Resolved code id {} for {}", code_id, contract_id);
+        Ok(code_id)
+    }
+
+    /// Propose to store the code of the contract, wait for the proposal to pass and register the stored code id for the contract id.
+    /// Votes and deposits by others have to happen while waiting.
+    pub async fn store_code_via_proposal(
+        &self,
+        contract_id: &str,
+        uploadable: &impl Uploadable,
+        content: &ProposalContent,
+        timeout: Duration,
+    ) -> Result<u64, CwOrchError> {
+        let proposal_id = self
+            .propose_store_code(
+                uploadable,
+                uploadable.instantiate_permission().as_ref(),
+                content,
+            )
+            .await?;
+        self.wait_for_proposal(proposal_id, timeout).await?;
+        self.resolve_proposal_code_id(contract_id, uploadable).await
+    }
+}
+
+/// Gov v1 message submitting the proposal, without title and summary for chains that don't support them
+fn submit_proposal_msg(
+    messages: Vec<Any>,
+    content: &ProposalContent,
+    proposer: String,
+    with_title: bool,
+) -> gov_proto::MsgSubmitProposal {
+    let (title, summary) = if with_title {
+        (content.title.clone(), content.summary.clone())
+    } else {
+        Default::default()
+    };
+    gov_proto::MsgSubmitProposal {
+        messages,
+        initial_deposit: parse_cw_coins_proto(&content.deposit),
+        proposer,
+        metadata: content.metadata.clone(),
+        title,
+        summary,
+    }
+}
+
+/// Whether a chain running the Cosmos SDK `sdk_version`, e.g. `v0.47.5`, accepts the title and summary of gov v1 proposals.
+/// Unknown versions are assumed to be recent.
+fn supports_proposal_title(sdk_version: &str) -> bool {
+    let mut parts = sdk_version.trim_start_matches('v').split('.').map(|part| {
+        part.chars()
+            .take_while(char::is_ascii_digit)
+            .collect::<String>()
+            .parse::<u64>()
+    });
+    match (parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor))) => (major, minor) >= PROPOSAL_TITLE_SDK_VERSION,
+        _ => true,
+    }
+}
+
+/// Address of the account of a Cosmos SDK module, the first 20 bytes of the sha256 hash of its name
+fn module_address(prefix: &str, module: &str) -> Result<Addr, DaemonError> {
+    let hash = hex::decode(sha256::digest(module)).expect("sha256 digest is hex");
+    let address = AccountId::new(prefix, &hash[..20])?;
+    Ok(Addr::unchecked(address.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn gov_module_address() {
+        assert_eq!(
+            module_address("cosmos", GOV_MODULE_NAME).unwrap(),
+            Addr::unchecked("cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn")
+        );
+    }
+
+    #[test]
+    fn proposal_title_needs_sdk_v047() {
+        assert!(!supports_proposal_title("v0.46.13"));
+        assert!(!supports_proposal_title("v0.45.16-ics"));
+        assert!(supports_proposal_title("v0.47.5"));
+        assert!(supports_proposal_title("0.50.1"));
+        assert!(supports_proposal_title(""));
+    }
+
+    #[test]
+    fn submit_proposal_encoding() {
+        let mut content = ProposalContent::new("t", "s");
+        content
+            .deposit(vec![Coin::new(10, "ujuno")])
+            .metadata("ipfs://m");
+
+        // fields 2 to 6 of cosmos.gov.v1.MsgSubmitProposal
+        let msg = submit_proposal_msg(vec![], &content, "juno1p".to_string(), true);
+        assert_eq!(
+            hex::encode(msg.encode_to_vec()),
+            "120b0a05756a756e6f12023130\
+             1a066a756e6f3170\
+             2208697066733a2f2f6d\
+             2a0174\
+             320173"
+        );
+
+        // the layout before Cosmos SDK v0.47 ends with the metadata
+        let msg = submit_proposal_msg(vec![], &content, "juno1p".to_string(), false);
+        assert_eq!(
+            hex::encode(msg.encode_to_vec()),
+            "120b0a05756a756e6f12023130\
+             1a066a756e6f3170\
+             2208697066733a2f2f6d"
+        );
+    }
+}
@@ -0,0 +1,14 @@
+#![allow(missing_docs)]
+//! Cosmos SDK queries that are not part of the `cosmrs` proto definitions
+
+pub const NODE_CONFIG_PATH: &str = "/cosmos.base.node.v1beta1.Service/Config";
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfigRequest {}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfigResponse {
+    /// Comma separated list of the minimum gas prices of the node, ex. `0.025ujuno,0.001uatom`
+    #[prost(string, tag = "1")]
+    pub minimum_gas_price: String,
+}
@@ -0,0 +1,141 @@
+#![allow(missing_docs)]
+//! CosmWasm messages with the `AccessConfig` layout of wasmd v0.31+, which the `cosmrs` proto definitions predate
+
+use cosmwasm_std::Addr;
+
+use crate::{daemon::error::DaemonError, interface_traits::AccessConfig as Permission};
+
+pub const MSG_STORE_CODE_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgStoreCode";
+pub const MSG_UPDATE_INSTANTIATE_CONFIG_TYPE_URL: &str =
+    "/cosmwasm.wasm.v1.MsgUpdateInstantiateConfig";
+pub const QUERY_CODE_PATH: &str = "/cosmwasm.wasm.v1.Query/Code";
+
+pub const ACCESS_TYPE_NOBODY: i32 = 1;
+pub const ACCESS_TYPE_ONLY_ADDRESS: i32 = 2;
+pub const ACCESS_TYPE_EVERYBODY: i32 = 3;
+pub const ACCESS_TYPE_ANY_OF_ADDRESSES: i32 = 4;
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccessConfig {
+    #[prost(int32, tag = "1")]
+    pub permission: i32,
+    /// Only used by `ACCESS_TYPE_ONLY_ADDRESS`, deprecated since wasmd v0.31
+    #[prost(string, tag = "2")]
+    pub address: String,
+    #[prost(string, repeated, tag = "3")]
+    pub addresses: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgStoreCode {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub wasm_byte_code: Vec<u8>,
+    #[prost(message, optional, tag = "5")]
+    pub instantiate_permission: Option<AccessConfig>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgUpdateInstantiateConfig {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(uint64, tag = "2")]
+    pub code_id: u64,
+    #[prost(message, optional, tag = "3")]
+    pub new_instantiate_permission: Option<AccessConfig>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryCodeRequest {
+    #[prost(uint64, tag = "1")]
+    pub code_id: u64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CodeInfoResponse {
+    #[prost(uint64, tag = "1")]
+    pub code_id: u64,
+    #[prost(string, tag = "2")]
+    pub creator: String,
+    #[prost(bytes = "vec", tag = "3")]
+    pub data_hash: Vec<u8>,
+    #[prost(message, optional, tag = "6")]
+    pub instantiate_permission: Option<AccessConfig>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryCodeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub code_info: Option<CodeInfoResponse>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub data: Vec<u8>,
+}
+
+impl From<&Permission> for AccessConfig {
+    fn from(config: &Permission) -> Self {
+        let (permission, address, addresses) = match config {
+            Permission::Nobody => (ACCESS_TYPE_NOBODY, String::new(), vec![]),
+            Permission::OnlyAddress(address) => {
+                (ACCESS_TYPE_ONLY_ADDRESS, address.to_string(), vec![])
+            }
+            Permission::AnyOfAddresses(addresses) => (
+                ACCESS_TYPE_ANY_OF_ADDRESSES,
+                String::new(),
+                addresses.iter().map(ToString::to_string).collect(),
+            ),
+            Permission::Everybody => (ACCESS_TYPE_EVERYBODY, String::new(), vec![]),
+        };
+        Self {
+            permission,
+            address,
+            addresses,
+        }
+    }
+}
+
+impl TryFrom<AccessConfig> for Permission {
+    type Error = DaemonError;
+
+    fn try_from(config: AccessConfig) -> Result<Self, DaemonError> {
+        Ok(match config.permission {
+            ACCESS_TYPE_NOBODY => Self::Nobody,
+            ACCESS_TYPE_ONLY_ADDRESS => Self::OnlyAddress(Addr::unchecked(config.address)),
+            ACCESS_TYPE_EVERYBODY => Self::Everybody,
+            ACCESS_TYPE_ANY_OF_ADDRESSES => {
+                Self::AnyOfAddresses(config.addresses.into_iter().map(Addr::unchecked).collect())
+            }
+            other => {
+                return Err(DaemonError::StdErr(format!(
+                    "unknown instantiate permission type {other}"
+                )))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_config_round_trip() {
+        let configs = [
+            Permission::Nobody,
+            Permission::OnlyAddress(Addr::unchecked("factory")),
+            Permission::AnyOfAddresses(vec![Addr::unchecked("a"), Addr::unchecked("b")]),
+            Permission::Everybody,
+        ];
+        for config in configs {
+            let proto = AccessConfig::from(&config);
+            assert_eq!(Permission::try_from(proto).unwrap(), config);
+        }
+
+        let unspecified = AccessConfig {
+            permission: 0,
+            address: String::new(),
+            addresses: vec![],
+        };
+        assert!(Permission::try_from(unspecified).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+#![allow(missing_docs)]
+//! `feemarket` module queries (<https://github.com/skip-mev/feemarket>)
+
+pub const GAS_PRICE_PATH: &str = "/feemarket.feemarket.v1.Query/GasPrice";
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GasPriceRequest {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GasPriceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub price: Option<DecCoin>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    /// `LegacyDec` encoded amount
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
@@ -0,0 +1,186 @@
+#![allow(missing_docs)]
+//! Gov v1 messages and CosmWasm messages signed by the gov module, which the `cosmrs` proto definitions don't include
+
+use cosmrs::{proto::cosmos::base::v1beta1::Coin, Any};
+
+pub const MSG_SUBMIT_PROPOSAL_TYPE_URL: &str = "/cosmos.gov.v1.MsgSubmitProposal";
+pub const MSG_DEPOSIT_TYPE_URL: &str = "/cosmos.gov.v1.MsgDeposit";
+pub const MSG_VOTE_TYPE_URL: &str = "/cosmos.gov.v1.MsgVote";
+pub const QUERY_PROPOSAL_PATH: &str = "/cosmos.gov.v1.Query/Proposal";
+pub const MSG_INSTANTIATE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgInstantiateContract";
+pub const MSG_MIGRATE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgMigrateContract";
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgSubmitProposal {
+    #[prost(message, repeated, tag = "1")]
+    pub messages: Vec<Any>,
+    #[prost(message, repeated, tag = "2")]
+    pub initial_deposit: Vec<Coin>,
+    #[prost(string, tag = "3")]
+    pub proposer: String,
+    #[prost(string, tag = "4")]
+    pub metadata: String,
+    /// Since Cosmos SDK v0.47
+    #[prost(string, tag = "5")]
+    pub title: String,
+    /// Since Cosmos SDK v0.47
+    #[prost(string, tag = "6")]
+    pub summary: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgDeposit {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub depositor: String,
+    #[prost(message, repeated, tag = "3")]
+    pub amount: Vec<Coin>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgVote {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub voter: String,
+    #[prost(int32, tag = "3")]
+    pub option: i32,
+    #[prost(string, tag = "4")]
+    pub metadata: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryProposalRequest {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryProposalResponse {
+    #[prost(message, optional, tag = "1")]
+    pub proposal: Option<Proposal>,
+}
+
+/// Only the fields cw-orch reads
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Proposal {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(int32, tag = "3")]
+    pub status: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgInstantiateContract {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub admin: String,
+    #[prost(uint64, tag = "3")]
+    pub code_id: u64,
+    #[prost(string, tag = "4")]
+    pub label: String,
+    #[prost(bytes = "vec", tag = "5")]
+    pub msg: Vec<u8>,
+    #[prost(message, repeated, tag = "6")]
+    pub funds: Vec<Coin>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgMigrateContract {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub contract: String,
+    #[prost(uint64, tag = "3")]
+    pub code_id: u64,
+    #[prost(bytes = "vec", tag = "4")]
+    pub msg: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    // Encodings of the messages as produced by the Cosmos SDK and wasmd protobuf definitions
+
+    #[test]
+    fn encodes_gov_messages() {
+        let vote = MsgVote {
+            proposal_id: 1,
+            voter: "juno1v".to_string(),
+            option: 1,
+            metadata: String::new(),
+        };
+        assert_eq!(
+            hex::encode(vote.encode_to_vec()),
+            "0801\
+             12066a756e6f3176\
+             1801"
+        );
+
+        let deposit = MsgDeposit {
+            proposal_id: 1,
+            depositor: "juno1d".to_string(),
+            amount: vec![Coin {
+                denom: "ujuno".to_string(),
+                amount: "10".to_string(),
+            }],
+        };
+        assert_eq!(
+            hex::encode(deposit.encode_to_vec()),
+            "0801\
+             12066a756e6f3164\
+             1a0b0a05756a756e6f12023130"
+        );
+
+        let submit = MsgSubmitProposal {
+            messages: vec![Any {
+                type_url: "/a".to_string(),
+                value: vec![1],
+            }],
+            proposer: "juno1p".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            hex::encode(submit.encode_to_vec()),
+            "0a070a022f61120101\
+             1a066a756e6f3170"
+        );
+    }
+
+    #[test]
+    fn encodes_wasm_messages() {
+        let instantiate = MsgInstantiateContract {
+            sender: "gov".to_string(),
+            admin: String::new(),
+            code_id: 2,
+            label: "l".to_string(),
+            msg: b"{}".to_vec(),
+            funds: vec![],
+        };
+        assert_eq!(
+            hex::encode(instantiate.encode_to_vec()),
+            "0a03676f76\
+             1802\
+             22016c\
+             2a027b7d"
+        );
+
+        let migrate = MsgMigrateContract {
+            sender: "gov".to_string(),
+            contract: "c".to_string(),
+            code_id: 2,
+            msg: b"{}".to_vec(),
+        };
+        assert_eq!(
+            hex::encode(migrate.encode_to_vec()),
+            "0a03676f76\
+             120163\
+             1802\
+             22027b7d"
+        );
+    }
+}
@@ -1 +1,6 @@
+pub mod cosmos;
+pub mod cosmwasm;
+pub mod feemarket;
+pub mod gov;
 pub mod injective;
+pub mod osmosis;
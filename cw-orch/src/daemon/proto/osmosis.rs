@@ -0,0 +1,24 @@
+#![allow(missing_docs)]
+//! Osmosis `txfees` module queries
+
+pub const BASE_DENOM_PATH: &str = "/osmosis.txfees.v1beta1.Query/BaseDenom";
+pub const EIP_BASE_FEE_PATH: &str = "/osmosis.txfees.v1beta1.Query/GetEipBaseFee";
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryBaseDenomRequest {}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryBaseDenomResponse {
+    #[prost(string, tag = "1")]
+    pub base_denom: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryEipBaseFeeRequest {}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryEipBaseFeeResponse {
+    /// `LegacyDec` encoded base fee
+    #[prost(string, tag = "1")]
+    pub base_fee: String,
+}
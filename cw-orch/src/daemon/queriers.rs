@@ -4,7 +4,7 @@
 //!
 //! ## Usage
 //!
-//! You will need to acquire a [gRPC channel](Channel) to a running CosmosSDK node to be able to use the queriers.
+//! You will need to acquire a [gRPC channel](DaemonChannel) to a running CosmosSDK node to be able to use the queriers.
 //! Here is an example of how to acquire one using the DaemonAsync builder.
 //!
 //! ```no_run
@@ -53,7 +53,7 @@ mod node;
 mod staking;
 
 pub use bank::Bank;
-pub use cosmwasm::CosmWasm;
+pub use cosmwasm::{CosmWasm, MAX_CODES_SCANNED};
 pub use feegrant::Feegrant;
 pub use ibc::Ibc;
 pub use node::Node;
@@ -62,10 +62,34 @@ pub use node::Node;
 pub use gov::*;
 pub use staking::*;
 
-use tonic::transport::Channel;
+use tonic::{codec::ProstCodec, codegen::http::uri::PathAndQuery};
+
+use super::{error::DaemonError, DaemonChannel};
 
 /// Constructor for a querier over a given channel
 pub trait DaemonQuerier {
     /// Construct an new querier over a given channel
-    fn new(channel: Channel) -> Self;
+    fn new(channel: DaemonChannel) -> Self;
+}
+
+/// Perform a unary gRPC query for messages that have no generated client, like chain-specific modules.
+pub(crate) async fn raw_query<Req, Resp>(
+    channel: DaemonChannel,
+    path: &'static str,
+    request: Req,
+) -> Result<Resp, DaemonError>
+where
+    Req: prost::Message + Send + Sync + 'static,
+    Resp: prost::Message + Default + Send + Sync + 'static,
+{
+    let mut client = tonic::client::Grpc::new(channel);
+    client.ready().await?;
+    let response = client
+        .unary(
+            tonic::Request::new(request),
+            PathAndQuery::from_static(path),
+            ProstCodec::default(),
+        )
+        .await?;
+    Ok(response.into_inner())
 }
@@ -1,16 +1,15 @@
-use crate::daemon::{cosmos_modules, error::DaemonError};
+use crate::daemon::{cosmos_modules, error::DaemonError, DaemonChannel};
 use cosmrs::proto::cosmos::base::{query::v1beta1::PageRequest, v1beta1::Coin};
-use tonic::transport::Channel;
 
 use super::DaemonQuerier;
 
 /// Queries for Cosmos Bank Module
 pub struct Bank {
-    channel: Channel,
+    channel: DaemonChannel,
 }
 
 impl DaemonQuerier for Bank {
-    fn new(channel: Channel) -> Self {
+    fn new(channel: DaemonChannel) -> Self {
         Self { channel }
     }
 }
@@ -26,7 +25,7 @@ impl Bank {
         use cosmos_modules::bank::query_client::QueryClient;
         match denom {
             Some(denom) => {
-                let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+                let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
                 let request = cosmos_modules::bank::QueryBalanceRequest {
                     address: address.into(),
                     denom,
@@ -36,7 +35,7 @@ impl Bank {
                 Ok(vec![coin])
             }
             None => {
-                let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+                let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
                 let request = cosmos_modules::bank::QueryAllBalancesRequest {
                     address: address.into(),
                     ..Default::default()
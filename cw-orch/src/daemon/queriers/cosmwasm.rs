@@ -1,16 +1,23 @@
-use crate::daemon::{cosmos_modules, error::DaemonError};
+use crate::{
+    code_search::{CodeSearch, CODES_PAGE_SIZE},
+    daemon::{
+        cosmos_modules, error::DaemonError, proto::cosmwasm as cosmwasm_proto, DaemonChannel,
+    },
+    interface_traits::AccessConfig,
+};
 use cosmrs::proto::cosmos::base::query::v1beta1::PageRequest;
-use tonic::transport::Channel;
 
-use super::DaemonQuerier;
+use super::{raw_query, DaemonQuerier};
+
+pub use crate::code_search::MAX_CODES_SCANNED;
 
 /// Querier for the CosmWasm SDK module
 pub struct CosmWasm {
-    channel: Channel,
+    channel: DaemonChannel,
 }
 
 impl DaemonQuerier for CosmWasm {
-    fn new(channel: Channel) -> Self {
+    fn new(channel: DaemonChannel) -> Self {
         Self { channel }
     }
 }
@@ -19,7 +26,7 @@ impl CosmWasm {
     /// Query code_id by hash
     pub async fn code_id_hash(&self, code_id: u64) -> Result<String, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QueryCodeRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         let request = QueryCodeRequest { code_id };
         let resp = client.code(request).await?.into_inner();
         let contract_hash = resp.code_info.unwrap().data_hash;
@@ -33,7 +40,7 @@ impl CosmWasm {
         address: impl Into<String>,
     ) -> Result<cosmos_modules::cosmwasm::ContractInfo, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QueryContractInfoRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         let request = QueryContractInfoRequest {
             address: address.into(),
         };
@@ -49,7 +56,7 @@ impl CosmWasm {
         pagination: Option<PageRequest>,
     ) -> Result<cosmos_modules::cosmwasm::QueryContractHistoryResponse, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QueryContractHistoryRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         let request = QueryContractHistoryRequest {
             address: address.into(),
             pagination,
@@ -64,7 +71,7 @@ impl CosmWasm {
         query_data: Vec<u8>,
     ) -> Result<Vec<u8>, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QuerySmartContractStateRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         let request = QuerySmartContractStateRequest {
             address: address.into(),
             query_data,
@@ -83,7 +90,7 @@ impl CosmWasm {
         pagination: Option<PageRequest>,
     ) -> Result<Vec<cosmos_modules::cosmwasm::Model>, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QueryAllContractStateRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         let request = QueryAllContractStateRequest {
             address: address.into(),
             pagination,
@@ -101,28 +108,82 @@ impl CosmWasm {
         code_id: u64,
     ) -> Result<cosmos_modules::cosmwasm::CodeInfoResponse, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QueryCodeRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         let request = QueryCodeRequest { code_id };
         Ok(client.code(request).await?.into_inner().code_info.unwrap())
     }
 
+    /// Query who can instantiate the code
+    pub async fn instantiate_permission(&self, code_id: u64) -> Result<AccessConfig, DaemonError> {
+        let resp: cosmwasm_proto::QueryCodeResponse = raw_query(
+            self.channel.clone(),
+            cosmwasm_proto::QUERY_CODE_PATH,
+            cosmwasm_proto::QueryCodeRequest { code_id },
+        )
+        .await?;
+        resp.code_info
+            .and_then(|info| info.instantiate_permission)
+            .ok_or_else(|| {
+                DaemonError::StdErr(format!(
+                    "no instantiate permission found for code id {code_id}"
+                ))
+            })?
+            .try_into()
+    }
+
     /// Query codes
     pub async fn codes(
         &self,
         pagination: Option<PageRequest>,
     ) -> Result<Vec<cosmos_modules::cosmwasm::CodeInfoResponse>, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QueryCodesRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         let request = QueryCodesRequest { pagination };
         Ok(client.codes(request).await?.into_inner().code_infos)
     }
 
+    /// Query the most recent code with the hex encoded checksum, optionally restricted to codes uploaded by `creator`.
+    /// Goes through the codes on chain, newest first, until a match is found.
+    /// Only the [`MAX_CODES_SCANNED`] most recent codes are looked at, older codes are not found.
+    pub async fn code_id_by_checksum(
+        &self,
+        checksum: &str,
+        creator: Option<&str>,
+    ) -> Result<Option<u64>, DaemonError> {
+        use cosmos_modules::cosmwasm::{query_client::*, QueryCodesRequest};
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
+        let mut search = CodeSearch::new(checksum, creator);
+        while let Some(key) = search.next_page() {
+            let request = QueryCodesRequest {
+                pagination: Some(PageRequest {
+                    key,
+                    offset: 0,
+                    limit: CODES_PAGE_SIZE,
+                    count_total: false,
+                    reverse: true,
+                }),
+            };
+            let resp = client.codes(request).await?.into_inner();
+            let codes = resp.code_infos.iter().map(|code| {
+                (
+                    code.code_id,
+                    code.creator.as_str(),
+                    code.data_hash.as_slice(),
+                )
+            });
+            if let Some(code_id) = search.page(codes, resp.pagination.map(|page| page.next_key)) {
+                return Ok(Some(code_id));
+            }
+        }
+        Ok(None)
+    }
+
     /// Query pinned codes
     pub async fn pinned_codes(
         &self,
     ) -> Result<cosmos_modules::cosmwasm::QueryPinnedCodesResponse, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QueryPinnedCodesRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         let request = QueryPinnedCodesRequest { pagination: None };
         Ok(client.pinned_codes(request).await?.into_inner())
     }
@@ -133,7 +194,7 @@ impl CosmWasm {
         code_id: u64,
     ) -> Result<cosmos_modules::cosmwasm::QueryContractsByCodeResponse, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QueryContractsByCodeRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         let request = QueryContractsByCodeRequest {
             code_id,
             pagination: None,
@@ -148,7 +209,7 @@ impl CosmWasm {
         query_data: Vec<u8>,
     ) -> Result<cosmos_modules::cosmwasm::QueryRawContractStateResponse, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QueryRawContractStateRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         let request = QueryRawContractStateRequest {
             address: address.into(),
             query_data,
@@ -161,7 +222,7 @@ impl CosmWasm {
         &self,
     ) -> Result<cosmos_modules::cosmwasm::QueryParamsResponse, DaemonError> {
         use cosmos_modules::cosmwasm::{query_client::*, QueryParamsRequest};
-        let mut client: QueryClient<Channel> = QueryClient::new(self.channel.clone());
+        let mut client: QueryClient<DaemonChannel> = QueryClient::new(self.channel.clone());
         Ok(client.params(QueryParamsRequest {}).await?.into_inner())
     }
 }
@@ -1,16 +1,15 @@
-use crate::daemon::{cosmos_modules, error::DaemonError};
+use crate::daemon::{cosmos_modules, error::DaemonError, DaemonChannel};
 use cosmrs::proto::cosmos::base::query::v1beta1::PageRequest;
-use tonic::transport::Channel;
 
 use super::DaemonQuerier;
 
 /// Querier for the Cosmos Gov module
 pub struct Gov {
-    channel: Channel,
+    channel: DaemonChannel,
 }
 
 impl DaemonQuerier for Gov {
-    fn new(channel: Channel) -> Self {
+    fn new(channel: DaemonChannel) -> Self {
         Self { channel }
     }
 }
@@ -167,6 +166,7 @@ impl Gov {
 
 /// Proposal status
 #[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GovProposalStatus {
     Unspecified = 0,
     DepositPeriod = 1,
@@ -175,3 +175,22 @@ pub enum GovProposalStatus {
     Rejected = 4,
     Failed = 5,
 }
+
+impl GovProposalStatus {
+    /// Status from its proto value, unknown values are `Unspecified`
+    pub fn from_i32(status: i32) -> Self {
+        match status {
+            1 => Self::DepositPeriod,
+            2 => Self::VotingPeriod,
+            3 => Self::Passed,
+            4 => Self::Rejected,
+            5 => Self::Failed,
+            _ => Self::Unspecified,
+        }
+    }
+
+    /// Whether the proposal can't change status anymore
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Passed | Self::Rejected | Self::Failed)
+    }
+}
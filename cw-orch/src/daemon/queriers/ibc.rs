@@ -1,5 +1,5 @@
 use super::DaemonQuerier;
-use crate::daemon::{cosmos_modules, error::DaemonError};
+use crate::daemon::{cosmos_modules, error::DaemonError, DaemonChannel};
 use cosmos_modules::ibc_channel;
 use cosmrs::proto::ibc::{
     applications::transfer::v1::{DenomTrace, QueryDenomTraceResponse},
@@ -11,15 +11,14 @@ use cosmrs::proto::ibc::{
     lightclients::tendermint::v1::ClientState,
 };
 use prost::Message;
-use tonic::transport::Channel;
 
 /// Querier for the Cosmos IBC module
 pub struct Ibc {
-    channel: Channel,
+    channel: DaemonChannel,
 }
 
 impl DaemonQuerier for Ibc {
-    fn new(channel: Channel) -> Self {
+    fn new(channel: DaemonChannel) -> Self {
         Self { channel }
     }
 }
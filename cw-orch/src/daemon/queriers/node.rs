@@ -1,25 +1,27 @@
 use std::{cmp::min, time::Duration};
 
-use crate::daemon::{cosmos_modules, error::DaemonError, tx_resp::CosmTxResponse};
+use crate::daemon::{
+    cosmos_modules, error::DaemonError, proto::cosmos as cosmos_proto, tx_resp::CosmTxResponse,
+    DaemonChannel,
+};
 
 use cosmrs::{
     proto::cosmos::{base::query::v1beta1::PageRequest, tx::v1beta1::SimulateResponse},
     tendermint::{Block, Time},
 };
-use tonic::transport::Channel;
 
-use super::DaemonQuerier;
+use super::{raw_query, DaemonQuerier};
 
 const MAX_TX_QUERY_RETRIES: usize = 50;
 
 /// Querier for the Tendermint node.
 /// Supports queries for block and tx information
 pub struct Node {
-    channel: Channel,
+    channel: DaemonChannel,
 }
 
 impl DaemonQuerier for Node {
-    fn new(channel: Channel) -> Self {
+    fn new(channel: DaemonChannel) -> Self {
         Self { channel }
     }
 }
@@ -40,6 +42,18 @@ impl Node {
         Ok(resp)
     }
 
+    /// Returns the minimum gas prices configured on the node, ex. `0.025ujuno,0.001uatom`
+    pub async fn min_gas_prices(&self) -> Result<String, DaemonError> {
+        let resp: cosmos_proto::ConfigResponse = raw_query(
+            self.channel.clone(),
+            cosmos_proto::NODE_CONFIG_PATH,
+            cosmos_proto::ConfigRequest {},
+        )
+        .await?;
+
+        Ok(resp.minimum_gas_price)
+    }
+
     /// Queries node syncing
     pub async fn syncing(&self) -> Result<bool, DaemonError> {
         let mut client =
@@ -201,6 +215,20 @@ impl Node {
         self.find_tx_with_retries(hash, MAX_TX_QUERY_RETRIES).await
     }
 
+    /// Query a TX by hash once, errors if the TX is not (yet) included in a block
+    pub async fn tx(&self, hash: String) -> Result<CosmTxResponse, DaemonError> {
+        let mut client =
+            cosmos_modules::tx::service_client::ServiceClient::new(self.channel.clone());
+
+        let resp = client
+            .get_tx(cosmos_modules::tx::GetTxRequest { hash: hash.clone() })
+            .await?
+            .into_inner();
+        resp.tx_response
+            .map(Into::into)
+            .ok_or(DaemonError::TXNotFound(hash, 1))
+    }
+
     /// Find TX by hash with a given amount of retries
     pub async fn find_tx_with_retries(
         &self,
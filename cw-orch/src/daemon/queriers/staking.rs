@@ -1,16 +1,15 @@
-use crate::daemon::{cosmos_modules, error::DaemonError};
+use crate::daemon::{cosmos_modules, error::DaemonError, DaemonChannel};
 use cosmrs::proto::cosmos::base::query::v1beta1::PageRequest;
-use tonic::transport::Channel;
 
 use super::DaemonQuerier;
 
 /// Querier for the Cosmos Staking module
 pub struct Staking {
-    channel: Channel,
+    channel: DaemonChannel,
 }
 
 impl DaemonQuerier for Staking {
-    fn new(channel: Channel) -> Self {
+    fn new(channel: DaemonChannel) -> Self {
         Self { channel }
     }
 }
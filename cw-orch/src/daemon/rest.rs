@@ -0,0 +1,1232 @@
+//! Bridge that serves gRPC calls from the Cosmos REST gateway (LCD).
+//!
+//! The bridge is exposed as a [`DaemonChannel`] so the queriers, simulation and broadcasting work unchanged.
+//! Calls are served in-process: their protobuf request is translated to a REST request and the JSON response back to protobuf.
+//! The bank and wasm queries used by the queriers, the tx service and the tendermint node service are mapped onto their REST routes.
+//! Other module queries are forwarded to the `abci_query` endpoint, which routes them to the same query services as gRPC.
+
+use std::{sync::Arc, time::Instant};
+
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE},
+    Engine,
+};
+use cosmrs::{
+    proto::{
+        cosmos::base::query::v1beta1::{PageRequest, PageResponse},
+        tendermint::{
+            google::protobuf::Timestamp,
+            types::{Block, BlockId, Commit, CommitSig, Data, EvidenceList, Header, PartSetHeader},
+            version::Consensus,
+        },
+    },
+    tendermint::Time,
+};
+use ibc_chain_registry::chain::Rest;
+use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+use prost::{
+    bytes::{Buf, BufMut},
+    Message,
+};
+use serde_json::{json, Value};
+use tonic::{
+    body::BoxBody,
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    codegen::{http, BoxFuture},
+    server::{Grpc, UnaryService},
+    Code, Status,
+};
+
+use super::{
+    channel::{DaemonChannel, EndpointTransport, GrpcEndpoints, GrpcPolicy, RankedEndpoint},
+    cosmos_modules::{
+        abci::{self, AbciMessageLog, Attribute, GasInfo, StringEvent, TxMsgData, TxResponse},
+        bank::{
+            QueryAllBalancesRequest, QueryAllBalancesResponse, QueryBalanceRequest,
+            QueryBalanceResponse,
+        },
+        base::Coin,
+        cosmwasm::{
+            AbsoluteTxPosition, ContractInfo, QueryContractInfoRequest, QueryContractInfoResponse,
+            QueryRawContractStateRequest, QueryRawContractStateResponse,
+            QuerySmartContractStateRequest, QuerySmartContractStateResponse,
+        },
+        tendermint::{
+            GetBlockByHeightRequest, GetBlockByHeightResponse, GetLatestBlockResponse,
+            GetNodeInfoResponse, GetSyncingResponse,
+        },
+        tendermint_abci::{Event, EventAttribute},
+        tx::{
+            AuthInfo, BroadcastTxRequest, BroadcastTxResponse, GetTxRequest, GetTxResponse,
+            SimulateRequest, SimulateResponse, Tx, TxBody, TxRaw,
+        },
+    },
+    error::DaemonError,
+    proto::cosmwasm::{
+        AccessConfig, CodeInfoResponse, QueryCodeRequest, QueryCodeResponse,
+        ACCESS_TYPE_ANY_OF_ADDRESSES, ACCESS_TYPE_EVERYBODY, ACCESS_TYPE_NOBODY,
+        ACCESS_TYPE_ONLY_ADDRESS, QUERY_CODE_PATH,
+    },
+};
+
+const GET_NODE_INFO: &str = "/cosmos.base.tendermint.v1beta1.Service/GetNodeInfo";
+const GET_SYNCING: &str = "/cosmos.base.tendermint.v1beta1.Service/GetSyncing";
+const GET_LATEST_BLOCK: &str = "/cosmos.base.tendermint.v1beta1.Service/GetLatestBlock";
+const GET_BLOCK_BY_HEIGHT: &str = "/cosmos.base.tendermint.v1beta1.Service/GetBlockByHeight";
+const SIMULATE: &str = "/cosmos.tx.v1beta1.Service/Simulate";
+const BROADCAST_TX: &str = "/cosmos.tx.v1beta1.Service/BroadcastTx";
+const GET_TX: &str = "/cosmos.tx.v1beta1.Service/GetTx";
+const BANK_BALANCE: &str = "/cosmos.bank.v1beta1.Query/Balance";
+const BANK_ALL_BALANCES: &str = "/cosmos.bank.v1beta1.Query/AllBalances";
+const WASM_CONTRACT_INFO: &str = "/cosmwasm.wasm.v1.Query/ContractInfo";
+const WASM_SMART_STATE: &str = "/cosmwasm.wasm.v1.Query/SmartContractState";
+const WASM_RAW_STATE: &str = "/cosmwasm.wasm.v1.Query/RawContractState";
+/// Services that can't be routed through `abci_query`, only the calls above are supported
+const NON_ABCI_SERVICES: [&str; 2] = [
+    "/cosmos.base.tendermint.v1beta1.Service/",
+    "/cosmos.tx.v1beta1.Service/",
+];
+
+/// A helper for constructing a [`DaemonChannel`] that talks to the Cosmos REST gateway (LCD) instead of gRPC.
+///
+/// Supported calls are all module queries (bank, wasm, staking, ...),
+/// the tx service's `Simulate`, `BroadcastTx` and `GetTx`
+/// and the tendermint service's `GetNodeInfo`, `GetSyncing`, `GetLatestBlock` and `GetBlockByHeight`.
+/// Other calls of the tx and tendermint services fail with [`Code::Unimplemented`].
+///
+/// The bank balance queries and the wasm `Code`, `ContractInfo`, `SmartContractState` and `RawContractState` queries
+/// use their REST routes. Other module queries require the `abci_query` endpoint of the gateway,
+/// available from Cosmos SDK v0.47. On older nodes they fail with [`Code::Unimplemented`].
+///
+/// The `tx` of `GetTx` is read from the block that includes it, it's left out if that block can't be queried.
+pub struct RestChannel {}
+
+impl RestChannel {
+    /// Connect to any of the provided REST endpoints
+    pub async fn connect(rest: &[Rest], chain_id: &ChainId) -> Result<DaemonChannel, DaemonError> {
+        Self::connect_with_policy(rest, chain_id, GrpcPolicy::default())
+            .await
+            .map(|endpoints| endpoints.channel())
+    }
+
+    /// Health-check all the provided REST endpoints and rank the healthy ones by latency.
+    /// The returned channel sends its calls to the fastest endpoint and fails over to the next one on connection errors.
+    pub async fn connect_with_policy(
+        rest: &[Rest],
+        chain_id: &ChainId,
+        policy: GrpcPolicy,
+    ) -> Result<GrpcEndpoints, DaemonError> {
+        let client = reqwest::Client::new();
+        let mut healthy = vec![];
+
+        for Rest { address, .. } in rest.iter() {
+            log::info!("Trying to connect to REST endpoint: {}", address);
+            let address = address.trim_end_matches('/').to_string();
+
+            let start = Instant::now();
+            let check = tokio::time::timeout(
+                policy.health_check_timeout,
+                health_check(&client, &address, chain_id),
+            )
+            .await;
+
+            match check {
+                Ok(true) => healthy.push(RankedEndpoint {
+                    transport: EndpointTransport::Rest(Arc::new(RestGateway {
+                        client: client.clone(),
+                        url: address.clone(),
+                    })),
+                    address,
+                    latency: start.elapsed(),
+                    headers: Default::default(),
+                }),
+                Ok(false) => continue,
+                Err(_) => log::warn!(
+                    "REST endpoint {} did not respond within {:?}",
+                    address,
+                    policy.health_check_timeout
+                ),
+            }
+        }
+
+        if healthy.is_empty() {
+            return Err(DaemonError::CannotConnectREST);
+        }
+
+        healthy.sort_by_key(|endpoint| endpoint.latency);
+        log::info!(
+            "Connected to REST endpoint {} ({:?})",
+            healthy[0].address,
+            healthy[0].latency
+        );
+
+        Ok(GrpcEndpoints::new(healthy, policy))
+    }
+}
+
+/// Returns whether the node at `address` is reachable and serves the expected chain.
+async fn health_check(client: &reqwest::Client, address: &str, chain_id: &ChainId) -> bool {
+    let resp = client
+        .get(format!(
+            "{address}/cosmos/base/tendermint/v1beta1/node_info"
+        ))
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status());
+    let body = match resp {
+        Ok(resp) => resp.bytes().await,
+        Err(e) => Err(e),
+    };
+    let node_info: Value = match body.map(|body| serde_json::from_slice(&body)) {
+        Ok(Ok(node_info)) => node_info,
+        Ok(Err(e)) => {
+            log::warn!("Invalid node info from {}, {:?}", address, e);
+            return false;
+        }
+        Err(e) => {
+            log::warn!("Cannot connect to REST endpoint: {}, {:?}", address, e);
+            return false;
+        }
+    };
+
+    let network = string(&node_info["default_node_info"], "network");
+    // local juno does not return a proper ChainId with epoch format
+    if ChainId::is_epoch_format(&network) && network != chain_id.as_str() {
+        log::error!(
+            "Network mismatch: connection:{} != config:{}",
+            network,
+            chain_id.as_str()
+        );
+        return false;
+    }
+    true
+}
+
+/// Sends the calls of the bridge to a REST endpoint
+#[derive(Debug)]
+pub(crate) struct RestGateway {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl RestGateway {
+    /// Serve the gRPC request from the REST endpoint
+    pub(crate) async fn serve(
+        self: &Arc<Self>,
+        req: http::Request<BoxBody>,
+    ) -> http::Response<BoxBody> {
+        let call = BridgeCall {
+            gateway: self.clone(),
+            path: req.uri().path().to_string(),
+        };
+        Grpc::new(RawCodec).unary(call, req).await
+    }
+
+    fn url(&self, route: &str) -> String {
+        format!("{}{route}", self.url)
+    }
+
+    /// Serve the gRPC call to `path` with the protobuf encoded `request`
+    async fn call(&self, path: &str, request: Vec<u8>) -> Result<Vec<u8>, Status> {
+        let response = match path {
+            GET_NODE_INFO => {
+                let json = self
+                    .get("/cosmos/base/tendermint/v1beta1/node_info")
+                    .await?;
+                node_info_from_json(&json).encode_to_vec()
+            }
+            GET_SYNCING => {
+                let json = self.get("/cosmos/base/tendermint/v1beta1/syncing").await?;
+                GetSyncingResponse {
+                    syncing: json["syncing"].as_bool().unwrap_or_default(),
+                }
+                .encode_to_vec()
+            }
+            GET_LATEST_BLOCK => {
+                let json = self
+                    .get("/cosmos/base/tendermint/v1beta1/blocks/latest")
+                    .await?;
+                GetLatestBlockResponse {
+                    block_id: Some(block_id_from_json(&json["block_id"])?),
+                    block: Some(block_from_json(&json["block"])?),
+                    ..Default::default()
+                }
+                .encode_to_vec()
+            }
+            GET_BLOCK_BY_HEIGHT => {
+                let request: GetBlockByHeightRequest = decode(&request)?;
+                let json = self
+                    .get(&format!(
+                        "/cosmos/base/tendermint/v1beta1/blocks/{}",
+                        request.height
+                    ))
+                    .await?;
+                GetBlockByHeightResponse {
+                    block_id: Some(block_id_from_json(&json["block_id"])?),
+                    block: Some(block_from_json(&json["block"])?),
+                    ..Default::default()
+                }
+                .encode_to_vec()
+            }
+            SIMULATE => {
+                let request: SimulateRequest = decode(&request)?;
+                let json = self
+                    .post(
+                        "/cosmos/tx/v1beta1/simulate",
+                        json!({ "tx_bytes": STANDARD.encode(request.tx_bytes) }),
+                    )
+                    .await?;
+                let result = &json["result"];
+                let data = bytes(result, "data")?;
+                // the data holds the protobuf encoded msg responses, which are only listed as JSON otherwise
+                let msg_responses = TxMsgData::decode(data.as_slice())
+                    .map(|data| data.msg_responses)
+                    .unwrap_or_default();
+                #[allow(deprecated)]
+                let response = SimulateResponse {
+                    gas_info: Some(GasInfo {
+                        gas_wanted: int(&json["gas_info"], "gas_wanted") as u64,
+                        gas_used: int(&json["gas_info"], "gas_used") as u64,
+                    }),
+                    result: Some(abci::Result {
+                        data,
+                        log: string(result, "log"),
+                        events: events_from_json(&result["events"]),
+                        msg_responses,
+                    }),
+                };
+                response.encode_to_vec()
+            }
+            BROADCAST_TX => {
+                let request: BroadcastTxRequest = decode(&request)?;
+                let mode = match request.mode {
+                    1 => "BROADCAST_MODE_BLOCK",
+                    3 => "BROADCAST_MODE_ASYNC",
+                    _ => "BROADCAST_MODE_SYNC",
+                };
+                let json = self
+                    .post(
+                        "/cosmos/tx/v1beta1/txs",
+                        json!({ "tx_bytes": STANDARD.encode(request.tx_bytes), "mode": mode }),
+                    )
+                    .await?;
+                BroadcastTxResponse {
+                    tx_response: Some(tx_response_from_json(&json["tx_response"])),
+                }
+                .encode_to_vec()
+            }
+            GET_TX => {
+                let request: GetTxRequest = decode(&request)?;
+                let json = self
+                    .get(&format!("/cosmos/tx/v1beta1/txs/{}", request.hash))
+                    .await?;
+                let tx_response = tx_response_from_json(&json["tx_response"]);
+                let tx = match self.tx_from_block(&tx_response).await {
+                    Ok(tx) => tx,
+                    Err(e) => {
+                        log::debug!("Can't read tx {} from its block: {}", request.hash, e);
+                        None
+                    }
+                };
+                GetTxResponse {
+                    tx,
+                    tx_response: Some(tx_response),
+                }
+                .encode_to_vec()
+            }
+            path if NON_ABCI_SERVICES
+                .iter()
+                .any(|service| path.starts_with(service)) =>
+            {
+                return Err(Status::unimplemented(format!(
+                    "{path} is not supported over REST"
+                )))
+            }
+            path => match self.module_query(path, &request).await? {
+                Some(response) => response,
+                None => self.abci_query(path, request).await?,
+            },
+        };
+        Ok(response)
+    }
+
+    /// Serve a module query from its REST route, `None` if it has no route mapped here
+    async fn module_query(&self, path: &str, request: &[u8]) -> Result<Option<Vec<u8>>, Status> {
+        let response = match path {
+            BANK_BALANCE => {
+                let request: QueryBalanceRequest = decode(request)?;
+                let route = format!("/cosmos/bank/v1beta1/balances/{}/by_denom", request.address);
+                let json = self
+                    .get_with_query(&route, &[("denom".to_string(), request.denom)])
+                    .await?;
+                QueryBalanceResponse {
+                    balance: coin_from_json(&json["balance"]),
+                }
+                .encode_to_vec()
+            }
+            BANK_ALL_BALANCES => {
+                let request: QueryAllBalancesRequest = decode(request)?;
+                let route = format!("/cosmos/bank/v1beta1/balances/{}", request.address);
+                let json = self
+                    .get_with_query(&route, &pagination_query(request.pagination))
+                    .await?;
+                QueryAllBalancesResponse {
+                    balances: json["balances"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(coin_from_json)
+                        .collect(),
+                    pagination: page_response_from_json(&json["pagination"])?,
+                }
+                .encode_to_vec()
+            }
+            QUERY_CODE_PATH => {
+                let request: QueryCodeRequest = decode(request)?;
+                let json = self
+                    .get(&format!("/cosmwasm/wasm/v1/code/{}", request.code_id))
+                    .await?;
+                let code_info = &json["code_info"];
+                QueryCodeResponse {
+                    code_info: Some(CodeInfoResponse {
+                        code_id: int(code_info, "code_id") as u64,
+                        creator: string(code_info, "creator"),
+                        // the checksum is hex encoded
+                        data_hash: hex::decode(string(code_info, "data_hash")).map_err(|e| {
+                            Status::internal(format!("invalid hex in field data_hash: {e}"))
+                        })?,
+                        instantiate_permission: access_config_from_json(
+                            &code_info["instantiate_permission"],
+                        ),
+                    }),
+                    data: bytes(&json, "data")?,
+                }
+                .encode_to_vec()
+            }
+            WASM_CONTRACT_INFO => {
+                let request: QueryContractInfoRequest = decode(request)?;
+                let json = self
+                    .get(&format!("/cosmwasm/wasm/v1/contract/{}", request.address))
+                    .await?;
+                let info = &json["contract_info"];
+                QueryContractInfoResponse {
+                    address: string(&json, "address"),
+                    contract_info: Some(ContractInfo {
+                        code_id: int(info, "code_id") as u64,
+                        creator: string(info, "creator"),
+                        admin: string(info, "admin"),
+                        label: string(info, "label"),
+                        created: match &info["created"] {
+                            Value::Null => None,
+                            created => Some(AbsoluteTxPosition {
+                                block_height: int(created, "block_height") as u64,
+                                tx_index: int(created, "tx_index") as u64,
+                            }),
+                        },
+                        ibc_port_id: string(info, "ibc_port_id"),
+                        extension: None,
+                    }),
+                }
+                .encode_to_vec()
+            }
+            WASM_SMART_STATE => {
+                let request: QuerySmartContractStateRequest = decode(request)?;
+                let json = self
+                    .get(&format!(
+                        "/cosmwasm/wasm/v1/contract/{}/smart/{}",
+                        request.address,
+                        URL_SAFE.encode(request.query_data)
+                    ))
+                    .await?;
+                // the contract's response is embedded as JSON
+                QuerySmartContractStateResponse {
+                    data: json["data"].to_string().into_bytes(),
+                }
+                .encode_to_vec()
+            }
+            WASM_RAW_STATE => {
+                let request: QueryRawContractStateRequest = decode(request)?;
+                let json = self
+                    .get(&format!(
+                        "/cosmwasm/wasm/v1/contract/{}/raw/{}",
+                        request.address,
+                        URL_SAFE.encode(request.query_data)
+                    ))
+                    .await?;
+                QueryRawContractStateResponse {
+                    data: bytes(&json, "data")?,
+                }
+                .encode_to_vec()
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(response))
+    }
+
+    /// Find the tx of the response in the block that includes it
+    async fn tx_from_block(&self, tx_response: &TxResponse) -> Result<Option<Tx>, Status> {
+        let json = self
+            .get(&format!(
+                "/cosmos/base/tendermint/v1beta1/blocks/{}",
+                tx_response.height
+            ))
+            .await?;
+        let block = block_from_json(&json["block"])?;
+        let Some(raw) = block.data.and_then(|data| {
+            data.txs
+                .into_iter()
+                .find(|tx| sha256::digest(tx.as_slice()).eq_ignore_ascii_case(&tx_response.txhash))
+        }) else {
+            return Ok(None);
+        };
+        let raw: TxRaw = decode(&raw)?;
+        Ok(Some(Tx {
+            body: Some(decode::<TxBody>(&raw.body_bytes)?),
+            auth_info: Some(decode::<AuthInfo>(&raw.auth_info_bytes)?),
+            signatures: raw.signatures,
+        }))
+    }
+
+    /// Run a module query through the node's ABCI query endpoint, available from Cosmos SDK v0.47
+    async fn abci_query(&self, path: &str, data: Vec<u8>) -> Result<Vec<u8>, Status> {
+        let resp = self
+            .client
+            .get(self.url("/cosmos/base/tendermint/v1beta1/abci_query"))
+            .query(&[("path", path.to_string()), ("data", STANDARD.encode(data))]);
+        let json = self.send(resp).await.map_err(|status| {
+            // nodes before v0.47 don't have the route
+            if matches!(status.code(), Code::NotFound | Code::Unimplemented) {
+                Status::unimplemented(format!(
+                    "{path} needs the abci_query endpoint of the REST gateway, available from Cosmos SDK v0.47"
+                ))
+            } else {
+                status
+            }
+        })?;
+
+        if int(&json, "code") != 0 {
+            return Err(Status::unknown(string(&json, "log")));
+        }
+        bytes(&json, "value")
+    }
+
+    async fn get(&self, route: &str) -> Result<Value, Status> {
+        self.send(self.client.get(self.url(route))).await
+    }
+
+    async fn get_with_query(
+        &self,
+        route: &str,
+        query: &[(String, String)],
+    ) -> Result<Value, Status> {
+        self.send(self.client.get(self.url(route)).query(query))
+            .await
+    }
+
+    async fn post(&self, route: &str, body: Value) -> Result<Value, Status> {
+        let request = self
+            .client
+            .post(self.url(route))
+            .header("content-type", "application/json")
+            .body(body.to_string());
+        self.send(request).await
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value, Status> {
+        let resp = request.send().await.map_err(|e| {
+            if e.is_connect() || e.is_timeout() {
+                Status::unavailable(e.to_string())
+            } else {
+                Status::unknown(e.to_string())
+            }
+        })?;
+        let status = resp.status();
+        let body = resp
+            .bytes()
+            .await
+            .map_err(|e| Status::unavailable(e.to_string()))?;
+        let json: Value = match serde_json::from_slice(&body) {
+            Ok(json) => json,
+            // routes the gateway doesn't know are answered without a JSON body
+            Err(_) if status == reqwest::StatusCode::NOT_FOUND => {
+                return Err(Status::unimplemented(
+                    "route not served by the REST gateway",
+                ))
+            }
+            Err(e) => return Err(Status::internal(format!("invalid REST response: {e}"))),
+        };
+
+        if !status.is_success() {
+            // the gateway returns the gRPC status code and message of the failed call
+            let code = Code::from_i32(int(&json, "code") as i32);
+            let code = if code == Code::Ok {
+                Code::Unknown
+            } else {
+                code
+            };
+            return Err(Status::new(code, string(&json, "message")));
+        }
+        Ok(json)
+    }
+}
+
+/// A single gRPC call that is served by the gateway
+struct BridgeCall {
+    gateway: Arc<RestGateway>,
+    path: String,
+}
+
+impl UnaryService<Vec<u8>> for BridgeCall {
+    type Response = Vec<u8>;
+    type Future = BoxFuture<tonic::Response<Vec<u8>>, Status>;
+
+    fn call(&mut self, request: tonic::Request<Vec<u8>>) -> Self::Future {
+        let gateway = self.gateway.clone();
+        let path = self.path.clone();
+        Box::pin(async move {
+            gateway
+                .call(&path, request.into_inner())
+                .await
+                .map(tonic::Response::new)
+        })
+    }
+}
+
+/// Codec that passes the protobuf encoded messages through, they are decoded per call
+#[derive(Clone, Copy, Default)]
+pub(crate) struct RawCodec;
+
+impl Codec for RawCodec {
+    type Encode = Vec<u8>;
+    type Decode = Vec<u8>;
+    type Encoder = RawCodec;
+    type Decoder = RawCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        RawCodec
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        RawCodec
+    }
+}
+
+impl Encoder for RawCodec {
+    type Item = Vec<u8>;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put_slice(&item);
+        Ok(())
+    }
+}
+
+impl Decoder for RawCodec {
+    type Item = Vec<u8>;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(Some(src.copy_to_bytes(src.remaining()).to_vec()))
+    }
+}
+
+/*
+    Conversion of the gateway's JSON (proto3 JSON mapping) to the protobuf messages
+*/
+
+fn decode<M: Message + Default>(bytes: &[u8]) -> Result<M, Status> {
+    M::decode(bytes).map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+fn string(json: &Value, key: &str) -> String {
+    json[key].as_str().unwrap_or_default().to_string()
+}
+
+/// 64-bit integers are encoded as strings, smaller ones as numbers
+fn int(json: &Value, key: &str) -> i64 {
+    match &json[key] {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        value => value.as_i64().unwrap_or_default(),
+    }
+}
+
+fn bytes(json: &Value, key: &str) -> Result<Vec<u8>, Status> {
+    match json[key].as_str() {
+        Some(encoded) => STANDARD
+            .decode(encoded)
+            .map_err(|e| Status::internal(format!("invalid base64 in field {key}: {e}"))),
+        None => Ok(vec![]),
+    }
+}
+
+fn timestamp(json: &Value, key: &str) -> Result<Option<Timestamp>, Status> {
+    match json[key].as_str() {
+        Some(time) => Time::parse_from_rfc3339(time)
+            .map(|time| Some(time.into()))
+            .map_err(|e| Status::internal(format!("invalid time in field {key}: {e}"))),
+        None => Ok(None),
+    }
+}
+
+fn coin_from_json(json: &Value) -> Option<Coin> {
+    json.is_object().then(|| Coin {
+        denom: string(json, "denom"),
+        amount: string(json, "amount"),
+    })
+}
+
+/// Query parameters of the pagination of a request
+fn pagination_query(pagination: Option<PageRequest>) -> Vec<(String, String)> {
+    let Some(pagination) = pagination else {
+        return vec![];
+    };
+    let mut query = vec![];
+    if !pagination.key.is_empty() {
+        query.push((
+            "pagination.key".to_string(),
+            STANDARD.encode(pagination.key),
+        ));
+    }
+    if pagination.offset != 0 {
+        query.push((
+            "pagination.offset".to_string(),
+            pagination.offset.to_string(),
+        ));
+    }
+    if pagination.limit != 0 {
+        query.push(("pagination.limit".to_string(), pagination.limit.to_string()));
+    }
+    if pagination.count_total {
+        query.push(("pagination.count_total".to_string(), "true".to_string()));
+    }
+    if pagination.reverse {
+        query.push(("pagination.reverse".to_string(), "true".to_string()));
+    }
+    query
+}
+
+fn page_response_from_json(json: &Value) -> Result<Option<PageResponse>, Status> {
+    if json.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(PageResponse {
+        next_key: bytes(json, "next_key")?,
+        total: int(json, "total") as u64,
+    }))
+}
+
+/// Access types are named as in the protobuf enum, or as in amino JSON by older wasmd versions
+fn access_config_from_json(json: &Value) -> Option<AccessConfig> {
+    if json.is_null() {
+        return None;
+    }
+    let permission = match json["permission"].as_str().unwrap_or_default() {
+        "ACCESS_TYPE_NOBODY" | "Nobody" => ACCESS_TYPE_NOBODY,
+        "ACCESS_TYPE_ONLY_ADDRESS" | "OnlyAddress" => ACCESS_TYPE_ONLY_ADDRESS,
+        "ACCESS_TYPE_EVERYBODY" | "Everybody" => ACCESS_TYPE_EVERYBODY,
+        "ACCESS_TYPE_ANY_OF_ADDRESSES" | "AnyOfAddresses" => ACCESS_TYPE_ANY_OF_ADDRESSES,
+        _ => int(json, "permission") as i32,
+    };
+    Some(AccessConfig {
+        permission,
+        address: string(json, "address"),
+        addresses: json["addresses"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|address| address.as_str().map(ToString::to_string))
+            .collect(),
+    })
+}
+
+fn node_info_from_json(json: &Value) -> GetNodeInfoResponse {
+    let mut response = GetNodeInfoResponse::default();
+    response.default_node_info = Some(Default::default());
+    response.application_version = Some(Default::default());
+    if let Some(info) = response.default_node_info.as_mut() {
+        let node_info = &json["default_node_info"];
+        info.default_node_id = string(node_info, "default_node_id");
+        info.listen_addr = string(node_info, "listen_addr");
+        info.network = string(node_info, "network");
+        info.version = string(node_info, "version");
+        info.moniker = string(node_info, "moniker");
+    }
+    if let Some(version) = response.application_version.as_mut() {
+        let app_version = &json["application_version"];
+        version.name = string(app_version, "name");
+        version.app_name = string(app_version, "app_name");
+        version.version = string(app_version, "version");
+        version.git_commit = string(app_version, "git_commit");
+        version.cosmos_sdk_version = string(app_version, "cosmos_sdk_version");
+    }
+    response
+}
+
+fn block_id_from_json(json: &Value) -> Result<BlockId, Status> {
+    let part_set_header = &json["part_set_header"];
+    Ok(BlockId {
+        hash: bytes(json, "hash")?,
+        part_set_header: Some(PartSetHeader {
+            total: int(part_set_header, "total") as u32,
+            hash: bytes(part_set_header, "hash")?,
+        }),
+    })
+}
+
+fn block_from_json(json: &Value) -> Result<Block, Status> {
+    let header = &json["header"];
+    let header = Header {
+        version: Some(Consensus {
+            block: int(&header["version"], "block") as u64,
+            app: int(&header["version"], "app") as u64,
+        }),
+        chain_id: string(header, "chain_id"),
+        height: int(header, "height"),
+        time: timestamp(header, "time")?,
+        last_block_id: Some(block_id_from_json(&header["last_block_id"])?),
+        last_commit_hash: bytes(header, "last_commit_hash")?,
+        data_hash: bytes(header, "data_hash")?,
+        validators_hash: bytes(header, "validators_hash")?,
+        next_validators_hash: bytes(header, "next_validators_hash")?,
+        consensus_hash: bytes(header, "consensus_hash")?,
+        app_hash: bytes(header, "app_hash")?,
+        last_results_hash: bytes(header, "last_results_hash")?,
+        evidence_hash: bytes(header, "evidence_hash")?,
+        proposer_address: bytes(header, "proposer_address")?,
+    };
+
+    let txs = json["data"]["txs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|tx| {
+            STANDARD
+                .decode(tx.as_str().unwrap_or_default())
+                .map_err(|e| Status::internal(format!("invalid base64 tx: {e}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let last_commit = match &json["last_commit"] {
+        Value::Null => None,
+        commit => Some(Commit {
+            height: int(commit, "height"),
+            round: int(commit, "round") as i32,
+            block_id: Some(block_id_from_json(&commit["block_id"])?),
+            signatures: commit["signatures"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|signature| {
+                    Ok(CommitSig {
+                        block_id_flag: match signature["block_id_flag"].as_str() {
+                            Some("BLOCK_ID_FLAG_ABSENT") => 1,
+                            Some("BLOCK_ID_FLAG_COMMIT") => 2,
+                            Some("BLOCK_ID_FLAG_NIL") => 3,
+                            _ => 0,
+                        },
+                        validator_address: bytes(signature, "validator_address")?,
+                        timestamp: timestamp(signature, "timestamp")?,
+                        signature: bytes(signature, "signature")?,
+                    })
+                })
+                .collect::<Result<Vec<_>, Status>>()?,
+        }),
+    };
+
+    Ok(Block {
+        header: Some(header),
+        data: Some(Data { txs }),
+        // evidence is not needed by the queriers
+        evidence: Some(EvidenceList { evidence: vec![] }),
+        last_commit,
+    })
+}
+
+fn tx_response_from_json(json: &Value) -> TxResponse {
+    let logs = json["logs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|log| AbciMessageLog {
+            msg_index: int(log, "msg_index") as u32,
+            log: string(log, "log"),
+            events: log["events"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|event| StringEvent {
+                    r#type: string(event, "type"),
+                    attributes: event["attributes"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .map(|attr| Attribute {
+                            key: string(attr, "key"),
+                            value: string(attr, "value"),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    TxResponse {
+        height: int(json, "height"),
+        txhash: string(json, "txhash"),
+        codespace: string(json, "codespace"),
+        code: int(json, "code") as u32,
+        data: string(json, "data"),
+        raw_log: string(json, "raw_log"),
+        logs,
+        info: string(json, "info"),
+        gas_wanted: int(json, "gas_wanted"),
+        gas_used: int(json, "gas_used"),
+        tx: None,
+        timestamp: string(json, "timestamp"),
+        events: events_from_json(&json["events"]),
+    }
+}
+
+fn events_from_json(json: &Value) -> Vec<Event> {
+    json.as_array()
+        .into_iter()
+        .flatten()
+        .map(|event| Event {
+            r#type: string(event, "type"),
+            attributes: event["attributes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|attr| EventAttribute {
+                    key: string(attr, "key"),
+                    value: string(attr, "value"),
+                    index: attr["index"].as_bool().unwrap_or_default(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DaemonChannel, RestChannel};
+    use crate::{
+        daemon::{
+            cosmos_modules::{
+                abci::TxMsgData,
+                bank::{QuerySupplyOfRequest, QuerySupplyOfResponse},
+                base::Coin,
+                cosmwasm::MsgExecuteContractResponse,
+                tx::{
+                    service_client::ServiceClient, AuthInfo, BroadcastTxRequest, GetTxRequest,
+                    GetTxsEventRequest, SimulateRequest, TxBody, TxRaw,
+                },
+            },
+            queriers::{Bank, CosmWasm, DaemonQuerier, Node},
+        },
+        prelude::AccessConfig,
+    };
+    use base64::{
+        engine::general_purpose::{STANDARD, URL_SAFE},
+        Engine,
+    };
+    use hyper::{server::conn::Http, service::service_fn, Body, Request, Response};
+    use ibc_chain_registry::chain::Rest;
+    use ibc_relayer_types::core::ics24_host::identifier::ChainId;
+    use prost::Message;
+    use serde_json::{json, Value};
+    use std::convert::Infallible;
+    use tokio::net::TcpListener;
+
+    const EXECUTE_RESPONSE_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContractResponse";
+
+    /// Tx included in the block at height 42
+    fn tx_bytes() -> Vec<u8> {
+        TxRaw {
+            body_bytes: TxBody {
+                memo: "stand-in tx".to_string(),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+            auth_info_bytes: AuthInfo::default().encode_to_vec(),
+            signatures: vec![vec![1; 64]],
+        }
+        .encode_to_vec()
+    }
+
+    fn tx_hash() -> String {
+        sha256::digest(tx_bytes().as_slice()).to_uppercase()
+    }
+
+    fn block(height: u64, txs: Vec<String>) -> Value {
+        json!({
+            "block_id": { "hash": "", "part_set_header": { "total": 0, "hash": "" } },
+            "block": {
+                "header": {
+                    "version": { "block": "11", "app": "0" },
+                    "chain_id": "standin-1",
+                    "height": height.to_string(),
+                    "time": "2023-06-01T12:00:00Z",
+                    "last_block_id": { "hash": "", "part_set_header": { "total": 0, "hash": "" } },
+                    "proposer_address": "AAAAAAAAAAAAAAAAAAAAAAAAAAA="
+                },
+                "data": { "txs": txs },
+                "evidence": { "evidence": [] },
+                "last_commit": null
+            }
+        })
+    }
+
+    /// Minimal REST gateway of a node on chain `standin-1`
+    async fn handle(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+        let path = req.uri().path().to_string();
+        let query = req.uri().query().unwrap_or_default().to_string();
+        let body: Value = match hyper::body::to_bytes(req.into_body()).await {
+            Ok(body) if !body.is_empty() => serde_json::from_slice(&body).unwrap(),
+            _ => Value::Null,
+        };
+
+        let json = match path.as_str() {
+            "/cosmos/base/tendermint/v1beta1/node_info" => json!({
+                "default_node_info": { "network": "standin-1", "moniker": "stand-in" },
+                "application_version": { "app_name": "standind" }
+            }),
+            "/cosmos/base/tendermint/v1beta1/blocks/latest" => block(1, vec![]),
+            "/cosmos/base/tendermint/v1beta1/blocks/42" => {
+                block(42, vec![STANDARD.encode(tx_bytes())])
+            }
+            "/cosmos/bank/v1beta1/balances/juno1standin/by_denom" => {
+                let params = parse_query(&query);
+                json!({ "balance": { "denom": params["denom"], "amount": "100" } })
+            }
+            "/cosmwasm/wasm/v1/code/7" => json!({
+                "code_info": {
+                    "code_id": "7",
+                    "creator": "juno1creator",
+                    "data_hash": "0A0B0C",
+                    "instantiate_permission": { "permission": "ACCESS_TYPE_EVERYBODY", "address": "", "addresses": [] }
+                },
+                "data": "AGFzbQ=="
+            }),
+            p if p.starts_with("/cosmwasm/wasm/v1/contract/juno1contract/smart/") => {
+                let query = p.rsplit('/').next().unwrap();
+                let query: Value =
+                    serde_json::from_slice(&URL_SAFE.decode(query).unwrap()).unwrap();
+                assert_eq!(query, json!({ "count": {} }));
+                json!({ "data": { "count": 1 } })
+            }
+            "/cosmos/base/tendermint/v1beta1/abci_query" => {
+                let params = parse_query(&query);
+                assert_eq!(params["path"], "/cosmos.bank.v1beta1.Query/SupplyOf");
+                let data = STANDARD.decode(&params["data"]).unwrap();
+                let request = QuerySupplyOfRequest::decode(data.as_slice()).unwrap();
+                let response = QuerySupplyOfResponse {
+                    amount: Some(Coin {
+                        denom: request.denom,
+                        amount: "1000".to_string(),
+                    }),
+                };
+                json!({ "code": 0, "value": STANDARD.encode(response.encode_to_vec()), "height": "1" })
+            }
+            "/cosmos/tx/v1beta1/simulate" => {
+                assert!(body["tx_bytes"].is_string());
+                let data = TxMsgData {
+                    msg_responses: vec![cosmrs::Any {
+                        type_url: EXECUTE_RESPONSE_TYPE_URL.to_string(),
+                        value: MsgExecuteContractResponse {
+                            data: b"ok".to_vec(),
+                        }
+                        .encode_to_vec(),
+                    }],
+                    ..Default::default()
+                };
+                json!({
+                    "gas_info": { "gas_wanted": "0", "gas_used": "12345" },
+                    "result": {
+                        "data": STANDARD.encode(data.encode_to_vec()),
+                        "log": "",
+                        "events": [],
+                        "msg_responses": [{ "@type": EXECUTE_RESPONSE_TYPE_URL, "data": "b2s=" }]
+                    }
+                })
+            }
+            "/cosmos/tx/v1beta1/txs" => {
+                assert_eq!(body["mode"], "BROADCAST_MODE_SYNC");
+                json!({ "tx_response": { "height": "0", "txhash": tx_hash(), "code": 0, "raw_log": "[]" } })
+            }
+            p if p == format!("/cosmos/tx/v1beta1/txs/{}", tx_hash()) => json!({
+                "tx_response": {
+                    "height": "42",
+                    "txhash": tx_hash(),
+                    "code": 0,
+                    "gas_used": "12345",
+                    "timestamp": "2023-06-01T12:00:00Z",
+                    "events": [{ "type": "message", "attributes": [{ "key": "action", "value": "send", "index": true }] }]
+                }
+            }),
+            _ => {
+                return Ok(Response::builder()
+                    .status(501)
+                    .body(
+                        json!({ "code": 12, "message": "not implemented" })
+                            .to_string()
+                            .into(),
+                    )
+                    .unwrap())
+            }
+        };
+        Ok(Response::new(json.to_string().into()))
+    }
+
+    fn parse_query(query: &str) -> std::collections::HashMap<String, String> {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(key, value)| (key.to_string(), percent_decode(value)))
+            .collect()
+    }
+
+    fn percent_decode(value: &str) -> String {
+        let mut decoded = vec![];
+        let mut bytes = value.bytes();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                b'%' => {
+                    let hex: String = bytes.by_ref().take(2).map(char::from).collect();
+                    decoded.push(u8::from_str_radix(&hex, 16).unwrap());
+                }
+                b'+' => decoded.push(b' '),
+                byte => decoded.push(byte),
+            }
+        }
+        String::from_utf8(decoded).unwrap()
+    }
+
+    /// Serves the stand-in gateway on a free local port
+    async fn spawn_gateway() -> Rest {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(Http::new().serve_connection(stream, service_fn(handle)));
+            }
+        });
+        Rest {
+            address: format!("http://{addr}/"),
+            provider: None,
+        }
+    }
+
+    async fn channel() -> DaemonChannel {
+        let unreachable = Rest {
+            address: "http://127.0.0.1:1".to_string(),
+            provider: None,
+        };
+        RestChannel::connect(
+            &[unreachable, spawn_gateway().await],
+            &ChainId::new("standin".to_string(), 1),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn node_service_over_rest() {
+        let node = Node::new(channel().await);
+
+        let info = node.info().await.unwrap();
+        assert_eq!(info.default_node_info.unwrap().network, "standin-1");
+        assert_eq!(node.block_height().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn module_queries_over_rest() {
+        let channel = channel().await;
+        let bank = Bank::new(channel.clone());
+
+        // served by the REST route of the query
+        let balance = bank
+            .balance("juno1standin", Some("ujuno".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(balance[0].denom, "ujuno");
+        assert_eq!(balance[0].amount, "100");
+
+        // served by abci_query
+        let supply = bank.supply_of("ujuno").await.unwrap();
+        assert_eq!(supply.amount, "1000");
+
+        let wasm = CosmWasm::new(channel);
+        assert_eq!(wasm.code_id_hash(7).await.unwrap(), "0a0b0c");
+        assert_eq!(
+            wasm.instantiate_permission(7).await.unwrap(),
+            AccessConfig::Everybody
+        );
+        let count = wasm
+            .contract_state("juno1contract", br#"{"count":{}}"#.to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&count).unwrap(),
+            json!({ "count": 1 })
+        );
+    }
+
+    #[tokio::test]
+    async fn tx_service_over_rest() {
+        let channel = channel().await;
+        let node = Node::new(channel.clone());
+
+        assert_eq!(node.simulate_tx(vec![1, 2, 3]).await.unwrap(), 12345);
+
+        let mut client = ServiceClient::new(channel);
+        #[allow(deprecated)]
+        let simulated = client
+            .simulate(SimulateRequest {
+                tx: None,
+                tx_bytes: vec![1, 2, 3],
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        let msg_responses = simulated.result.unwrap().msg_responses;
+        assert_eq!(msg_responses[0].type_url, EXECUTE_RESPONSE_TYPE_URL);
+        let response =
+            MsgExecuteContractResponse::decode(msg_responses[0].value.as_slice()).unwrap();
+        assert_eq!(response.data, b"ok");
+
+        let broadcasted = client
+            .broadcast_tx(BroadcastTxRequest {
+                tx_bytes: vec![1, 2, 3],
+                mode: 2,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(broadcasted.tx_response.unwrap().txhash, tx_hash());
+
+        let tx = node.tx(tx_hash()).await.unwrap();
+        assert_eq!(tx.height, 42);
+        assert_eq!(tx.events[0].attributes[0].value, "send");
+
+        // the tx itself is read from its block
+        let found = client
+            .get_tx(GetTxRequest { hash: tx_hash() })
+            .await
+            .unwrap()
+            .into_inner();
+        let tx = found.tx.unwrap();
+        assert_eq!(tx.body.unwrap().memo, "stand-in tx");
+        assert_eq!(tx.signatures, vec![vec![1; 64]]);
+    }
+
+    #[tokio::test]
+    async fn unsupported_calls_are_rejected() {
+        let err = ServiceClient::new(channel().await)
+            .get_txs_event(GetTxsEventRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unimplemented);
+    }
+}
@@ -4,31 +4,43 @@ use super::{
     chain_info::ChainKind,
     cosmos_modules::{self, auth::BaseAccount},
     error::DaemonError,
+    gas_price::GasPriceSource,
+    pending_tx::{ConfirmationPolicy, PendingTx},
     queriers::{DaemonQuerier, Node},
     state::DaemonState,
-    tx_builder::TxBuilder,
+    tx_builder::{TxBuilder, GAS_BUFFER},
     tx_resp::CosmTxResponse,
+    DaemonChannel,
 };
 use crate::daemon::proto::injective::InjectiveEthAccount;
 
 #[cfg(feature = "eth")]
 use crate::daemon::proto::injective::InjectiveSigner;
 
-use crate::{daemon::core::parse_cw_coins, keys::private::PrivateKey};
+use crate::{
+    daemon::core::{parse_cw_coins, parse_cw_coins_proto},
+    keys::private::PrivateKey,
+};
 use cosmrs::{
     bank::MsgSend,
     crypto::secp256k1::SigningKey,
-    proto::traits::Message,
-    tendermint::chain::Id,
-    tx::{self, ModeInfo, Msg, Raw, SignDoc, SignMode, SignerInfo},
-    AccountId,
+    proto::{cosmos::tx::v1beta1::TxRaw, traits::Message},
+    tendermint::{chain::Id, Time},
+    tx::{self, AuthInfo, Fee, ModeInfo, Msg, Raw, SignDoc, SignMode, SignerInfo},
+    AccountId, Any,
 };
 use cosmwasm_std::Addr;
 use secp256k1::{All, Context, Secp256k1, Signing};
-use std::{convert::TryFrom, env, rc::Rc, str::FromStr};
+use std::{cell::Cell, convert::TryFrom, env, rc::Rc, str::FromStr, time::Duration};
 
 use cosmos_modules::vesting::PeriodicVestingAccount;
-use tonic::transport::Channel;
+
+const MAX_SEQUENCE_RETRIES: usize = 3;
+
+const BASIC_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.BasicAllowance";
+const PERIODIC_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.PeriodicAllowance";
+const MSG_GRANT_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.MsgGrantAllowance";
+const MSG_REVOKE_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.MsgRevokeAllowance";
 
 /// A wallet is a sender of transactions, can be safely cloned and shared within the same thread.
 pub type Wallet = Rc<Sender<All>>;
@@ -39,10 +51,61 @@ pub struct Sender<C: Signing + Context> {
     pub private_key: PrivateKey,
     pub secp: Secp256k1<C>,
     pub(crate) daemon_state: Rc<DaemonState>,
+    pub(crate) options: SenderOptions,
+    /// Account that pays the fees of the txs and co-signs them
+    fee_payer: Option<PrivateKey>,
+    /// Locally tracked account number and next sequence
+    account_sequence: Cell<Option<(u64, u64)>>,
+}
+
+/// Options for how txs are paid for by a [`Sender`].
+/// Can be overwritten per tx through the [`TxBuilder`].
+#[derive(Clone, Default)]
+pub struct SenderOptions {
+    /// Address of the account whose fee allowance pays the fees of the txs
+    pub fee_granter: Option<String>,
+    /// Mnemonic of the account that pays the fees, it signs every tx next to the sender
+    pub fee_payer: Option<String>,
+    /// Denom to pay the fees in, defaults to the chain's main fee token
+    pub fee_denom: Option<String>,
+    /// Gas price for the fee denom, defaults to the chain's configured gas price for that denom
+    pub gas_price: Option<f64>,
+    /// Where to get the current gas price from, the static gas price is used as fallback
+    pub gas_price_source: GasPriceSource,
+    /// Multiplier applied to the simulated gas, defaults to [`GAS_BUFFER`]
+    pub gas_adjustment: Option<f64>,
+    /// Maximum fee amount a single tx is allowed to pay
+    pub max_fee: Option<u128>,
+    /// When a broadcasted tx is considered confirmed
+    pub confirmation_policy: ConfirmationPolicy,
+}
+
+impl std::fmt::Debug for SenderOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // keep the fee payer's mnemonic out of the logs
+        f.debug_struct("SenderOptions")
+            .field("fee_granter", &self.fee_granter)
+            .field("fee_payer", &self.fee_payer.as_ref().map(|_| "<mnemonic>"))
+            .field("fee_denom", &self.fee_denom)
+            .field("gas_price", &self.gas_price)
+            .field("gas_price_source", &self.gas_price_source)
+            .field("gas_adjustment", &self.gas_adjustment)
+            .field("max_fee", &self.max_fee)
+            .field("confirmation_policy", &self.confirmation_policy)
+            .finish()
+    }
 }
 
 impl Sender<All> {
     pub fn new(daemon_state: &Rc<DaemonState>) -> Result<Sender<All>, DaemonError> {
+        Self::new_with_options(daemon_state, SenderOptions::default())
+    }
+
+    /// Construct a new Sender from the mnemonic env variable with the given options
+    pub fn new_with_options(
+        daemon_state: &Rc<DaemonState>,
+        options: SenderOptions,
+    ) -> Result<Sender<All>, DaemonError> {
         let kind = ChainKind::from(daemon_state.chain_data.network_type.clone());
         // NETWORK_MNEMONIC_GROUP
         let mnemonic = env::var(kind.mnemonic_name()).unwrap_or_else(|_| {
@@ -52,43 +115,75 @@ impl Sender<All> {
             )
         });
 
-        Self::from_mnemonic(daemon_state, &mnemonic)
+        Self::from_mnemonic_with_options(daemon_state, &mnemonic, options)
     }
 
     /// Construct a new Sender from a mnemonic
     pub fn from_mnemonic(
         daemon_state: &Rc<DaemonState>,
         mnemonic: &str,
+    ) -> Result<Sender<All>, DaemonError> {
+        Self::from_mnemonic_with_options(daemon_state, mnemonic, SenderOptions::default())
+    }
+
+    /// Construct a new Sender from a mnemonic with the given options
+    pub fn from_mnemonic_with_options(
+        daemon_state: &Rc<DaemonState>,
+        mnemonic: &str,
+        options: SenderOptions,
     ) -> Result<Sender<All>, DaemonError> {
         let secp = Secp256k1::new();
         let p_key: PrivateKey =
             PrivateKey::from_words(&secp, mnemonic, 0, 0, daemon_state.chain_data.slip44)?;
 
-        let sender = Sender {
+        let fee_payer = options.fee_payer.clone();
+        let mut sender = Sender {
             daemon_state: daemon_state.clone(),
             private_key: p_key,
             secp,
+            options,
+            fee_payer: None,
+            account_sequence: Cell::new(None),
         };
         log::info!(
             "Interacting with {} using address: {}",
             daemon_state.chain_data.chain_id,
             sender.pub_addr_str()?
         );
+        if let Some(mnemonic) = fee_payer {
+            sender.set_fee_payer(&mnemonic)?;
+        }
         Ok(sender)
     }
 
-    fn cosmos_private_key(&self) -> SigningKey {
-        SigningKey::from_slice(&self.private_key.raw_key()).unwrap()
+    /// Let the account of the mnemonic pay the fees of the txs.
+    /// The payer signs every tx next to the sender.
+    pub fn set_fee_payer(&mut self, mnemonic: &str) -> Result<(), DaemonError> {
+        let payer = PrivateKey::from_words(
+            &self.secp,
+            mnemonic,
+            0,
+            0,
+            self.daemon_state.chain_data.slip44,
+        )?;
+        self.fee_payer = Some(payer);
+        self.options.fee_payer = Some(mnemonic.to_string());
+        log::info!("Paying fees with {}", self.fee_payer()?.unwrap());
+        Ok(())
     }
 
-    pub fn channel(&self) -> Channel {
+    pub fn channel(&self) -> DaemonChannel {
         self.daemon_state.grpc_channel.clone()
     }
 
     pub(crate) fn pub_addr(&self) -> Result<AccountId, DaemonError> {
+        self.key_addr(&self.private_key)
+    }
+
+    fn key_addr(&self, key: &PrivateKey) -> Result<AccountId, DaemonError> {
         Ok(AccountId::new(
             &self.daemon_state.chain_data.bech32_prefix,
-            &self.private_key.public_key(&self.secp).raw_address.unwrap(),
+            &key.public_key(&self.secp).raw_address.unwrap(),
         )?)
     }
 
@@ -114,6 +209,71 @@ impl Sender<All> {
         self.commit_tx(vec![msg_send], Some("sending tokens")).await
     }
 
+    /// Grant a basic fee allowance to the grantee, paid by this sender.
+    /// A `None` spend limit or expiration means the allowance is unlimited in that regard.
+    pub async fn grant_basic_allowance(
+        &self,
+        grantee: &str,
+        spend_limit: Option<Vec<cosmwasm_std::Coin>>,
+        expiration: Option<Time>,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        let allowance = basic_allowance(spend_limit, expiration);
+        self.grant_allowance(grantee, encode_any(BASIC_ALLOWANCE_TYPE_URL, allowance))
+            .await
+    }
+
+    /// Grant a periodic fee allowance to the grantee, paid by this sender.
+    /// The grantee can spend up to `period_spend_limit` every `period`.
+    pub async fn grant_periodic_allowance(
+        &self,
+        grantee: &str,
+        spend_limit: Option<Vec<cosmwasm_std::Coin>>,
+        expiration: Option<Time>,
+        period: Duration,
+        period_spend_limit: Vec<cosmwasm_std::Coin>,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        let period_spend_limit = parse_cw_coins_proto(&period_spend_limit);
+        let allowance = cosmos_modules::feegrant::PeriodicAllowance {
+            basic: Some(basic_allowance(spend_limit, expiration)),
+            period: Some(period.into()),
+            period_can_spend: period_spend_limit.clone(),
+            period_spend_limit,
+            period_reset: None,
+        };
+        self.grant_allowance(grantee, encode_any(PERIODIC_ALLOWANCE_TYPE_URL, allowance))
+            .await
+    }
+
+    /// Revoke an existing fee allowance granted by this sender to the grantee.
+    pub async fn revoke_allowance(&self, grantee: &str) -> Result<CosmTxResponse, DaemonError> {
+        let msg = cosmos_modules::feegrant::MsgRevokeAllowance {
+            granter: self.pub_addr_str()?,
+            grantee: AccountId::from_str(grantee)?.to_string(),
+        };
+        self.commit_tx_any(
+            vec![encode_any(MSG_REVOKE_ALLOWANCE_TYPE_URL, msg)],
+            Some("revoking fee allowance"),
+        )
+        .await
+    }
+
+    async fn grant_allowance(
+        &self,
+        grantee: &str,
+        allowance: Any,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        let msg = cosmos_modules::feegrant::MsgGrantAllowance {
+            granter: self.pub_addr_str()?,
+            grantee: AccountId::from_str(grantee)?.to_string(),
+            allowance: Some(allowance),
+        };
+        self.commit_tx_any(
+            vec![encode_any(MSG_GRANT_ALLOWANCE_TYPE_URL, msg)],
+            Some("granting fee allowance"),
+        )
+        .await
+    }
+
     pub async fn calculate_gas(
         &self,
         tx_body: &tx::Body,
@@ -122,25 +282,13 @@ impl Sender<All> {
     ) -> Result<u64, DaemonError> {
         let fee = TxBuilder::build_fee(
             0u8,
-            &self.daemon_state.chain_data.fees.fee_tokens[0].denom,
+            &self.fee_denom(),
             0,
+            self.fee_granter()?,
+            self.fee_payer()?,
         );
 
-        let auth_info = SignerInfo {
-            public_key: self.private_key.get_signer_public_key(&self.secp),
-            mode_info: ModeInfo::single(SignMode::Direct),
-            sequence,
-        }
-        .auth_info(fee);
-
-        let sign_doc = SignDoc::new(
-            tx_body,
-            &auth_info,
-            &Id::try_from(self.daemon_state.chain_data.chain_id.to_string())?,
-            account_number,
-        )?;
-
-        let tx_raw = self.sign(sign_doc)?;
+        let tx_raw = self.sign_tx(tx_body, fee, account_number, sequence).await?;
 
         Node::new(self.channel())
             .simulate_tx(tx_raw.to_bytes()?)
@@ -152,72 +300,335 @@ impl Sender<All> {
         msgs: Vec<T>,
         memo: Option<&str>,
     ) -> Result<CosmTxResponse, DaemonError> {
+        let msgs = msgs
+            .into_iter()
+            .map(Msg::into_any)
+            .collect::<Result<Vec<Any>, _>>()?;
+
+        self.commit_tx_any(msgs, memo).await
+    }
+
+    /// Commit a tx with messages that are already encoded as [`Any`].
+    /// Useful for messages that have no [`Msg`] implementation in cosmrs.
+    pub async fn commit_tx_any(
+        &self,
+        msgs: Vec<Any>,
+        memo: Option<&str>,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        self.submit_tx_any(msgs, memo).await?.wait().await
+    }
+
+    /// Broadcast a tx without waiting for it to be included in a block.
+    /// The returned [`PendingTx`] can be awaited later on.
+    pub async fn submit_tx<T: Msg>(
+        &self,
+        msgs: Vec<T>,
+        memo: Option<&str>,
+    ) -> Result<PendingTx, DaemonError> {
+        let msgs = msgs
+            .into_iter()
+            .map(Msg::into_any)
+            .collect::<Result<Vec<Any>, _>>()?;
+
+        self.submit_tx_any(msgs, memo).await
+    }
+
+    /// Same as [`Sender::submit_tx`] for messages that are already encoded as [`Any`].
+    pub async fn submit_tx_any(
+        &self,
+        msgs: Vec<Any>,
+        memo: Option<&str>,
+    ) -> Result<PendingTx, DaemonError> {
         let timeout_height = Node::new(self.channel()).block_height().await? + 10u64;
 
-        let tx_body = TxBuilder::build_body(msgs, memo, timeout_height);
+        let tx_body = TxBuilder::build_body_any(msgs, memo, timeout_height);
 
         let mut tx_builder = TxBuilder::new(tx_body);
 
-        // now commit and check the result, if we get an insufficient fee error, we can try again with the proposed fee
+        let tx_response = self.build_and_broadcast(&mut tx_builder).await?;
 
-        let tx = tx_builder.build(self).await?;
+        Ok(self.pending_tx(tx_response.txhash))
+    }
 
-        let mut tx_response = self.broadcast_tx(tx).await?;
+    /// Broadcast multiple txs back to back and wait for their inclusion concurrently.
+    /// Each element of `txs` contains the messages of a single tx.
+    ///
+    /// Useful for bulk operations like airdrops, where waiting for every tx before sending the next one is too slow.
+    /// The sequences are tracked per [`Sender`], so txs of the same account must go through the same sender.
+    /// Returns the result of every tx in the order they were provided.
+    pub async fn commit_txs_pipelined(
+        &self,
+        txs: Vec<Vec<Any>>,
+        memo: Option<&str>,
+    ) -> Result<Vec<Result<CosmTxResponse, DaemonError>>, DaemonError> {
+        // broadcast all txs, the sequence tracker makes sure they get consecutive sequences
+        let mut broadcasted = Vec::with_capacity(txs.len());
+        for msgs in txs {
+            // broadcasting a large batch takes several blocks, so every tx gets its own timeout
+            let timeout_height = Node::new(self.channel()).block_height().await? + 10u64;
+            let tx_body = TxBuilder::build_body_any(msgs, memo, timeout_height);
+            let mut tx_builder = TxBuilder::new(tx_body);
+            let result = self.build_and_broadcast(&mut tx_builder).await;
+            match &result {
+                Ok(tx_response) => log::info!("Broadcasted tx {}", tx_response.txhash),
+                Err(e) => log::warn!("Failed to broadcast tx: {}", e),
+            }
+            broadcasted.push(result);
+        }
 
-        log::debug!("tx broadcast response: {:?}", tx_response);
+        // wait for the confirmation of all broadcasted txs concurrently
+        let handles = broadcasted
+            .into_iter()
+            .map(|result| {
+                result.map(|tx_response| {
+                    let pending = self.pending_tx(tx_response.txhash);
+                    tokio::spawn(async move { pending.wait().await })
+                })
+            })
+            .collect::<Vec<_>>();
 
-        if has_insufficient_fee(&tx_response.raw_log) {
-            // get the suggested fee from the error message
-            let suggested_fee = parse_suggested_fee(&tx_response.raw_log);
+        let mut results = Vec::with_capacity(handles.len());
+        for handle in handles {
+            let result = match handle {
+                Ok(handle) => handle.await?,
+                Err(e) => Err(e),
+            };
+            results.push(result);
+        }
+        Ok(results)
+    }
 
-            let Some(new_fee) = suggested_fee else {
-                return Err(DaemonError::InsufficientFee(
-                    tx_response.raw_log,
-                ));
+    /// Handle to a broadcasted tx that uses the sender's confirmation policy
+    fn pending_tx(&self, hash: String) -> PendingTx {
+        PendingTx::new(
+            hash,
+            self.channel(),
+            self.options.confirmation_policy.clone(),
+        )
+    }
+
+    /// Build, sign and broadcast a tx.
+    /// Retries when the fee was insufficient or the account sequence didn't match the one of the chain.
+    /// Only returns once the tx is accepted into the mempool, not when it's included in a block.
+    pub(crate) async fn build_and_broadcast(
+        &self,
+        tx_builder: &mut TxBuilder,
+    ) -> Result<cosmrs::proto::cosmos::base::abci::v1beta1::TxResponse, DaemonError> {
+        let mut retried_fee = false;
+        let mut sequence_retries = 0;
+
+        loop {
+            // a sequence set on the tx builder is never overwritten
+            let can_retry_sequence =
+                tx_builder.sequence.is_none() && sequence_retries < MAX_SEQUENCE_RETRIES;
+
+            // the simulation fails if the sequence is wrong
+            let tx = match tx_builder.build(self).await {
+                Ok(tx) => tx,
+                Err(DaemonError::Status(status))
+                    if can_retry_sequence && has_sequence_mismatch(status.message()) =>
+                {
+                    log::warn!("Account sequence mismatch, retrying: {}", status.message());
+                    self.resync_sequence(status.message());
+                    sequence_retries += 1;
+                    continue;
+                }
+                Err(e) => return Err(e),
             };
 
-            // update the fee and try again
-            tx_builder.fee_amount(new_fee);
-            let tx = tx_builder.build(self).await?;
+            let tx_response = self.broadcast_tx(tx).await?;
 
-            tx_response = self.broadcast_tx(tx).await?;
             log::debug!("tx broadcast response: {:?}", tx_response);
-        }
 
-        let resp = Node::new(self.channel())
-            .find_tx(tx_response.txhash)
-            .await?;
+            if has_insufficient_fee(&tx_response.raw_log) && !retried_fee {
+                // get the suggested fee from the error message
+                let suggested_fee = parse_suggested_fee(&tx_response.raw_log);
 
-        // if tx result != 0 then the tx failed, so we return an error
-        // if tx result == 0 then the tx succeeded, so we return the tx response
-        if resp.code == 0 {
-            Ok(resp)
-        } else {
-            Err(DaemonError::TxFailed {
-                code: resp.code,
-                reason: resp.raw_log,
-            })
+                let Some(new_fee) = suggested_fee else {
+                    return Err(DaemonError::InsufficientFee(tx_response.raw_log));
+                };
+
+                // update the fee and try again
+                tx_builder.fee_amount(new_fee);
+                retried_fee = true;
+                continue;
+            }
+
+            if can_retry_sequence && has_sequence_mismatch(&tx_response.raw_log) {
+                log::warn!(
+                    "Account sequence mismatch, retrying: {}",
+                    tx_response.raw_log
+                );
+                self.resync_sequence(&tx_response.raw_log);
+                sequence_retries += 1;
+                continue;
+            }
+
+            // the tx is not accepted in the mempool so it will never be included in a block
+            if tx_response.code != 0 {
+                return Err(DaemonError::TxFailed {
+                    code: tx_response.code as usize,
+                    reason: tx_response.raw_log,
+                });
+            }
+
+            // the sequence is only incremented when the tx is accepted in the mempool
+            // and only if it used the tracked sequence, not one set on the tx builder
+            if tx_builder.sequence.is_none() {
+                self.increment_sequence();
+            }
+
+            return Ok(tx_response);
         }
     }
 
     pub fn sign(&self, sign_doc: SignDoc) -> Result<Raw, DaemonError> {
-        let tx_raw = if self.private_key.coin_type == ETHEREUM_COIN_TYPE {
-            #[cfg(not(feature = "eth"))]
-            panic!(
-                "Coin Type {} not supported without eth feature",
-                ETHEREUM_COIN_TYPE
-            );
-            #[cfg(feature = "eth")]
-            self.private_key.sign_injective(sign_doc)?
-        } else {
-            sign_doc.sign(&self.cosmos_private_key())?
+        sign_with(&self.private_key, sign_doc)
+    }
+
+    /// Sign a tx with the sender and, if the sender has one, with the fee payer.
+    pub(crate) async fn sign_tx(
+        &self,
+        body: &tx::Body,
+        fee: Fee,
+        account_number: u64,
+        sequence: u64,
+    ) -> Result<Raw, DaemonError> {
+        let mut signers = vec![TxSigner {
+            key: &self.private_key,
+            account_number,
+            sequence,
+        }];
+        if let Some(payer) = &self.fee_payer {
+            // the payer's sequence isn't tracked, it only signs the txs of this sender
+            let BaseAccount {
+                account_number,
+                sequence,
+                ..
+            } = self.account(self.key_addr(payer)?.to_string()).await?;
+            signers.push(TxSigner {
+                key: payer,
+                account_number,
+                sequence,
+            });
+        }
+        sign_tx_with(
+            &self.secp,
+            &self.daemon_state.chain_data.chain_id.to_string(),
+            body,
+            fee,
+            &signers,
+        )
+    }
+
+    /// Returns the account number and the sequence to use for the next tx.
+    /// The sequence is tracked locally so consecutive txs don't need to wait for each other's inclusion.
+    pub(crate) async fn account_sequence(&self) -> Result<(u64, u64), DaemonError> {
+        if let Some(account_sequence) = self.account_sequence.get() {
+            return Ok(account_sequence);
+        }
+        let BaseAccount {
+            account_number,
+            sequence,
+            ..
+        } = self.base_account().await?;
+        self.account_sequence.set(Some((account_number, sequence)));
+        Ok((account_number, sequence))
+    }
+
+    fn increment_sequence(&self) {
+        if let Some((account_number, sequence)) = self.account_sequence.get() {
+            self.account_sequence
+                .set(Some((account_number, sequence + 1)));
+        }
+    }
+
+    /// Use the sequence the chain expects, or query it again if it can't be parsed from the error.
+    fn resync_sequence(&self, raw_log: &str) {
+        let resynced = match (
+            self.account_sequence.get(),
+            parse_expected_sequence(raw_log),
+        ) {
+            (Some((account_number, _)), Some(expected)) => Some((account_number, expected)),
+            _ => None,
         };
-        Ok(tx_raw)
+        self.account_sequence.set(resynced);
+    }
+
+    /// Denom the fees are paid in by this sender
+    pub(crate) fn fee_denom(&self) -> String {
+        self.options.fee_denom.clone().unwrap_or_else(|| {
+            self.daemon_state.chain_data.fees.fee_tokens[0]
+                .denom
+                .clone()
+        })
+    }
+
+    /// Gas price to use when paying fees in the given denom.
+    /// Queries the configured gas price source and falls back to the static gas price.
+    pub(crate) async fn gas_price(&self, denom: &str) -> Result<f64, DaemonError> {
+        let source = &self.options.gas_price_source;
+        match source.gas_price(self.channel(), denom).await {
+            Ok(Some(gas_price)) => {
+                log::debug!("Using {:?} gas price: {}{}", source, gas_price, denom);
+                return Ok(gas_price);
+            }
+            Ok(None) => (),
+            Err(e) => log::warn!(
+                "Failed to query {:?} gas price, using static gas price: {}",
+                source,
+                e
+            ),
+        }
+        self.static_gas_price(denom)
+    }
+
+    /// Gas price of the daemon configuration for the given denom
+    fn static_gas_price(&self, denom: &str) -> Result<f64, DaemonError> {
+        // the configured gas price only applies to the configured fee denom
+        if let Some(gas_price) = self.options.gas_price {
+            if denom == self.fee_denom() {
+                return Ok(gas_price);
+            }
+        }
+        self.daemon_state
+            .chain_data
+            .fees
+            .fee_tokens
+            .iter()
+            .find(|token| token.denom == denom)
+            .map(|token| token.fixed_min_gas_price)
+            .ok_or_else(|| DaemonError::GasPriceError(denom.to_string()))
+    }
+
+    /// Multiplier applied to the simulated gas of txs
+    pub(crate) fn gas_adjustment(&self) -> f64 {
+        self.options.gas_adjustment.unwrap_or(GAS_BUFFER)
+    }
+
+    /// Fee granter configured for this sender, if any
+    pub(crate) fn fee_granter(&self) -> Result<Option<AccountId>, DaemonError> {
+        self.options
+            .fee_granter
+            .as_ref()
+            .map(|granter| AccountId::from_str(granter))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Fee payer configured for this sender, if any
+    pub(crate) fn fee_payer(&self) -> Result<Option<AccountId>, DaemonError> {
+        self.fee_payer
+            .as_ref()
+            .map(|payer| self.key_addr(payer))
+            .transpose()
     }
 
     pub async fn base_account(&self) -> Result<BaseAccount, DaemonError> {
-        let addr = self.pub_addr().unwrap().to_string();
+        self.account(self.pub_addr()?.to_string()).await
+    }
 
+    async fn account(&self, addr: String) -> Result<BaseAccount, DaemonError> {
         let mut client = cosmos_modules::auth::query_client::QueryClient::new(self.channel());
 
         let resp = client
@@ -260,6 +671,109 @@ impl Sender<All> {
     }
 }
 
+/// Key, account number and sequence of one of the signers of a tx
+pub(crate) struct TxSigner<'a> {
+    pub key: &'a PrivateKey,
+    pub account_number: u64,
+    pub sequence: u64,
+}
+
+/// Sign a tx with every signer in order, the first signer being the one of the messages.
+/// Every signer signs the same body and auth info, with their own account number.
+pub(crate) fn sign_tx_with<C: Signing + Context>(
+    secp: &Secp256k1<C>,
+    chain_id: &str,
+    body: &tx::Body,
+    fee: Fee,
+    signers: &[TxSigner],
+) -> Result<Raw, DaemonError> {
+    let auth_info = AuthInfo {
+        signer_infos: signers
+            .iter()
+            .map(|signer| SignerInfo {
+                public_key: signer.key.get_signer_public_key(secp),
+                mode_info: ModeInfo::single(SignMode::Direct),
+                sequence: signer.sequence,
+            })
+            .collect(),
+        fee,
+    };
+    let chain_id = Id::try_from(chain_id.to_string())?;
+
+    let mut tx_raw: Option<TxRaw> = None;
+    for signer in signers {
+        let sign_doc = SignDoc::new(body, &auth_info, &chain_id, signer.account_number)?;
+        let mut signed = TxRaw::from(sign_with(signer.key, sign_doc)?);
+        match &mut tx_raw {
+            Some(tx_raw) => tx_raw.signatures.append(&mut signed.signatures),
+            None => tx_raw = Some(signed),
+        }
+    }
+    tx_raw
+        .map(Into::into)
+        .ok_or_else(|| DaemonError::StdErr("a tx needs at least one signer".into()))
+}
+
+fn sign_with(key: &PrivateKey, sign_doc: SignDoc) -> Result<Raw, DaemonError> {
+    let tx_raw = if key.coin_type == ETHEREUM_COIN_TYPE {
+        #[cfg(not(feature = "eth"))]
+        panic!(
+            "Coin Type {} not supported without eth feature",
+            ETHEREUM_COIN_TYPE
+        );
+        #[cfg(feature = "eth")]
+        key.sign_injective(sign_doc)?
+    } else {
+        sign_doc.sign(&SigningKey::from_slice(&key.raw_key()).unwrap())?
+    };
+    Ok(tx_raw)
+}
+
+fn basic_allowance(
+    spend_limit: Option<Vec<cosmwasm_std::Coin>>,
+    expiration: Option<Time>,
+) -> cosmos_modules::feegrant::BasicAllowance {
+    cosmos_modules::feegrant::BasicAllowance {
+        spend_limit: spend_limit
+            .map(|coins| parse_cw_coins_proto(&coins))
+            .unwrap_or_default(),
+        expiration: expiration.map(Into::into),
+    }
+}
+
+pub(crate) fn encode_any<M: Message>(type_url: &str, msg: M) -> Any {
+    Any {
+        type_url: type_url.to_string(),
+        value: msg.encode_to_vec(),
+    }
+}
+
+pub(crate) fn check_tx_result(resp: CosmTxResponse) -> Result<CosmTxResponse, DaemonError> {
+    // if tx result != 0 then the tx failed, so we return an error
+    // if tx result == 0 then the tx succeeded, so we return the tx response
+    if resp.code == 0 {
+        Ok(resp)
+    } else {
+        Err(DaemonError::TxFailed {
+            code: resp.code,
+            reason: resp.raw_log,
+        })
+    }
+}
+
+fn has_sequence_mismatch(raw_log: &str) -> bool {
+    raw_log.contains("account sequence mismatch")
+}
+
+// from logs: "account sequence mismatch, expected 12, got 11: incorrect account sequence"
+fn parse_expected_sequence(raw_log: &str) -> Option<u64> {
+    let (_, expected) = raw_log.split_once("expected ")?;
+    let end = expected
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(expected.len());
+    expected[..end].parse().ok()
+}
+
 fn has_insufficient_fee(raw_log: &str) -> bool {
     raw_log.contains("insufficient fees")
 }
@@ -305,6 +819,30 @@ fn parse_suggested_fee(raw_log: &str) -> Option<u128> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::daemon::{
+        cosmos_modules::{
+            abci::{GasInfo, TxResponse},
+            auth::QueryAccountRequest,
+            tx::{
+                BroadcastTxRequest, BroadcastTxResponse, GetTxRequest, GetTxResponse,
+                SimulateRequest, SimulateResponse,
+            },
+        },
+        stand_in::{account, StandInNode},
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+    use tonic::Status;
+
+    #[test]
+    fn test_parse_expected_sequence() {
+        let log = "account sequence mismatch, expected 12, got 11: incorrect account sequence";
+        assert!(has_sequence_mismatch(log));
+        assert_eq!(parse_expected_sequence(log), Some(12));
+        assert_eq!(parse_expected_sequence("account sequence mismatch"), None);
+    }
 
     #[test]
     fn test_parse_suggested_fee() {
@@ -312,4 +850,209 @@ mod tests {
         let fee = parse_suggested_fee(log).unwrap();
         assert_eq!(fee, 444255);
     }
+
+    #[test]
+    fn fee_payer_signs_the_tx() {
+        let secp = Secp256k1::new();
+        let sender = PrivateKey::from_words(&secp, "wonder caution square unveil april art add hover spend smile proud admit modify old copper throw crew happy nature luggage reopen exhibit ordinary napkin", 0, 0, 118).unwrap();
+        let payer = PrivateKey::from_words(&secp, "notice oak worry limit wrap speak medal online prefer cluster roof addict wrist behave treat actual wasp year salad speed social layer crew genius", 0, 0, 118).unwrap();
+        let payer_addr =
+            AccountId::new("juno", &payer.public_key(&secp).raw_address.unwrap()).unwrap();
+
+        let body = TxBuilder::build_body_any(vec![], Some("paid by someone else"), 0);
+        let fee = TxBuilder::build_fee(100u128, "ujuno", 200_000, None, Some(payer_addr.clone()));
+        let signers = [
+            TxSigner {
+                key: &sender,
+                account_number: 1,
+                sequence: 7,
+            },
+            TxSigner {
+                key: &payer,
+                account_number: 2,
+                sequence: 3,
+            },
+        ];
+        let raw = sign_tx_with(&secp, "juno-1", &body, fee, &signers).unwrap();
+
+        let tx = cosmrs::Tx::from_bytes(&raw.to_bytes().unwrap()).unwrap();
+        let signer_infos = &tx.auth_info.signer_infos;
+        assert_eq!(signer_infos.len(), 2);
+        assert_eq!(
+            signer_infos[0].public_key,
+            sender.get_signer_public_key(&secp)
+        );
+        assert_eq!(signer_infos[0].sequence, 7);
+        assert_eq!(
+            signer_infos[1].public_key,
+            payer.get_signer_public_key(&secp)
+        );
+        assert_eq!(signer_infos[1].sequence, 3);
+        assert_eq!(tx.auth_info.fee.payer, Some(payer_addr));
+        assert_eq!(tx.signatures.len(), 2);
+        assert_ne!(tx.signatures[0], tx.signatures[1]);
+    }
+
+    /*
+        Sequence tracking against an in-process node
+    */
+
+    /// Account of the sender as seen by the stand-in node
+    #[derive(Default)]
+    struct Chain {
+        /// Sequence the next tx of the account needs
+        sequence: Mutex<u64>,
+        /// Sequences of the txs accepted in the mempool
+        accepted: Mutex<Vec<u64>>,
+        account_queries: AtomicUsize,
+    }
+
+    fn signer_sequence(tx_bytes: &[u8]) -> u64 {
+        cosmrs::Tx::from_bytes(tx_bytes)
+            .unwrap()
+            .auth_info
+            .signer_infos[0]
+            .sequence
+    }
+
+    fn mismatch(expected: u64, got: u64) -> String {
+        format!(
+            "account sequence mismatch, expected {expected}, got {got}: incorrect account sequence"
+        )
+    }
+
+    /// Node that only accepts txs with the next sequence of the account, like the ante handler
+    fn node(chain: &Arc<Chain>) -> StandInNode {
+        let (queried, simulated, broadcasted) = (chain.clone(), chain.clone(), chain.clone());
+        StandInNode::new()
+            .unary(
+                "/cosmos.auth.v1beta1.Query/Account",
+                move |request: QueryAccountRequest| {
+                    queried.account_queries.fetch_add(1, Ordering::SeqCst);
+                    Ok(account(request.address, *queried.sequence.lock().unwrap()))
+                },
+            )
+            .unary(
+                "/cosmos.tx.v1beta1.Service/Simulate",
+                move |request: SimulateRequest| {
+                    let expected = *simulated.sequence.lock().unwrap();
+                    let got = signer_sequence(&request.tx_bytes);
+                    if got != expected {
+                        return Err(Status::unknown(mismatch(expected, got)));
+                    }
+                    Ok(SimulateResponse {
+                        gas_info: Some(GasInfo {
+                            gas_wanted: 0,
+                            gas_used: 100_000,
+                        }),
+                        result: None,
+                    })
+                },
+            )
+            .unary(
+                "/cosmos.tx.v1beta1.Service/BroadcastTx",
+                move |request: BroadcastTxRequest| {
+                    let mut sequence = broadcasted.sequence.lock().unwrap();
+                    let got = signer_sequence(&request.tx_bytes);
+                    let tx_response = if got == *sequence {
+                        *sequence += 1;
+                        broadcasted.accepted.lock().unwrap().push(got);
+                        TxResponse {
+                            txhash: format!("TX{got}"),
+                            ..Default::default()
+                        }
+                    } else {
+                        TxResponse {
+                            code: 32,
+                            raw_log: mismatch(*sequence, got),
+                            ..Default::default()
+                        }
+                    };
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(tx_response),
+                    })
+                },
+            )
+            .unary(
+                "/cosmos.tx.v1beta1.Service/GetTx",
+                |request: GetTxRequest| {
+                    Ok(GetTxResponse {
+                        tx: None,
+                        tx_response: Some(TxResponse {
+                            height: 100,
+                            txhash: request.hash,
+                            timestamp: "2023-06-01T12:00:00Z".to_string(),
+                            ..Default::default()
+                        }),
+                    })
+                },
+            )
+    }
+
+    fn chain(sequence: u64) -> Arc<Chain> {
+        let chain = Arc::new(Chain::default());
+        *chain.sequence.lock().unwrap() = sequence;
+        chain
+    }
+
+    #[tokio::test]
+    async fn sequence_is_tracked_locally() {
+        let chain = chain(3);
+        let sender = node(&chain).sender(SenderOptions::default()).await;
+
+        sender.commit_tx_any(vec![], None).await.unwrap();
+        sender.commit_tx_any(vec![], None).await.unwrap();
+
+        assert_eq!(*chain.accepted.lock().unwrap(), vec![3, 4]);
+        // the account is only queried for the first tx
+        assert_eq!(chain.account_queries.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn sequence_is_resynced_on_mismatch() {
+        let chain = chain(3);
+        let sender = node(&chain).sender(SenderOptions::default()).await;
+        sender.commit_tx_any(vec![], None).await.unwrap();
+
+        // another client sent txs from the same account
+        *chain.sequence.lock().unwrap() = 7;
+        sender.commit_tx_any(vec![], None).await.unwrap();
+
+        assert_eq!(*chain.accepted.lock().unwrap(), vec![3, 7]);
+        // the expected sequence is taken from the error instead of querying the account again
+        assert_eq!(chain.account_queries.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn explicit_sequence_is_not_tracked() {
+        let chain = chain(3);
+        let sender = node(&chain).sender(SenderOptions::default()).await;
+
+        let body = TxBuilder::build_body_any(vec![], None, 0);
+        let mut tx_builder = TxBuilder::new(body);
+        tx_builder.sequence(3);
+        sender.build_and_broadcast(&mut tx_builder).await.unwrap();
+
+        // the tracker wasn't moved by the explicit sequence, so the next tx is resynced
+        sender.commit_tx_any(vec![], None).await.unwrap();
+        assert_eq!(*chain.accepted.lock().unwrap(), vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn pipelined_txs_get_consecutive_sequences() {
+        let chain = chain(3);
+        let sender = node(&chain).sender(SenderOptions::default()).await;
+
+        let results = sender
+            .commit_txs_pipelined(vec![vec![], vec![], vec![]], None)
+            .await
+            .unwrap();
+
+        let hashes: Vec<_> = results
+            .into_iter()
+            .map(|result| result.unwrap().txhash)
+            .collect();
+        assert_eq!(hashes, vec!["TX3", "TX4", "TX5"]);
+        assert_eq!(*chain.accepted.lock().unwrap(), vec![3, 4, 5]);
+    }
 }
@@ -0,0 +1,246 @@
+//! In-process gRPC node the daemon tests run against.
+//!
+//! The node serves the calls of chain [`CHAIN_ID`] with the handlers registered by the test,
+//! other calls fail with [`Code::Unimplemented`](tonic::Code::Unimplemented).
+
+use std::{collections::HashMap, convert::Infallible, rc::Rc, sync::Arc};
+
+use cosmrs::proto::tendermint::{
+    google::protobuf::Timestamp,
+    types::{Block, BlockId, Data, EvidenceList, Header, PartSetHeader},
+    version::Consensus,
+};
+use hyper::{server::conn::Http, service::service_fn, Body};
+use ibc_chain_registry::chain::Grpc;
+use prost::Message;
+use secp256k1::All;
+use tokio::net::TcpListener;
+use tonic::{
+    codegen::{http, BoxFuture},
+    server::{Grpc as GrpcServer, UnaryService},
+    Status,
+};
+
+use super::{
+    channel::ChannelOptions,
+    cosmos_modules::{
+        auth::{BaseAccount, QueryAccountResponse},
+        tendermint::{
+            GetBlockByHeightRequest, GetBlockByHeightResponse, GetLatestBlockRequest,
+            GetLatestBlockResponse, GetNodeInfoRequest, GetNodeInfoResponse,
+        },
+    },
+    rest::RawCodec,
+    sender::{Sender, SenderOptions},
+    state::DaemonState,
+    state_backend::MemoryBackend,
+    ChainInfoOwned, ChainKind, NetworkInfoOwned,
+};
+
+pub(crate) const CHAIN_ID: &str = "standin-1";
+pub(crate) const FEE_DENOM: &str = "ustake";
+/// Static gas price of the chain
+pub(crate) const GAS_PRICE: f64 = 0.025;
+/// Height of the latest block, blocks are 5 seconds apart
+const LATEST_HEIGHT: i64 = 100;
+const MNEMONIC: &str = "wonder caution square unveil april art add hover spend smile proud admit modify old copper throw crew happy nature luggage reopen exhibit ordinary napkin";
+
+type Handler = Arc<dyn Fn(Vec<u8>) -> Result<Vec<u8>, Status> + Send + Sync>;
+
+/// gRPC node answering unary calls with the handlers of the test
+#[derive(Clone)]
+pub(crate) struct StandInNode {
+    handlers: HashMap<&'static str, Handler>,
+}
+
+impl StandInNode {
+    /// Node that answers the health check and serves blocks
+    pub(crate) fn new() -> Self {
+        StandInNode {
+            handlers: HashMap::new(),
+        }
+        .unary(
+            "/cosmos.base.tendermint.v1beta1.Service/GetNodeInfo",
+            |_: GetNodeInfoRequest| {
+                let mut response = GetNodeInfoResponse {
+                    default_node_info: Some(Default::default()),
+                    ..Default::default()
+                };
+                if let Some(info) = response.default_node_info.as_mut() {
+                    info.network = CHAIN_ID.to_string();
+                }
+                Ok(response)
+            },
+        )
+        .unary(
+            "/cosmos.base.tendermint.v1beta1.Service/GetLatestBlock",
+            |_: GetLatestBlockRequest| {
+                Ok(GetLatestBlockResponse {
+                    block_id: Some(block_id()),
+                    block: Some(block(LATEST_HEIGHT)),
+                    ..Default::default()
+                })
+            },
+        )
+        .unary(
+            "/cosmos.base.tendermint.v1beta1.Service/GetBlockByHeight",
+            |request: GetBlockByHeightRequest| {
+                Ok(GetBlockByHeightResponse {
+                    block_id: Some(block_id()),
+                    block: Some(block(request.height)),
+                    ..Default::default()
+                })
+            },
+        )
+    }
+
+    /// Answer the calls to `path` with `handler`
+    pub(crate) fn unary<Req, Resp, F>(mut self, path: &'static str, handler: F) -> Self
+    where
+        Req: Message + Default,
+        Resp: Message,
+        F: Fn(Req) -> Result<Resp, Status> + Send + Sync + 'static,
+    {
+        let handler: Handler = Arc::new(move |request: Vec<u8>| {
+            let request = Req::decode(request.as_slice())
+                .map_err(|e| Status::invalid_argument(e.to_string()))?;
+            handler(request).map(|response| response.encode_to_vec())
+        });
+        self.handlers.insert(path, handler);
+        self
+    }
+
+    /// Serve the node on a free local port
+    pub(crate) async fn spawn(self) -> Grpc {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let node = Arc::new(self);
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let node = node.clone();
+                let service = service_fn(move |req| {
+                    let node = node.clone();
+                    async move { Ok::<_, Infallible>(node.serve(req).await) }
+                });
+                tokio::spawn(
+                    Http::new()
+                        .http2_only(true)
+                        .serve_connection(stream, service),
+                );
+            }
+        });
+        Grpc {
+            address: format!("http://{addr}"),
+            provider: None,
+        }
+    }
+
+    /// Daemon state of a chain whose only endpoint is this node, stored in memory
+    pub(crate) async fn state(self) -> Rc<DaemonState> {
+        let chain = ChainInfoOwned {
+            chain_id: CHAIN_ID.to_string(),
+            gas_denom: FEE_DENOM.to_string(),
+            gas_price: GAS_PRICE,
+            grpc_urls: vec![self.spawn().await.address],
+            lcd_url: None,
+            fcd_url: None,
+            network_info: NetworkInfoOwned {
+                id: "standin".to_string(),
+                pub_address_prefix: "juno".to_string(),
+                coin_type: 118,
+            },
+            kind: ChainKind::Local,
+        };
+        let state = DaemonState::new_with_backend(
+            chain.into(),
+            "default".to_string(),
+            ChannelOptions::default(),
+            Some(Arc::new(MemoryBackend::new())),
+        )
+        .await
+        .unwrap();
+        Rc::new(state)
+    }
+
+    /// Sender of [`MNEMONIC`] connected to this node
+    pub(crate) async fn sender(self, options: SenderOptions) -> Sender<All> {
+        Sender::from_mnemonic_with_options(&self.state().await, MNEMONIC, options).unwrap()
+    }
+
+    async fn serve(&self, req: http::Request<Body>) -> http::Response<tonic::body::BoxBody> {
+        let call = StandInCall {
+            handler: self.handlers.get(req.uri().path()).cloned(),
+            path: req.uri().path().to_string(),
+        };
+        GrpcServer::new(RawCodec).unary(call, req).await
+    }
+}
+
+/// A single call served by the stand-in node
+struct StandInCall {
+    handler: Option<Handler>,
+    path: String,
+}
+
+impl UnaryService<Vec<u8>> for StandInCall {
+    type Response = Vec<u8>;
+    type Future = BoxFuture<tonic::Response<Vec<u8>>, Status>;
+
+    fn call(&mut self, request: tonic::Request<Vec<u8>>) -> Self::Future {
+        let response = match &self.handler {
+            Some(handler) => handler(request.into_inner()),
+            None => Err(Status::unimplemented(format!(
+                "{} is not served by the stand-in node",
+                self.path
+            ))),
+        };
+        Box::pin(async move { response.map(tonic::Response::new) })
+    }
+}
+
+/// Response to an account query for a base account
+pub(crate) fn account(address: String, sequence: u64) -> QueryAccountResponse {
+    let account = BaseAccount {
+        address,
+        pub_key: None,
+        account_number: 1,
+        sequence,
+    };
+    QueryAccountResponse {
+        account: Some(cosmrs::Any {
+            type_url: "/cosmos.auth.v1beta1.BaseAccount".to_string(),
+            value: account.encode_to_vec(),
+        }),
+    }
+}
+
+fn block_id() -> BlockId {
+    BlockId {
+        hash: vec![],
+        part_set_header: Some(PartSetHeader {
+            total: 0,
+            hash: vec![],
+        }),
+    }
+}
+
+fn block(height: i64) -> Block {
+    Block {
+        header: Some(Header {
+            version: Some(Consensus { block: 11, app: 0 }),
+            chain_id: CHAIN_ID.to_string(),
+            height,
+            time: Some(Timestamp {
+                seconds: 1_685_620_800 + height * 5,
+                nanos: 0,
+            }),
+            last_block_id: Some(block_id()),
+            proposer_address: vec![0; 20],
+            ..Default::default()
+        }),
+        data: Some(Data { txs: vec![] }),
+        evidence: Some(EvidenceList { evidence: vec![] }),
+        last_commit: None,
+    }
+}
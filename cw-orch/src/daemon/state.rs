@@ -1,176 +1,364 @@
-use super::error::DaemonError;
+use super::{error::DaemonError, DaemonChannel};
 use crate::{
-    daemon::{chain_info::ChainKind, channel::GrpcChannel},
+    daemon::{
+        chain_info::ChainKind,
+        channel::{ChannelOptions, GrpcChannel, GrpcEndpoints, Transport},
+        rest::RestChannel,
+        state_backend::{
+            check_deployment_id, ChainScope, JsonFileBackend, StateBackend, CODE_IDS_NAMESPACE,
+            HISTORY_NAMESPACE, JOURNAL_NAMESPACE,
+        },
+        state_export::{DeploymentExport, ImportSummary, MergeStrategy},
+    },
     error::CwOrchError,
-    state::{DeployDetails, StateInterface},
+    paths::WasmLimits,
+    state::{DeployDetails, DeploymentRecord, StateInterface, StepRecord},
 };
 
 use cosmwasm_std::Addr;
 use ibc_chain_registry::chain::ChainData;
 use serde::Serialize;
-use serde_json::{json, Value};
-use std::{collections::HashMap, env, fs::File, path::Path, rc::Rc};
-use tonic::transport::Channel;
+use serde_json::{json, Map, Value};
+use std::{collections::HashMap, rc::Rc, sync::Arc};
 
 /// Stores the chain information and deployment state.
-/// Uses a simple JSON file to store the deployment information locally.
+/// The deployment information is stored in a [`StateBackend`], a local JSON file by default.
 #[derive(Clone, Debug)]
 pub struct DaemonState {
-    /// this is passed via env var STATE_FILE
-    pub json_file_path: String,
+    /// Storage of the deployment state, the JSON file at the `STATE_FILE` env var by default
+    pub backend: Arc<dyn StateBackend>,
     /// Deployment identifier
     pub deployment_id: String,
     /// gRPC channel
-    pub grpc_channel: Channel,
+    pub grpc_channel: DaemonChannel,
+    /// Healthy endpoints the channel can fail over to
+    pub grpc_endpoints: GrpcEndpoints,
     /// Information about the chain
     pub chain_data: ChainData,
+    /// Limits wasm files are validated against before they are uploaded
+    pub wasm_limits: WasmLimits,
 }
 
 impl DaemonState {
+    /// Path of the JSON state file, `None` if the state is not stored in a file
+    #[deprecated(note = "the state is stored in `backend`, use `StateBackend::file_path`")]
+    pub fn json_file_path(&self) -> Option<String> {
+        self.backend
+            .file_path()
+            .map(|path| path.to_string_lossy().to_string())
+    }
+
     /// Creates a new state from the given chain data and deployment id.
     /// Attempts to connect to any of the provided gRPC endpoints.
     pub async fn new(
+        chain_data: ChainData,
+        deployment_id: String,
+    ) -> Result<DaemonState, DaemonError> {
+        Self::new_with_options(chain_data, deployment_id, ChannelOptions::default()).await
+    }
+
+    /// Creates a new state, connecting to the chain with the given transport and endpoint policy.
+    pub async fn new_with_options(
+        chain_data: ChainData,
+        deployment_id: String,
+        options: ChannelOptions,
+    ) -> Result<DaemonState, DaemonError> {
+        Self::new_with_backend(chain_data, deployment_id, options, None).await
+    }
+
+    /// Creates a new state that stores the deployment information in `backend`.
+    /// Defaults to the JSON file at the `STATE_FILE` env var, see [`JsonFileBackend::from_env`].
+    /// Errors if the deployment id is one of the [`RESERVED_NAMESPACES`](super::state_backend::RESERVED_NAMESPACES).
+    pub async fn new_with_backend(
         mut chain_data: ChainData,
         deployment_id: String,
+        options: ChannelOptions,
+        backend: Option<Arc<dyn StateBackend>>,
     ) -> Result<DaemonState, DaemonError> {
-        if chain_data.apis.grpc.is_empty() {
-            return Err(DaemonError::GRPCListIsEmpty);
-        }
+        check_deployment_id(&deployment_id)?;
 
-        log::info!("Found {} gRPC endpoints", chain_data.apis.grpc.len());
-
-        // find working grpc channel
-        let grpc_channel =
-            GrpcChannel::connect(&chain_data.apis.grpc, &chain_data.chain_id).await?;
-
-        // check if STATE_FILE en var is configured, default to state.json
-        let mut json_file_path = env::var("STATE_FILE").unwrap_or("./state.json".to_string());
-
-        // if the network we are connecting is a local kind, add it to the fn
-        if chain_data.network_type == ChainKind::Local.to_string() {
-            let name = Path::new(&json_file_path)
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap();
-            let folder = Path::new(&json_file_path)
-                .parent()
-                .unwrap()
-                .to_str()
-                .unwrap();
-
-            json_file_path = format!("{folder}/{name}_local.json");
-        }
+        // find working endpoints, fastest first
+        let grpc_endpoints = match options.transport {
+            Transport::Grpc => {
+                if chain_data.apis.grpc.is_empty() {
+                    return Err(DaemonError::GRPCListIsEmpty);
+                }
+
+                log::info!("Found {} gRPC endpoints", chain_data.apis.grpc.len());
 
-        // Try to get the standard fee token (probably shortest denom)
-        let shortest_denom_token = chain_data.fees.fee_tokens.iter().fold(
-            chain_data.fees.fee_tokens[0].clone(),
-            |acc, item| {
-                if item.denom.len() < acc.denom.len() {
-                    item.clone()
-                } else {
-                    acc
+                GrpcChannel::connect_with_options(
+                    &chain_data.apis.grpc,
+                    &chain_data.chain_id,
+                    &options,
+                )
+                .await?
+            }
+            Transport::Rest => {
+                if chain_data.apis.rest.is_empty() {
+                    return Err(DaemonError::RestListIsEmpty);
                 }
-            },
-        );
-        // set a single fee token
-        chain_data.fees.fee_tokens = vec![shortest_denom_token];
+
+                log::info!("Found {} REST endpoints", chain_data.apis.rest.len());
+
+                RestChannel::connect_with_policy(
+                    &chain_data.apis.rest,
+                    &chain_data.chain_id,
+                    options.policy.clone(),
+                )
+                .await?
+            }
+        };
+        let grpc_channel = grpc_endpoints.channel();
+
+        // if the network we are connecting is a local kind, the default state file is suffixed with _local
+        let backend = backend.unwrap_or_else(|| {
+            let local = chain_data.network_type == ChainKind::Local.to_string();
+            Arc::new(JsonFileBackend::from_env(local))
+        });
+
+        // Use the standard fee token (probably shortest denom) by default.
+        // The other fee tokens are kept so they can be selected for paying fees.
+        chain_data
+            .fees
+            .fee_tokens
+            .sort_by_key(|token| token.denom.len());
 
         // build daemon state
         let state = DaemonState {
-            json_file_path,
+            backend,
             deployment_id,
             grpc_channel,
+            grpc_endpoints,
             chain_data,
+            wasm_limits: WasmLimits::default(),
         };
 
-        log::info!(
-            "Writing daemon state JSON file: {:#?}",
-            state.json_file_path
-        );
+        log::info!("Using daemon state backend: {:?}", state.backend);
 
-        // write json state file
-        crate::daemon::json_file::write(
-            &state.json_file_path,
-            &state.chain_data.chain_id.to_string(),
-            &state.chain_data.chain_name,
-            &state.deployment_id,
-        );
+        // prepare the state storage for this chain and deployment
+        state.backend.init(&state.scope(), &state.deployment_id)?;
 
         // finish
         Ok(state)
     }
 
-    /// Get the state filepath and read it as json
-    fn read_state(&self) -> serde_json::Value {
-        crate::daemon::json_file::read(&self.json_file_path)
+    /// Chain the state entries of this daemon belong to
+    pub fn scope(&self) -> ChainScope {
+        ChainScope {
+            chain_name: self.chain_data.chain_name.clone(),
+            chain_id: self.chain_data.chain_id.to_string(),
+        }
     }
 
-    /// Retrieve a stateful value using the chainId and networkId
-    fn get(&self, key: &str) -> Value {
-        let json = self.read_state();
-        json[&self.chain_data.chain_name][&self.chain_data.chain_id.to_string()][key].clone()
+    /// Export the code ids, addresses and history of this deployment.
+    /// Export another deployment id to compare them with [`DeploymentExport::diff`].
+    pub fn export(&self) -> Result<DeploymentExport, DaemonError> {
+        DeploymentExport::export(self.backend.as_ref(), &self.scope(), &self.deployment_id)
+    }
+
+    /// Import a deployment, possibly exported from another chain, deployment id or state file, into this deployment.
+    /// Code ids can only be imported on the chain id they were exported from, see [`DeploymentExport::import`].
+    pub fn import(
+        &self,
+        deployment: &DeploymentExport,
+        strategy: MergeStrategy,
+    ) -> Result<ImportSummary, DaemonError> {
+        deployment.import(
+            self.backend.as_ref(),
+            &self.scope(),
+            &self.deployment_id,
+            strategy,
+        )
+    }
+
+    /// Retrieve the stateful values of a namespace using the chainId and networkId
+    fn get(&self, namespace: &str) -> Result<Map<String, Value>, DaemonError> {
+        self.backend.get(&self.scope(), namespace)
     }
 
     /// Set a stateful value using the chainId and networkId
-    fn set<T: Serialize>(&self, key: &str, contract_id: &str, value: T) {
-        let mut json = self.read_state();
+    fn set<T: Serialize>(
+        &self,
+        namespace: &str,
+        contract_id: &str,
+        value: T,
+    ) -> Result<(), DaemonError> {
+        self.backend
+            .set(&self.scope(), namespace, contract_id, json!(value))
+    }
+}
 
-        json[&self.chain_data.chain_name][&self.chain_data.chain_id.to_string()][key]
-            [contract_id] = json!(value);
+fn parse_address(contract_id: &str, value: &Value) -> Result<Addr, DaemonError> {
+    value
+        .as_str()
+        .map(Addr::unchecked)
+        .ok_or_else(|| DaemonError::InvalidStateEntry {
+            key: contract_id.to_string(),
+            value: value.to_string(),
+        })
+}
 
-        serde_json::to_writer_pretty(File::create(&self.json_file_path).unwrap(), &json).unwrap();
+/// Records of the deployment id in the history of a contract, stored as `deployment_id -> [records]`
+fn parse_history(
+    contract_id: &str,
+    history: &Value,
+    deployment_id: &str,
+) -> Result<Vec<DeploymentRecord>, DaemonError> {
+    match history.get(deployment_id) {
+        None | Some(Value::Null) => Ok(vec![]),
+        Some(records) => {
+            serde_json::from_value(records.clone()).map_err(|e| DaemonError::InvalidStateEntry {
+                key: format!("{HISTORY_NAMESPACE}/{contract_id}/{deployment_id}"),
+                value: e.to_string(),
+            })
+        }
     }
 }
 
+/// Append the record to the history of the deployment id
+fn push_history(
+    contract_id: &str,
+    history: &mut Value,
+    deployment_id: &str,
+    record: Value,
+) -> Result<(), DaemonError> {
+    if history.is_null() {
+        *history = json!({});
+    }
+    let invalid = |value: &Value| DaemonError::InvalidStateEntry {
+        key: format!("{HISTORY_NAMESPACE}/{contract_id}"),
+        value: value.to_string(),
+    };
+    if !history.is_object() {
+        return Err(invalid(history));
+    }
+    let records = &mut history[deployment_id];
+    if records.is_null() {
+        *records = json!([]);
+    }
+    match records {
+        Value::Array(records) => {
+            records.push(record);
+            Ok(())
+        }
+        other => Err(invalid(other)),
+    }
+}
+
+/// Journal of the deployment id in the journals of a plan, stored as `deployment_id -> step -> record`
+fn parse_journal(
+    plan: &str,
+    journals: &Value,
+    deployment_id: &str,
+) -> Result<HashMap<String, StepRecord>, DaemonError> {
+    match journals.get(deployment_id) {
+        None | Some(Value::Null) => Ok(HashMap::new()),
+        Some(journal) => {
+            serde_json::from_value(journal.clone()).map_err(|e| DaemonError::InvalidStateEntry {
+                key: format!("{JOURNAL_NAMESPACE}/{plan}/{deployment_id}"),
+                value: e.to_string(),
+            })
+        }
+    }
+}
+
+/// Set the record of the step in the journal of the deployment id, or remove the whole journal if `None`
+fn update_journal(
+    plan: &str,
+    journals: &mut Value,
+    deployment_id: &str,
+    step: Option<(&str, Value)>,
+) -> Result<(), DaemonError> {
+    if journals.is_null() {
+        *journals = json!({});
+    }
+    let invalid = |value: &Value| DaemonError::InvalidStateEntry {
+        key: format!("{JOURNAL_NAMESPACE}/{plan}"),
+        value: value.to_string(),
+    };
+    let journals = match journals {
+        Value::Object(journals) => journals,
+        other => return Err(invalid(other)),
+    };
+    let Some((step, record)) = step else {
+        journals.remove(deployment_id);
+        return Ok(());
+    };
+    let journal = journals.entry(deployment_id).or_insert_with(|| json!({}));
+    match journal {
+        Value::Object(journal) => {
+            journal.insert(step.to_string(), record);
+            Ok(())
+        }
+        other => Err(invalid(other)),
+    }
+}
+
+fn parse_code_id(contract_id: &str, value: &Value) -> Result<u64, DaemonError> {
+    value
+        .as_u64()
+        .ok_or_else(|| DaemonError::InvalidStateEntry {
+            key: contract_id.to_string(),
+            value: value.to_string(),
+        })
+}
+
 impl StateInterface for Rc<DaemonState> {
     /// Read address for contract in deployment id from state file
     fn get_address(&self, contract_id: &str) -> Result<Addr, CwOrchError> {
-        let value = self
-            .get(&self.deployment_id)
+        let addresses = self.get(&self.deployment_id)?;
+        let value = addresses
             .get(contract_id)
-            .ok_or_else(|| CwOrchError::AddrNotInStore(contract_id.to_owned()))?
-            .clone();
-        Ok(Addr::unchecked(value.as_str().unwrap()))
+            .ok_or_else(|| CwOrchError::AddrNotInStore(contract_id.to_owned()))?;
+        Ok(parse_address(contract_id, value)?)
     }
 
-    /// Set address for contract in deployment id in state file
+    /// Set address for contract in deployment id in state file.
+    /// Panics if the state can't be written, use [`StateInterface::try_set_address`] to handle it.
     fn set_address(&mut self, contract_id: &str, address: &Addr) {
-        self.set(&self.deployment_id, contract_id, address.as_str());
+        self.try_set_address(contract_id, address)
+            .unwrap_or_else(|e| panic!("Failed to store {contract_id} in the daemon state: {e}"))
+    }
+
+    fn try_set_address(&mut self, contract_id: &str, address: &Addr) -> Result<(), CwOrchError> {
+        Ok(self.set(&self.deployment_id, contract_id, address.as_str())?)
     }
 
     /// Get the locally-saved version of the contract's version on this network
     fn get_code_id(&self, contract_id: &str) -> Result<u64, CwOrchError> {
-        let value = self
-            .get("code_ids")
+        let code_ids = self.get(CODE_IDS_NAMESPACE)?;
+        let value = code_ids
             .get(contract_id)
-            .ok_or_else(|| CwOrchError::CodeIdNotInStore(contract_id.to_owned()))?
-            .clone();
-        Ok(value.as_u64().unwrap())
+            .ok_or_else(|| CwOrchError::CodeIdNotInStore(contract_id.to_owned()))?;
+        Ok(parse_code_id(contract_id, value)?)
     }
 
-    /// Set the locally-saved version of the contract's latest version on this network
+    /// Set the locally-saved version of the contract's latest version on this network.
+    /// Panics if the state can't be written, use [`StateInterface::try_set_code_id`] to handle it.
     fn set_code_id(&mut self, contract_id: &str, code_id: u64) {
-        self.set("code_ids", contract_id, code_id);
+        self.try_set_code_id(contract_id, code_id)
+            .unwrap_or_else(|e| panic!("Failed to store {contract_id} in the daemon state: {e}"))
+    }
+
+    fn try_set_code_id(&mut self, contract_id: &str, code_id: u64) -> Result<(), CwOrchError> {
+        Ok(self.set(CODE_IDS_NAMESPACE, contract_id, code_id)?)
     }
 
     /// Get all addresses for deployment id from state file
     fn get_all_addresses(&self) -> Result<HashMap<String, Addr>, CwOrchError> {
         let mut store = HashMap::new();
-        let addresses = self.get(&self.deployment_id);
-        let value = addresses.as_object().unwrap();
-        for (id, addr) in value {
-            store.insert(id.clone(), Addr::unchecked(addr.as_str().unwrap()));
+        for (id, addr) in self.get(&self.deployment_id)? {
+            let addr = parse_address(&id, &addr)?;
+            store.insert(id, addr);
         }
         Ok(store)
     }
 
     fn get_all_code_ids(&self) -> Result<HashMap<String, u64>, CwOrchError> {
         let mut store = HashMap::new();
-        let code_ids = self.get("code_ids");
-        let value = code_ids.as_object().unwrap();
-        for (id, code_id) in value {
-            store.insert(id.clone(), code_id.as_u64().unwrap());
+        for (id, code_id) in self.get(CODE_IDS_NAMESPACE)? {
+            let code_id = parse_code_id(&id, &code_id)?;
+            store.insert(id, code_id);
         }
         Ok(store)
     }
@@ -182,4 +370,139 @@ impl StateInterface for Rc<DaemonState> {
             deployment_id: self.deployment_id.clone(),
         }
     }
+
+    /// Read the history of the contract in deployment id from state file
+    fn get_history(&self, contract_id: &str) -> Result<Vec<DeploymentRecord>, CwOrchError> {
+        let history = self.get(HISTORY_NAMESPACE)?;
+        match history.get(contract_id) {
+            Some(history) => Ok(parse_history(contract_id, history, &self.deployment_id)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Append to the history of the contract in deployment id in state file
+    fn try_record_deployment(
+        &mut self,
+        contract_id: &str,
+        record: DeploymentRecord,
+    ) -> Result<(), CwOrchError> {
+        let record = json!(record);
+        Ok(self.backend.update(
+            &self.scope(),
+            HISTORY_NAMESPACE,
+            contract_id,
+            &mut |history| push_history(contract_id, history, &self.deployment_id, record.clone()),
+        )?)
+    }
+
+    /// Read the journal of the plan in deployment id from state file
+    fn get_journal(&self, plan: &str) -> Result<HashMap<String, StepRecord>, CwOrchError> {
+        let journals = self.get(JOURNAL_NAMESPACE)?;
+        match journals.get(plan) {
+            Some(journals) => Ok(parse_journal(plan, journals, &self.deployment_id)?),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    /// Store the outcome of the step in the journal of the plan in deployment id in state file
+    fn try_record_step(
+        &mut self,
+        plan: &str,
+        step: &str,
+        record: StepRecord,
+    ) -> Result<(), CwOrchError> {
+        let record = json!(record);
+        Ok(self
+            .backend
+            .update(&self.scope(), JOURNAL_NAMESPACE, plan, &mut |journals| {
+                update_journal(
+                    plan,
+                    journals,
+                    &self.deployment_id,
+                    Some((step, record.clone())),
+                )
+            })?)
+    }
+
+    /// Remove the journal of the plan in deployment id from state file
+    fn try_clear_journal(&mut self, plan: &str) -> Result<(), CwOrchError> {
+        Ok(self
+            .backend
+            .update(&self.scope(), JOURNAL_NAMESPACE, plan, &mut |journals| {
+                update_journal(plan, journals, &self.deployment_id, None)
+            })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{DeploymentAction, StepKind, StepStatus};
+
+    #[test]
+    fn malformed_entries_error() {
+        let res = parse_address("counter", &json!(12));
+        assert!(matches!(res, Err(DaemonError::InvalidStateEntry { key, .. }) if key == "counter"));
+
+        let res = parse_code_id("counter", &json!("juno1counter"));
+        assert!(matches!(res, Err(DaemonError::InvalidStateEntry { .. })));
+
+        assert_eq!(parse_code_id("counter", &json!(12)).unwrap(), 12);
+    }
+
+    #[test]
+    fn history_per_deployment() {
+        let mut history = Value::Null;
+        let record = DeploymentRecord {
+            code_id: Some(1),
+            ..DeploymentRecord::new(DeploymentAction::Upload)
+        };
+        push_history("counter", &mut history, "default", json!(record)).unwrap();
+        push_history("counter", &mut history, "default", json!(record)).unwrap();
+        push_history("counter", &mut history, "v2", json!(record)).unwrap();
+
+        assert_eq!(
+            parse_history("counter", &history, "default").unwrap(),
+            vec![record.clone(), record.clone()]
+        );
+        assert_eq!(parse_history("counter", &history, "v2").unwrap().len(), 1);
+        assert!(parse_history("counter", &history, "v3").unwrap().is_empty());
+
+        let mut malformed = json!({ "default": "not a list" });
+        assert!(parse_history("counter", &malformed, "default").is_err());
+        assert!(push_history("counter", &mut malformed, "default", json!(record)).is_err());
+    }
+
+    #[test]
+    fn journal_per_deployment() {
+        let mut journals = Value::Null;
+        let record = StepRecord {
+            kind: StepKind::Upload,
+            status: StepStatus::Completed,
+            error: None,
+            timestamp: 0,
+            code_ids: vec![],
+            addresses: vec![],
+        };
+        update_journal(
+            "app",
+            &mut journals,
+            "default",
+            Some(("upload", json!(record))),
+        )
+        .unwrap();
+        update_journal("app", &mut journals, "v2", Some(("upload", json!(record)))).unwrap();
+
+        let journal = parse_journal("app", &journals, "default").unwrap();
+        assert_eq!(journal.get("upload"), Some(&record));
+
+        update_journal("app", &mut journals, "default", None).unwrap();
+        assert!(parse_journal("app", &journals, "default")
+            .unwrap()
+            .is_empty());
+        assert_eq!(parse_journal("app", &journals, "v2").unwrap().len(), 1);
+
+        let malformed = json!({ "default": ["upload"] });
+        assert!(parse_journal("app", &malformed, "default").is_err());
+    }
 }
@@ -0,0 +1,310 @@
+//! Storage backends for the deployment state of daemons.
+//!
+//! The state is laid out as `chain_name -> chain_id -> namespace -> contract_id -> value`,
+//! where the namespace is either a deployment id, holding the contract addresses, or one of the [`RESERVED_NAMESPACES`].
+
+mod file;
+mod memory;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use file::JsonFileBackend;
+pub use memory::MemoryBackend;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteBackend;
+
+use std::{fmt::Debug, path::Path};
+
+use serde_json::{Map, Value};
+
+use super::error::DaemonError;
+
+/// Namespace that holds the code ids of a chain
+pub const CODE_IDS_NAMESPACE: &str = "code_ids";
+/// Namespace that holds the deployment history of the contracts of a chain, by deployment id
+pub const HISTORY_NAMESPACE: &str = "history";
+/// Namespace that holds the journals of the deployment plans run on a chain, by deployment id
+pub const JOURNAL_NAMESPACE: &str = "journal";
+/// Namespaces that aren't deployments, so they can't be used as deployment id
+pub const RESERVED_NAMESPACES: [&str; 3] =
+    [CODE_IDS_NAMESPACE, HISTORY_NAMESPACE, JOURNAL_NAMESPACE];
+
+/// Errors if the deployment id would overwrite one of the [`RESERVED_NAMESPACES`]
+pub(crate) fn check_deployment_id(deployment_id: &str) -> Result<(), DaemonError> {
+    if RESERVED_NAMESPACES.contains(&deployment_id) {
+        return Err(DaemonError::ReservedDeploymentId(deployment_id.to_string()));
+    }
+    Ok(())
+}
+
+/// Chain the state entries belong to
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ChainScope {
+    /// E.g. juno
+    pub chain_name: String,
+    /// E.g. juno-1
+    pub chain_id: String,
+}
+
+/// Storage of the deployment state of a [`DaemonState`](super::DaemonState).
+///
+/// Set a custom backend with `DaemonBuilder::state_backend`, the default is a [`JsonFileBackend`] at the `STATE_FILE` env variable.
+pub trait StateBackend: Debug + Send + Sync {
+    /// Prepare the storage of the chain for the deployment id.
+    fn init(&self, scope: &ChainScope, deployment_id: &str) -> Result<(), DaemonError>;
+
+    /// Get all the entries stored under the namespace of the chain, by contract id.
+    fn get(&self, scope: &ChainScope, namespace: &str) -> Result<Map<String, Value>, DaemonError>;
+
+    /// Store the value of the contract id under the namespace of the chain.
+    fn set(
+        &self,
+        scope: &ChainScope,
+        namespace: &str,
+        contract_id: &str,
+        value: Value,
+    ) -> Result<(), DaemonError>;
+
+    /// Update the value of the contract id under the namespace of the chain, the value is `null` if missing.
+    /// Backends that can be shared between processes should apply the update atomically.
+    fn update(
+        &self,
+        scope: &ChainScope,
+        namespace: &str,
+        contract_id: &str,
+        update: &mut dyn FnMut(&mut Value) -> Result<(), DaemonError>,
+    ) -> Result<(), DaemonError> {
+        let mut value = self
+            .get(scope, namespace)?
+            .remove(contract_id)
+            .unwrap_or(Value::Null);
+        update(&mut value)?;
+        self.set(scope, namespace, contract_id, value)
+    }
+
+    /// Update all the entries of the chain in one change, laid out as `namespace -> contract_id -> value`.
+    /// Backends that can be shared between processes should apply the update atomically.
+    fn update_chain(
+        &self,
+        scope: &ChainScope,
+        update: &mut dyn FnMut(&mut Map<String, Value>) -> Result<(), DaemonError>,
+    ) -> Result<(), DaemonError>;
+
+    /// Path of the file the state is stored in, if any
+    fn file_path(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// Helpers for backends that keep the whole state in a single JSON tree
+pub(crate) mod tree {
+    use super::{ChainScope, DaemonError, CODE_IDS_NAMESPACE};
+    use serde_json::{Map, Value};
+
+    /// Object under `key`, inserted if missing
+    fn object_entry<'a>(
+        value: &'a mut Value,
+        key: &str,
+        path: &str,
+    ) -> Result<&'a mut Map<String, Value>, DaemonError> {
+        if value.is_null() {
+            *value = Value::Object(Map::new());
+        }
+        let entry = match value {
+            Value::Object(map) => map.entry(key).or_insert(Value::Null),
+            other if path.is_empty() => return Err(invalid_entry("state", other)),
+            other => return Err(invalid_entry(path, other)),
+        };
+        if entry.is_null() {
+            *entry = Value::Object(Map::new());
+        }
+        match entry {
+            Value::Object(map) => Ok(map),
+            other if path.is_empty() => Err(invalid_entry(key, other)),
+            other => Err(invalid_entry(&format!("{path}/{key}"), other)),
+        }
+    }
+
+    fn invalid_entry(key: &str, value: &Value) -> DaemonError {
+        DaemonError::InvalidStateEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// Namespaces of the chain, inserted if missing
+    pub(crate) fn chain_mut<'a>(
+        tree: &'a mut Value,
+        scope: &ChainScope,
+    ) -> Result<&'a mut Map<String, Value>, DaemonError> {
+        let chain = object_entry(tree, &scope.chain_name, "")?;
+        let chain = chain.entry(&scope.chain_id).or_insert(Value::Null);
+        if chain.is_null() {
+            *chain = Value::Object(Map::new());
+        }
+        match chain {
+            Value::Object(namespaces) => Ok(namespaces),
+            other => Err(invalid_entry(
+                &format!("{}/{}", scope.chain_name, scope.chain_id),
+                other,
+            )),
+        }
+    }
+
+    fn namespace_mut<'a>(
+        tree: &'a mut Value,
+        scope: &ChainScope,
+        namespace: &str,
+    ) -> Result<&'a mut Map<String, Value>, DaemonError> {
+        let chain = object_entry(tree, &scope.chain_name, "")?;
+        let chain = chain.entry(&scope.chain_id).or_insert(Value::Null);
+        let path = format!("{}/{}", scope.chain_name, scope.chain_id);
+        object_entry(chain, namespace, &path)
+    }
+
+    pub(crate) fn init(
+        tree: &mut Value,
+        scope: &ChainScope,
+        deployment_id: &str,
+    ) -> Result<(), DaemonError> {
+        namespace_mut(tree, scope, deployment_id)?;
+        namespace_mut(tree, scope, CODE_IDS_NAMESPACE)?;
+        Ok(())
+    }
+
+    pub(crate) fn get(
+        tree: &Value,
+        scope: &ChainScope,
+        namespace: &str,
+    ) -> Result<Map<String, Value>, DaemonError> {
+        match &tree[&scope.chain_name][&scope.chain_id][namespace] {
+            Value::Null => Ok(Map::new()),
+            Value::Object(entries) => Ok(entries.clone()),
+            other => Err(invalid_entry(
+                &format!("{}/{}/{}", scope.chain_name, scope.chain_id, namespace),
+                other,
+            )),
+        }
+    }
+
+    /// Value of the contract id, inserted as `null` if missing
+    pub(crate) fn entry<'a>(
+        tree: &'a mut Value,
+        scope: &ChainScope,
+        namespace: &str,
+        contract_id: &str,
+    ) -> Result<&'a mut Value, DaemonError> {
+        Ok(namespace_mut(tree, scope, namespace)?
+            .entry(contract_id)
+            .or_insert(Value::Null))
+    }
+
+    pub(crate) fn set(
+        tree: &mut Value,
+        scope: &ChainScope,
+        namespace: &str,
+        contract_id: &str,
+        value: Value,
+    ) -> Result<(), DaemonError> {
+        namespace_mut(tree, scope, namespace)?.insert(contract_id.to_string(), value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    pub(crate) fn scope() -> ChainScope {
+        ChainScope {
+            chain_name: "juno".to_string(),
+            chain_id: "juno-1".to_string(),
+        }
+    }
+
+    /// Checks the behavior every backend should have
+    pub(crate) fn check_backend(backend: &dyn StateBackend) {
+        let scope = scope();
+        backend.init(&scope, "default").unwrap();
+        assert!(backend.get(&scope, "default").unwrap().is_empty());
+
+        backend
+            .set(&scope, CODE_IDS_NAMESPACE, "counter", json!(12))
+            .unwrap();
+        backend
+            .set(&scope, "default", "counter", json!("juno1counter"))
+            .unwrap();
+        backend
+            .set(&scope, "default", "counter", json!("juno1counter2"))
+            .unwrap();
+
+        let code_ids = backend.get(&scope, CODE_IDS_NAMESPACE).unwrap();
+        assert_eq!(code_ids.get("counter"), Some(&json!(12)));
+        let addresses = backend.get(&scope, "default").unwrap();
+        assert_eq!(addresses.len(), 1);
+        assert_eq!(addresses.get("counter"), Some(&json!("juno1counter2")));
+
+        for entry in [1, 2] {
+            backend
+                .update(&scope, HISTORY_NAMESPACE, "counter", &mut |value| {
+                    if value.is_null() {
+                        *value = json!([]);
+                    }
+                    value.as_array_mut().unwrap().push(json!(entry));
+                    Ok(())
+                })
+                .unwrap();
+        }
+        let history = backend.get(&scope, HISTORY_NAMESPACE).unwrap();
+        assert_eq!(history.get("counter"), Some(&json!([1, 2])));
+
+        // a failing chain update changes nothing
+        let res = backend.update_chain(&scope, &mut |chain| {
+            chain["default"]["token"] = json!("juno1token");
+            Err(DaemonError::StdErr("abort".to_string()))
+        });
+        assert!(res.is_err());
+        assert!(!backend
+            .get(&scope, "default")
+            .unwrap()
+            .contains_key("token"));
+
+        backend
+            .update_chain(&scope, &mut |chain| {
+                assert_eq!(chain[CODE_IDS_NAMESPACE]["counter"], json!(12));
+                chain[CODE_IDS_NAMESPACE]["token"] = json!(13);
+                chain["default"]["token"] = json!("juno1token");
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(
+            backend
+                .get(&scope, CODE_IDS_NAMESPACE)
+                .unwrap()
+                .get("token"),
+            Some(&json!(13))
+        );
+        assert_eq!(backend.get(&scope, "default").unwrap().len(), 2);
+
+        let other_chain = ChainScope {
+            chain_id: "uni-6".to_string(),
+            ..scope
+        };
+        assert!(backend.get(&other_chain, "default").unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_tree_errors() {
+        let mut tree = json!({ "juno": { "juno-1": { "default": "not an object" } } });
+
+        assert!(matches!(
+            tree::get(&tree, &scope(), "default"),
+            Err(DaemonError::InvalidStateEntry { .. })
+        ));
+        assert!(matches!(
+            tree::set(&mut tree, &scope(), "default", "counter", json!("addr")),
+            Err(DaemonError::InvalidStateEntry { .. })
+        ));
+    }
+}
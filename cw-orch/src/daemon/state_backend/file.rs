@@ -0,0 +1,163 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{Map, Value};
+
+use super::{tree, ChainScope, StateBackend};
+use crate::{
+    daemon::{error::DaemonError, json_file},
+    state_file::{self, StateFile},
+};
+
+/// Stores the state in a JSON file, laid out as described in [`state_file`](crate::state_file).
+///
+/// Files in an older layout are migrated on the first change.
+/// Every change is applied to the latest content of the file while holding a lock on it, and the file is replaced atomically.
+/// Daemons and scripts that deploy concurrently, to the same or different chains, can share one state file.
+#[derive(Clone, Debug)]
+pub struct JsonFileBackend {
+    path: PathBuf,
+}
+
+impl JsonFileBackend {
+    /// Store the state in the JSON file at `path`, the file is created if missing.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Store the state in the file at the `STATE_FILE` env variable, defaults to `./state.json`.
+    /// Local chains use a separate file with a `_local` suffix, ex. `./state_local.json`.
+    pub fn from_env(local: bool) -> Self {
+        let path = env::var("STATE_FILE").unwrap_or("./state.json".to_string());
+        if !local {
+            return Self::new(path);
+        }
+
+        let path = Path::new(&path);
+        let name = path
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let folder = path.parent().unwrap_or_else(|| Path::new("."));
+        Self::new(folder.join(format!("{name}_local.json")))
+    }
+
+    /// Path of the state file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Typed content of the state file
+    pub fn state_file(&self) -> Result<StateFile, DaemonError> {
+        Ok(StateFile::from_json(json_file::read(&self.path)?)?)
+    }
+
+    /// Read the chains of the state file, in the current layout
+    fn read(&self) -> Result<Value, DaemonError> {
+        let mut json = state_file::migrate(json_file::read(&self.path)?)?;
+        Ok(json[state_file::CHAINS_KEY].take())
+    }
+
+    /// Apply `update` to the chains of the state file, migrating the file to the current layout
+    fn update_chains(
+        &self,
+        update: impl FnOnce(&mut Value) -> Result<(), DaemonError>,
+    ) -> Result<(), DaemonError> {
+        json_file::update(&self.path, |json| {
+            *json = state_file::migrate(json.take())?;
+            update(&mut json[state_file::CHAINS_KEY])
+        })
+    }
+}
+
+impl StateBackend for JsonFileBackend {
+    fn init(&self, scope: &ChainScope, deployment_id: &str) -> Result<(), DaemonError> {
+        self.update_chains(|chains| tree::init(chains, scope, deployment_id))
+    }
+
+    fn get(&self, scope: &ChainScope, namespace: &str) -> Result<Map<String, Value>, DaemonError> {
+        tree::get(&self.read()?, scope, namespace)
+    }
+
+    fn set(
+        &self,
+        scope: &ChainScope,
+        namespace: &str,
+        contract_id: &str,
+        value: Value,
+    ) -> Result<(), DaemonError> {
+        self.update_chains(|chains| tree::set(chains, scope, namespace, contract_id, value))
+    }
+
+    fn update(
+        &self,
+        scope: &ChainScope,
+        namespace: &str,
+        contract_id: &str,
+        update: &mut dyn FnMut(&mut Value) -> Result<(), DaemonError>,
+    ) -> Result<(), DaemonError> {
+        self.update_chains(|chains| update(tree::entry(chains, scope, namespace, contract_id)?))
+    }
+
+    fn update_chain(
+        &self,
+        scope: &ChainScope,
+        update: &mut dyn FnMut(&mut Map<String, Value>) -> Result<(), DaemonError>,
+    ) -> Result<(), DaemonError> {
+        self.update_chains(|chains| update(tree::chain_mut(chains, scope)?))
+    }
+
+    fn file_path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::state_backend::{
+        tests::{check_backend, scope},
+        CODE_IDS_NAMESPACE,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn json_file_backend() {
+        let path = env::temp_dir().join("cw-orch-json-file-backend.json");
+        let _ = std::fs::remove_file(&path);
+        let backend = JsonFileBackend::new(&path);
+
+        check_backend(&backend);
+
+        // the state survives reopening the file
+        let reopened = JsonFileBackend::new(&path);
+        assert_eq!(reopened.get(&scope(), "default").unwrap().len(), 1);
+        let state = reopened.state_file().unwrap();
+        assert_eq!(state.version, state_file::STATE_FILE_VERSION);
+        assert_eq!(state.chain_ids(), vec!["juno-1"]);
+    }
+
+    #[test]
+    fn legacy_file_is_migrated() {
+        let path = env::temp_dir().join("cw-orch-json-file-legacy.json");
+        std::fs::write(
+            &path,
+            json!({ "juno": { "juno-1": { "code_ids": { "counter": 12 } } } }).to_string(),
+        )
+        .unwrap();
+        let backend = JsonFileBackend::new(&path);
+
+        let code_ids = backend.get(&scope(), CODE_IDS_NAMESPACE).unwrap();
+        assert_eq!(code_ids.get("counter"), Some(&json!(12)));
+
+        backend.init(&scope(), "default").unwrap();
+        let json = json_file::read(&path).unwrap();
+        assert_eq!(json["version"], json!(state_file::STATE_FILE_VERSION));
+        assert_eq!(
+            json["chains"]["juno"]["juno-1"]["code_ids"]["counter"],
+            json!(12)
+        );
+    }
+}
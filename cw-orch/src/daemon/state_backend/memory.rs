@@ -0,0 +1,89 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use serde_json::{Map, Value};
+
+use super::{tree, ChainScope, StateBackend};
+use crate::daemon::error::DaemonError;
+
+/// Keeps the state in memory, it is lost when the backend is dropped.
+/// Useful for CI and dry runs that should not touch the state file.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    state: Mutex<Value>,
+}
+
+impl MemoryBackend {
+    /// Create an empty in-memory state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The state of all chains, laid out as the `chains` of the state file
+    pub fn json(&self) -> Value {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<Value> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl StateBackend for MemoryBackend {
+    fn init(&self, scope: &ChainScope, deployment_id: &str) -> Result<(), DaemonError> {
+        tree::init(&mut self.lock(), scope, deployment_id)
+    }
+
+    fn get(&self, scope: &ChainScope, namespace: &str) -> Result<Map<String, Value>, DaemonError> {
+        tree::get(&self.lock(), scope, namespace)
+    }
+
+    fn set(
+        &self,
+        scope: &ChainScope,
+        namespace: &str,
+        contract_id: &str,
+        value: Value,
+    ) -> Result<(), DaemonError> {
+        tree::set(&mut self.lock(), scope, namespace, contract_id, value)
+    }
+
+    fn update(
+        &self,
+        scope: &ChainScope,
+        namespace: &str,
+        contract_id: &str,
+        update: &mut dyn FnMut(&mut Value) -> Result<(), DaemonError>,
+    ) -> Result<(), DaemonError> {
+        update(tree::entry(
+            &mut self.lock(),
+            scope,
+            namespace,
+            contract_id,
+        )?)
+    }
+
+    fn update_chain(
+        &self,
+        scope: &ChainScope,
+        update: &mut dyn FnMut(&mut Map<String, Value>) -> Result<(), DaemonError>,
+    ) -> Result<(), DaemonError> {
+        let mut state = self.lock();
+        let chain = tree::chain_mut(&mut state, scope)?;
+        // only apply the update if it succeeds as a whole
+        let mut updated = chain.clone();
+        update(&mut updated)?;
+        *chain = updated;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::state_backend::tests::check_backend;
+
+    #[test]
+    fn memory_backend() {
+        check_backend(&MemoryBackend::new());
+    }
+}
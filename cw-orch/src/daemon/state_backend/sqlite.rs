@@ -0,0 +1,169 @@
+use std::{
+    path::Path,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use rusqlite::{params, Connection, TransactionBehavior};
+use serde_json::{Map, Value};
+
+use super::{ChainScope, StateBackend};
+use crate::daemon::error::DaemonError;
+
+const INSERT_ENTRY: &str = "INSERT INTO state (chain_name, chain_id, namespace, contract_id, value) VALUES (?1, ?2, ?3, ?4, ?5)
+    ON CONFLICT (chain_name, chain_id, namespace, contract_id) DO UPDATE SET value = excluded.value";
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS state (
+    chain_name TEXT NOT NULL,
+    chain_id TEXT NOT NULL,
+    namespace TEXT NOT NULL,
+    contract_id TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (chain_name, chain_id, namespace, contract_id)
+)";
+
+/// Stores the state in an SQLite database.
+/// Entries are written one by one, so several processes can share the database.
+#[derive(Debug)]
+pub struct SqliteBackend {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteBackend {
+    /// Open the database at `path`, it is created if missing.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DaemonError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Use a database that only lives in memory
+    pub fn in_memory() -> Result<Self, DaemonError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, DaemonError> {
+        connection.execute_batch(CREATE_TABLE)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> MutexGuard<Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl StateBackend for SqliteBackend {
+    fn init(&self, _scope: &ChainScope, _deployment_id: &str) -> Result<(), DaemonError> {
+        // namespaces don't need to exist before entries are added
+        Ok(())
+    }
+
+    fn get(&self, scope: &ChainScope, namespace: &str) -> Result<Map<String, Value>, DaemonError> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(
+            "SELECT contract_id, value FROM state WHERE chain_name = ?1 AND chain_id = ?2 AND namespace = ?3",
+        )?;
+        let rows = statement.query_map(
+            params![scope.chain_name, scope.chain_id, namespace],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )?;
+
+        let mut entries = Map::new();
+        for row in rows {
+            let (contract_id, value) = row?;
+            entries.insert(contract_id, serde_json::from_str(&value)?);
+        }
+        Ok(entries)
+    }
+
+    fn set(
+        &self,
+        scope: &ChainScope,
+        namespace: &str,
+        contract_id: &str,
+        value: Value,
+    ) -> Result<(), DaemonError> {
+        self.connection().execute(
+            INSERT_ENTRY,
+            params![
+                scope.chain_name,
+                scope.chain_id,
+                namespace,
+                contract_id,
+                value.to_string()
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn update_chain(
+        &self,
+        scope: &ChainScope,
+        update: &mut dyn FnMut(&mut Map<String, Value>) -> Result<(), DaemonError>,
+    ) -> Result<(), DaemonError> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let mut chain = Map::new();
+        {
+            let mut statement = transaction.prepare(
+                "SELECT namespace, contract_id, value FROM state WHERE chain_name = ?1 AND chain_id = ?2",
+            )?;
+            let rows = statement.query_map(params![scope.chain_name, scope.chain_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?;
+            for row in rows {
+                let (namespace, contract_id, value) = row?;
+                chain
+                    .entry(namespace)
+                    .or_insert_with(|| Value::Object(Map::new()))[contract_id.as_str()] =
+                    serde_json::from_str(&value)?;
+            }
+        }
+
+        update(&mut chain)?;
+
+        transaction.execute(
+            "DELETE FROM state WHERE chain_name = ?1 AND chain_id = ?2",
+            params![scope.chain_name, scope.chain_id],
+        )?;
+        for (namespace, entries) in &chain {
+            let Value::Object(entries) = entries else {
+                return Err(DaemonError::InvalidStateEntry {
+                    key: format!("{}/{}/{namespace}", scope.chain_name, scope.chain_id),
+                    value: entries.to_string(),
+                });
+            };
+            for (contract_id, value) in entries {
+                transaction.execute(
+                    INSERT_ENTRY,
+                    params![
+                        scope.chain_name,
+                        scope.chain_id,
+                        namespace,
+                        contract_id,
+                        value.to_string()
+                    ],
+                )?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::state_backend::tests::check_backend;
+
+    #[test]
+    fn sqlite_backend() {
+        check_backend(&SqliteBackend::in_memory().unwrap());
+    }
+}
@@ -0,0 +1,600 @@
+//! Export, import, merge and diff deployments of the daemon state.
+//!
+//! A [`DeploymentExport`] holds the code ids, addresses and history of one deployment id on one chain.
+//! It can be saved to a standalone file and imported in another state, possibly under another chain or deployment id,
+//! which allows promoting a release from one state file to another in a controlled way.
+
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::{
+    error::DaemonError,
+    state_backend::{
+        check_deployment_id, ChainScope, StateBackend, CODE_IDS_NAMESPACE, HISTORY_NAMESPACE,
+    },
+};
+use crate::state::DeploymentRecord;
+
+/// Code ids, addresses and history of a deployment, detached from any state storage
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct DeploymentExport {
+    /// E.g. juno
+    pub chain_name: String,
+    /// E.g. juno-1
+    pub chain_id: String,
+    /// E.g. default
+    pub deployment_id: String,
+    /// Code ids of the chain, by contract id
+    pub code_ids: BTreeMap<String, u64>,
+    /// Addresses of the deployment, by contract id
+    pub addresses: BTreeMap<String, String>,
+    /// Deployment history of the contracts
+    #[serde(default)]
+    pub history: BTreeMap<String, Vec<DeploymentRecord>>,
+}
+
+/// How entries that differ between the imported deployment and the state are resolved
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Fail without changing the state if any entry conflicts
+    #[default]
+    Strict,
+    /// Replace the conflicting entries of the state with the imported ones
+    Overwrite,
+    /// Keep the conflicting entries of the state, only add the missing ones
+    KeepExisting,
+}
+
+/// Kind of state entry
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EntryKind {
+    /// Code id of a contract
+    CodeId,
+    /// Address of a contract
+    Address,
+}
+
+impl fmt::Display for EntryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryKind::CodeId => write!(f, "code id"),
+            EntryKind::Address => write!(f, "address"),
+        }
+    }
+}
+
+/// An entry that has a different value in the state and in the imported deployment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateConflict {
+    /// Kind of the entry
+    pub kind: EntryKind,
+    /// Contract the entry belongs to
+    pub contract_id: String,
+    /// Value in the state
+    pub existing: String,
+    /// Value in the imported deployment
+    pub incoming: String,
+}
+
+impl fmt::Display for StateConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {}: {} in state, {} imported",
+            self.kind, self.contract_id, self.existing, self.incoming
+        )
+    }
+}
+
+/// Outcome of an import
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Entries that were missing in the state
+    pub added: usize,
+    /// Conflicting entries that were overwritten
+    pub updated: usize,
+    /// Entries that already had the imported value
+    pub unchanged: usize,
+    /// Conflicts found, resolved with the merge strategy
+    pub conflicts: Vec<StateConflict>,
+}
+
+/// Change of an entry between two deployments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryChange<T> {
+    /// Contract the entry belongs to
+    pub contract_id: String,
+    /// Value in the first deployment, `None` if added
+    pub before: Option<T>,
+    /// Value in the second deployment, `None` if removed
+    pub after: Option<T>,
+}
+
+/// Differences between two deployments
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeploymentDiff {
+    /// Changed code ids
+    pub code_ids: Vec<EntryChange<u64>>,
+    /// Changed addresses
+    pub addresses: Vec<EntryChange<String>>,
+}
+
+impl DeploymentDiff {
+    /// Whether the deployments have the same code ids and addresses
+    pub fn is_empty(&self) -> bool {
+        self.code_ids.is_empty() && self.addresses.is_empty()
+    }
+}
+
+impl DeploymentExport {
+    /// Read the deployment from the state
+    pub fn export(
+        backend: &dyn StateBackend,
+        scope: &ChainScope,
+        deployment_id: &str,
+    ) -> Result<Self, DaemonError> {
+        Self::from_namespaces(
+            scope,
+            deployment_id,
+            backend.get(scope, CODE_IDS_NAMESPACE)?,
+            backend.get(scope, deployment_id)?,
+            backend.get(scope, HISTORY_NAMESPACE)?,
+        )
+    }
+
+    fn from_namespaces(
+        scope: &ChainScope,
+        deployment_id: &str,
+        code_ids: Map<String, Value>,
+        addresses: Map<String, Value>,
+        history: Map<String, Value>,
+    ) -> Result<Self, DaemonError> {
+        let mut export = DeploymentExport {
+            chain_name: scope.chain_name.clone(),
+            chain_id: scope.chain_id.clone(),
+            deployment_id: deployment_id.to_string(),
+            ..Default::default()
+        };
+
+        for (contract_id, code_id) in code_ids {
+            let code_id = code_id
+                .as_u64()
+                .ok_or_else(|| invalid_entry(CODE_IDS_NAMESPACE, &contract_id, &code_id))?;
+            export.code_ids.insert(contract_id, code_id);
+        }
+        for (contract_id, address) in addresses {
+            let address = address
+                .as_str()
+                .ok_or_else(|| invalid_entry(deployment_id, &contract_id, &address))?;
+            export.addresses.insert(contract_id, address.to_string());
+        }
+        for (contract_id, history) in history {
+            let Some(records) = history.get(deployment_id) else {
+                continue;
+            };
+            let records = serde_json::from_value(records.clone())
+                .map_err(|_| invalid_entry(HISTORY_NAMESPACE, &contract_id, records))?;
+            export.history.insert(contract_id, records);
+        }
+
+        Ok(export)
+    }
+
+    /// Chain the deployment belongs to
+    pub fn scope(&self) -> ChainScope {
+        ChainScope {
+            chain_name: self.chain_name.clone(),
+            chain_id: self.chain_id.clone(),
+        }
+    }
+
+    /// Save the deployment to a standalone JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DaemonError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Load a deployment saved with [`DeploymentExport::save`]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DaemonError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Conflicts between this deployment and the one in the state
+    pub fn conflicts(
+        &self,
+        backend: &dyn StateBackend,
+        scope: &ChainScope,
+        deployment_id: &str,
+    ) -> Result<Vec<StateConflict>, DaemonError> {
+        let existing = Self::export(backend, scope, deployment_id)?;
+        Ok(existing.diff(self).into_conflicts())
+    }
+
+    /// Import the deployment in the state under the given chain and deployment id.
+    /// Entries that differ are resolved with `strategy`, history records are appended if missing.
+    /// The conflicts are checked and the entries written in a single update of the backend.
+    ///
+    /// Code ids only exist on the chain they were uploaded to,
+    /// so a deployment with code ids can't be imported on another chain id.
+    /// Use [`DeploymentExport::without_code_ids`] to only import the addresses and history,
+    /// or set the `chain_id` of the export if the chain kept its state under a new chain id (e.g. after a hard fork).
+    pub fn import(
+        &self,
+        backend: &dyn StateBackend,
+        scope: &ChainScope,
+        deployment_id: &str,
+        strategy: MergeStrategy,
+    ) -> Result<ImportSummary, DaemonError> {
+        check_deployment_id(deployment_id)?;
+        if self.chain_id != scope.chain_id && !self.code_ids.is_empty() {
+            return Err(DaemonError::CrossChainImport {
+                from: self.chain_id.clone(),
+                to: scope.chain_id.clone(),
+            });
+        }
+
+        let mut summary = ImportSummary::default();
+        backend.update_chain(scope, &mut |chain| {
+            summary = self.merge_into(chain, scope, deployment_id, strategy)?;
+            Ok(())
+        })?;
+        Ok(summary)
+    }
+
+    /// The deployment without its code ids, to import it on another chain
+    pub fn without_code_ids(&self) -> Self {
+        DeploymentExport {
+            code_ids: BTreeMap::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Merge the deployment into the namespaces of a chain
+    fn merge_into(
+        &self,
+        chain: &mut Map<String, Value>,
+        scope: &ChainScope,
+        deployment_id: &str,
+        strategy: MergeStrategy,
+    ) -> Result<ImportSummary, DaemonError> {
+        let existing = Self::from_namespaces(
+            scope,
+            deployment_id,
+            namespace(chain, CODE_IDS_NAMESPACE)?,
+            namespace(chain, deployment_id)?,
+            namespace(chain, HISTORY_NAMESPACE)?,
+        )?;
+        let conflicts = existing.diff(self).into_conflicts();
+        if strategy == MergeStrategy::Strict && !conflicts.is_empty() {
+            return Err(DaemonError::StateConflict(conflicts));
+        }
+
+        let mut summary = ImportSummary {
+            conflicts,
+            ..Default::default()
+        };
+        let entries = self
+            .code_ids
+            .iter()
+            .map(|(id, code_id)| {
+                (
+                    CODE_IDS_NAMESPACE,
+                    id,
+                    json!(code_id),
+                    existing.code_ids.get(id).map(|c| json!(c)),
+                )
+            })
+            .chain(self.addresses.iter().map(|(id, address)| {
+                (
+                    deployment_id,
+                    id,
+                    json!(address),
+                    existing.addresses.get(id).map(|a| json!(a)),
+                )
+            }));
+        for (namespace, contract_id, incoming, current) in entries {
+            match current {
+                None => summary.added += 1,
+                Some(current) if current == incoming => {
+                    summary.unchanged += 1;
+                    continue;
+                }
+                Some(_) if strategy == MergeStrategy::KeepExisting => continue,
+                Some(_) => summary.updated += 1,
+            }
+            namespace_mut(chain, namespace)?.insert(contract_id.clone(), incoming);
+        }
+
+        for (contract_id, records) in &self.history {
+            let known = existing
+                .history
+                .get(contract_id)
+                .cloned()
+                .unwrap_or_default();
+            let missing: Vec<_> = records.iter().filter(|r| !known.contains(r)).collect();
+            if missing.is_empty() {
+                continue;
+            }
+            let history = namespace_mut(chain, HISTORY_NAMESPACE)?
+                .entry(contract_id)
+                .or_insert(Value::Null);
+            if history.is_null() {
+                *history = json!({});
+            }
+            if !history.is_object() {
+                return Err(invalid_entry(HISTORY_NAMESPACE, contract_id, history));
+            }
+            let records = &mut history[deployment_id];
+            if records.is_null() {
+                *records = json!([]);
+            }
+            match records {
+                Value::Array(records) => records.extend(missing.iter().map(|record| json!(record))),
+                other => return Err(invalid_entry(HISTORY_NAMESPACE, contract_id, other)),
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Changes from this deployment to `other`
+    pub fn diff(&self, other: &DeploymentExport) -> DeploymentDiff {
+        DeploymentDiff {
+            code_ids: diff_entries(&self.code_ids, &other.code_ids),
+            addresses: diff_entries(&self.addresses, &other.addresses),
+        }
+    }
+}
+
+impl DeploymentDiff {
+    /// Changed entries that are present in both deployments, considering `self` as the existing state
+    fn into_conflicts(self) -> Vec<StateConflict> {
+        let code_ids = self.code_ids.into_iter().filter_map(|change| {
+            Some(StateConflict {
+                kind: EntryKind::CodeId,
+                existing: change.before?.to_string(),
+                incoming: change.after?.to_string(),
+                contract_id: change.contract_id,
+            })
+        });
+        let addresses = self.addresses.into_iter().filter_map(|change| {
+            Some(StateConflict {
+                kind: EntryKind::Address,
+                existing: change.before?,
+                incoming: change.after?,
+                contract_id: change.contract_id,
+            })
+        });
+        code_ids.chain(addresses).collect()
+    }
+}
+
+fn diff_entries<T: Clone + PartialEq>(
+    before: &BTreeMap<String, T>,
+    after: &BTreeMap<String, T>,
+) -> Vec<EntryChange<T>> {
+    let mut ids: Vec<_> = before.keys().chain(after.keys()).collect();
+    ids.sort();
+    ids.dedup();
+
+    ids.into_iter()
+        .filter_map(|id| {
+            let (before, after) = (before.get(id), after.get(id));
+            (before != after).then(|| EntryChange {
+                contract_id: id.clone(),
+                before: before.cloned(),
+                after: after.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Entries of the namespace of a chain
+fn namespace(
+    chain: &Map<String, Value>,
+    namespace: &str,
+) -> Result<Map<String, Value>, DaemonError> {
+    match chain.get(namespace) {
+        None | Some(Value::Null) => Ok(Map::new()),
+        Some(Value::Object(entries)) => Ok(entries.clone()),
+        Some(other) => Err(DaemonError::InvalidStateEntry {
+            key: namespace.to_string(),
+            value: other.to_string(),
+        }),
+    }
+}
+
+/// Entries of the namespace of a chain, inserted if missing
+fn namespace_mut<'a>(
+    chain: &'a mut Map<String, Value>,
+    namespace: &str,
+) -> Result<&'a mut Map<String, Value>, DaemonError> {
+    let entries = chain
+        .entry(namespace)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if entries.is_null() {
+        *entries = Value::Object(Map::new());
+    }
+    match entries {
+        Value::Object(entries) => Ok(entries),
+        other => Err(DaemonError::InvalidStateEntry {
+            key: namespace.to_string(),
+            value: other.to_string(),
+        }),
+    }
+}
+
+fn invalid_entry(namespace: &str, contract_id: &str, value: &Value) -> DaemonError {
+    DaemonError::InvalidStateEntry {
+        key: format!("{namespace}/{contract_id}"),
+        value: value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{daemon::state_backend::MemoryBackend, state::DeploymentAction};
+
+    fn scope(chain_id: &str) -> ChainScope {
+        ChainScope {
+            chain_name: "juno".to_string(),
+            chain_id: chain_id.to_string(),
+        }
+    }
+
+    fn testnet_backend() -> MemoryBackend {
+        let backend = MemoryBackend::new();
+        let testnet = scope("uni-6");
+        backend.init(&testnet, "v1").unwrap();
+        backend
+            .set(&testnet, CODE_IDS_NAMESPACE, "counter", json!(1))
+            .unwrap();
+        backend
+            .set(&testnet, "v1", "counter", json!("juno1counter"))
+            .unwrap();
+        backend
+            .update(&testnet, HISTORY_NAMESPACE, "counter", &mut |history| {
+                *history = json!({ "v1": [DeploymentRecord::new(DeploymentAction::Upload)] });
+                Ok(())
+            })
+            .unwrap();
+        backend
+    }
+
+    #[test]
+    fn export_import_roundtrip() {
+        let backend = testnet_backend();
+        let export = DeploymentExport::export(&backend, &scope("uni-6"), "v1").unwrap();
+        assert_eq!(export.code_ids.get("counter"), Some(&1));
+        assert_eq!(export.history.get("counter").unwrap().len(), 1);
+
+        let path = std::env::temp_dir().join("cw-orch-deployment-export.json");
+        export.save(&path).unwrap();
+        let loaded = DeploymentExport::load(&path).unwrap();
+        assert_eq!(loaded, export);
+
+        // import under another deployment id
+        let summary = loaded
+            .import(&backend, &scope("uni-6"), "v2", MergeStrategy::Strict)
+            .unwrap();
+        assert_eq!(summary.added, 1);
+        assert_eq!(summary.unchanged, 1);
+
+        let imported = DeploymentExport::export(&backend, &scope("uni-6"), "v2").unwrap();
+        assert!(export.diff(&imported).is_empty());
+        assert_eq!(imported.history, export.history);
+
+        // importing again changes nothing, history is not duplicated
+        let summary = loaded
+            .import(&backend, &scope("uni-6"), "v2", MergeStrategy::Strict)
+            .unwrap();
+        assert_eq!(summary.unchanged, 2);
+        let imported = DeploymentExport::export(&backend, &scope("uni-6"), "v2").unwrap();
+        assert_eq!(imported.history.get("counter").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn code_ids_are_not_imported_on_another_chain() {
+        let backend = testnet_backend();
+        let export = DeploymentExport::export(&backend, &scope("uni-6"), "v1").unwrap();
+
+        let err = export
+            .import(&backend, &scope("juno-1"), "v1", MergeStrategy::Strict)
+            .unwrap_err();
+        assert!(matches!(err, DaemonError::CrossChainImport { .. }));
+        let mainnet = DeploymentExport::export(&backend, &scope("juno-1"), "v1").unwrap();
+        assert!(mainnet.code_ids.is_empty());
+
+        let summary = export
+            .without_code_ids()
+            .import(&backend, &scope("juno-1"), "v1", MergeStrategy::Strict)
+            .unwrap();
+        assert_eq!(summary.added, 1);
+        let mainnet = DeploymentExport::export(&backend, &scope("juno-1"), "v1").unwrap();
+        assert!(mainnet.code_ids.is_empty());
+        assert_eq!(mainnet.addresses, export.addresses);
+    }
+
+    #[test]
+    fn reserved_deployment_ids_are_rejected() {
+        let backend = testnet_backend();
+        let export = DeploymentExport::export(&backend, &scope("uni-6"), "v1").unwrap();
+
+        let err = export
+            .import(
+                &backend,
+                &scope("uni-6"),
+                HISTORY_NAMESPACE,
+                MergeStrategy::Overwrite,
+            )
+            .unwrap_err();
+        assert!(matches!(err, DaemonError::ReservedDeploymentId(id) if id == HISTORY_NAMESPACE));
+        // the history is untouched
+        let history = backend.get(&scope("uni-6"), HISTORY_NAMESPACE).unwrap();
+        assert!(history["counter"].get("v1").is_some());
+    }
+
+    #[test]
+    fn conflicts_follow_strategy() {
+        let backend = testnet_backend();
+        let mut incoming = DeploymentExport::export(&backend, &scope("uni-6"), "v1").unwrap();
+        incoming.code_ids.insert("counter".to_string(), 2);
+        incoming
+            .addresses
+            .insert("token".to_string(), "juno1token".to_string());
+
+        let err = incoming
+            .import(&backend, &scope("uni-6"), "v1", MergeStrategy::Strict)
+            .unwrap_err();
+        assert!(matches!(err, DaemonError::StateConflict(ref conflicts) if conflicts.len() == 1));
+        // nothing was written
+        let state = DeploymentExport::export(&backend, &scope("uni-6"), "v1").unwrap();
+        assert!(!state.addresses.contains_key("token"));
+
+        let summary = incoming
+            .import(&backend, &scope("uni-6"), "v1", MergeStrategy::KeepExisting)
+            .unwrap();
+        assert_eq!(summary.added, 1);
+        assert_eq!(summary.conflicts.len(), 1);
+        let state = DeploymentExport::export(&backend, &scope("uni-6"), "v1").unwrap();
+        assert_eq!(state.code_ids.get("counter"), Some(&1));
+
+        let summary = incoming
+            .import(&backend, &scope("uni-6"), "v1", MergeStrategy::Overwrite)
+            .unwrap();
+        assert_eq!(summary.updated, 1);
+        let state = DeploymentExport::export(&backend, &scope("uni-6"), "v1").unwrap();
+        assert_eq!(state.code_ids.get("counter"), Some(&2));
+    }
+
+    #[test]
+    fn diff_deployments() {
+        let before = DeploymentExport {
+            code_ids: BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)]),
+            addresses: BTreeMap::from([("a".to_string(), "addr_a".to_string())]),
+            ..Default::default()
+        };
+        let after = DeploymentExport {
+            code_ids: BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 3)]),
+            addresses: BTreeMap::from([("c".to_string(), "addr_c".to_string())]),
+            ..Default::default()
+        };
+
+        let diff = before.diff(&after);
+
+        assert_eq!(
+            diff.code_ids,
+            vec![EntryChange {
+                contract_id: "b".to_string(),
+                before: Some(2),
+                after: Some(3)
+            }]
+        );
+        assert_eq!(diff.addresses.len(), 2);
+        assert_eq!(diff.addresses[0].after, None);
+        assert_eq!(diff.addresses[1].before, None);
+    }
+}
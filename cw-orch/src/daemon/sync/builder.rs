@@ -1,8 +1,20 @@
+use std::sync::Arc;
+
 use ibc_chain_registry::chain::ChainData;
 
-use crate::prelude::DaemonAsyncBuilder;
+use crate::prelude::{DaemonAsyncBuilder, WasmLimits};
 
-use super::{super::error::DaemonError, core::Daemon};
+use super::{
+    super::{
+        channel::{ChannelOptions, GrpcEndpointConfig, GrpcPolicy, Transport},
+        error::DaemonError,
+        gas_price::GasPriceSource,
+        pending_tx::ConfirmationPolicy,
+        sender::SenderOptions,
+        state_backend::StateBackend,
+    },
+    core::Daemon,
+};
 
 #[derive(Clone, Default)]
 /// Create [`Daemon`] through [`DaemonBuilder`]
@@ -24,6 +36,14 @@ pub struct DaemonBuilder {
     pub(crate) deployment_id: Option<String>,
     /// Wallet mnemonic
     pub(crate) mnemonic: Option<String>,
+    /// Fee payment options of the sender
+    pub(crate) sender_options: SenderOptions,
+    /// Transport and endpoint policy used to connect to the chain
+    pub(crate) channel_options: ChannelOptions,
+    /// Storage of the deployment state
+    pub(crate) state_backend: Option<Arc<dyn StateBackend>>,
+    /// Limits wasm files are validated against before upload
+    pub(crate) wasm_limits: WasmLimits,
 }
 
 impl DaemonBuilder {
@@ -64,6 +84,167 @@ impl DaemonBuilder {
         self
     }
 
+    /// Pay the tx fees with a fee allowance granted to the sender by `granter`.
+    /// Allows running scripts from unfunded wallets.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use cw_orch::prelude::{Daemon, networks};
+    /// use tokio::runtime::Runtime;
+    /// let rt = Runtime::new().unwrap();
+    /// let daemon = Daemon::builder()
+    ///     .chain(networks::UNI_6)
+    ///     .handle(rt.handle())
+    ///     .fee_granter("juno1...")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn fee_granter(&mut self, granter: impl ToString) -> &mut Self {
+        self.sender_options.fee_granter = Some(granter.to_string());
+        self
+    }
+
+    /// Let the account of this mnemonic pay the tx fees.
+    /// The payer signs every tx next to the sender.
+    pub fn fee_payer_mnemonic(&mut self, mnemonic: impl ToString) -> &mut Self {
+        self.sender_options.fee_payer = Some(mnemonic.to_string());
+        self
+    }
+
+    /// Set the denom used to pay tx fees.
+    /// Defaults to the chain's main fee token. Can be any denom accepted by the chain, like an IBC denom.
+    pub fn fee_denom(&mut self, denom: impl ToString) -> &mut Self {
+        self.sender_options.fee_denom = Some(denom.to_string());
+        self
+    }
+
+    /// Set the gas price for the fee denom.
+    /// Defaults to the gas price configured in the chain info for that denom.
+    pub fn gas_price(&mut self, gas_price: f64) -> &mut Self {
+        self.sender_options.gas_price = Some(gas_price);
+        self
+    }
+
+    /// Set where the gas price of txs is queried from.
+    /// Defaults to [`GasPriceSource::Static`], which uses the gas price of the chain info or [`Self::gas_price`].
+    /// That static gas price is also used when the source has no price for the fee denom or can't be queried.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use cw_orch::prelude::{Daemon, networks};
+    /// use cw_orch::daemon::GasPriceSource;
+    /// use tokio::runtime::Runtime;
+    /// let rt = Runtime::new().unwrap();
+    /// // use the EIP-1559 base fee of osmosis, falling back to 0.025uosmo
+    /// let daemon = Daemon::builder()
+    ///     .chain(networks::OSMO_5)
+    ///     .handle(rt.handle())
+    ///     .gas_price_source(GasPriceSource::OsmosisTxFees)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn gas_price_source(&mut self, source: GasPriceSource) -> &mut Self {
+        self.sender_options.gas_price_source = source;
+        self
+    }
+
+    /// Set the multiplier applied to the simulated gas of a tx.
+    /// Defaults to 1.3
+    pub fn gas_adjustment(&mut self, gas_adjustment: f64) -> &mut Self {
+        self.sender_options.gas_adjustment = Some(gas_adjustment);
+        self
+    }
+
+    /// Set the maximum fee amount a single tx is allowed to pay.
+    /// Txs that would pay more are not broadcasted.
+    pub fn max_fee(&mut self, max_fee: u128) -> &mut Self {
+        self.sender_options.max_fee = Some(max_fee);
+        self
+    }
+
+    /// Set when a broadcasted tx is considered confirmed.
+    /// Defaults to inclusion in a block without timeout.
+    pub fn confirmation_policy(&mut self, policy: ConfirmationPolicy) -> &mut Self {
+        self.sender_options.confirmation_policy = policy;
+        self
+    }
+
+    /// Set how the gRPC endpoints of the chain are health-checked and how idempotent queries are retried.
+    /// The Daemon fails over to the next fastest endpoint when a query fails because of the connection.
+    pub fn grpc_policy(&mut self, policy: GrpcPolicy) -> &mut Self {
+        self.channel_options.policy = policy;
+        self
+    }
+
+    /// Set the protocol used to talk to the nodes of the chain.
+    /// Defaults to [`Transport::Grpc`], use [`Transport::Rest`] for providers that only expose the REST (LCD) endpoint.
+    pub fn transport(&mut self, transport: Transport) -> &mut Self {
+        self.channel_options.transport = transport;
+        self
+    }
+
+    /// Set the connection settings of every gRPC endpoint, like the API key headers, CA certificate, timeouts and concurrency limit.
+    /// Applied to the tx service and to all the queriers.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use cw_orch::prelude::{Daemon, networks};
+    /// use cw_orch::daemon::GrpcEndpointConfig;
+    /// use tokio::runtime::Runtime;
+    /// let rt = Runtime::new().unwrap();
+    /// let daemon = Daemon::builder()
+    ///     .chain(networks::UNI_6)
+    ///     .handle(rt.handle())
+    ///     .grpc_config(GrpcEndpointConfig::default().header("x-api-key", "my-secret-key"))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn grpc_config(&mut self, config: GrpcEndpointConfig) -> &mut Self {
+        self.channel_options.grpc_config = config;
+        self
+    }
+
+    /// Set the connection settings of the gRPC endpoint at `address`, replacing the ones set with [`Self::grpc_config`] for that endpoint.
+    pub fn grpc_endpoint_config(
+        &mut self,
+        address: impl Into<String>,
+        config: GrpcEndpointConfig,
+    ) -> &mut Self {
+        self.channel_options
+            .grpc_endpoint_configs
+            .insert(address.into(), config);
+        self
+    }
+
+    /// Set where the deployment state (code ids and addresses) is stored.
+    /// Defaults to the JSON file at the `STATE_FILE` env variable.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use cw_orch::prelude::{Daemon, networks};
+    /// use cw_orch::daemon::MemoryBackend;
+    /// use tokio::runtime::Runtime;
+    /// let rt = Runtime::new().unwrap();
+    /// // don't touch the state file in CI
+    /// let daemon = Daemon::builder()
+    ///     .chain(networks::LOCAL_JUNO)
+    ///     .handle(rt.handle())
+    ///     .state_backend(MemoryBackend::new())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn state_backend(&mut self, backend: impl StateBackend + 'static) -> &mut Self {
+        self.state_backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Set the size limit and supported capabilities of the chain, which wasm files are validated against before upload.
+    /// Defaults to the wasmd size limit, without capability check.
+    pub fn wasm_limits(&mut self, limits: WasmLimits) -> &mut Self {
+        self.wasm_limits = limits;
+        self
+    }
+
     /// Build a Daemon
     pub fn build(&self) -> Result<Daemon, DaemonError> {
         let rt_handle = self
@@ -1,22 +1,31 @@
-use super::super::{queriers::Node, sender::Wallet, tx_resp::CosmTxResponse, DaemonAsync};
+use super::{
+    super::{
+        core::store_code_msg,
+        proposal::{ProposalContent, VoteOption},
+        queriers::{GovProposalStatus, Node},
+        sender::Wallet,
+        tx_resp::CosmTxResponse,
+        DaemonAsync, DaemonChannel,
+    },
+    BlockingPendingTx,
+};
 use crate::{
     daemon::{error::DaemonError, state::DaemonState},
-    environment::TxHandler,
+    environment::{ContractInfo, TxHandler, WasmQuerier},
+    error::CwOrchError,
     prelude::{
         queriers::{CosmWasm, DaemonQuerier},
-        CallAs, ContractInstance, CwOrchExecute, DaemonBuilder, IndexResponse, Uploadable,
-        WasmPath,
+        AccessConfig, CallAs, ContractInstance, CwOrchExecute, DaemonBuilder, IndexResponse,
+        Uploadable, WasmPath,
     },
     state::ChainState,
 };
-use cosmrs::tendermint::Time;
+use cosmrs::{tendermint::Time, tx::Msg, Any};
 use cosmwasm_std::{Addr, Coin};
-use flate2::{write, Compression};
 use serde::{de::DeserializeOwned, Serialize};
 
-use std::{fmt::Debug, io::Write, rc::Rc, time::Duration};
+use std::{fmt::Debug, rc::Rc, time::Duration};
 use tokio::runtime::Handle;
-use tonic::transport::Channel;
 
 #[derive(Clone)]
 /**
@@ -65,7 +74,7 @@ impl Daemon {
     }
 
     /// Get the channel configured for this Daemon
-    pub fn channel(&self) -> Channel {
+    pub fn channel(&self) -> DaemonChannel {
         self.state().grpc_channel.clone()
     }
 
@@ -73,6 +82,219 @@ impl Daemon {
     pub fn wallet(&self) -> Wallet {
         self.daemon.sender.clone()
     }
+
+    /// Upload a contract to the chain, only instantiable as allowed by `permission`.
+    /// `None` uses the default permission of the chain.
+    pub fn upload_with_permission(
+        &self,
+        uploadable: &impl Uploadable,
+        permission: Option<&AccessConfig>,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        self.rt_handle
+            .block_on(self.daemon.upload_with_permission(uploadable, permission))
+    }
+
+    /// Change who can instantiate an uploaded code. Only the uploader of the code can do this.
+    pub fn update_instantiate_config(
+        &self,
+        code_id: u64,
+        permission: &AccessConfig,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        self.rt_handle
+            .block_on(self.daemon.update_instantiate_config(code_id, permission))
+    }
+
+    /// Who can instantiate the code stored under the code id.
+    pub fn instantiate_permission(&self, code_id: u64) -> Result<AccessConfig, DaemonError> {
+        self.rt_handle
+            .block_on(self.daemon.instantiate_permission(code_id))
+    }
+
+    /// Broadcast a tx without waiting for it to be included in a block.
+    /// The returned handle blocks on [`BlockingPendingTx::wait`].
+    pub fn submit_tx<T: Msg>(
+        &self,
+        msgs: Vec<T>,
+        memo: Option<&str>,
+    ) -> Result<BlockingPendingTx, DaemonError> {
+        let pending = self.rt_handle.block_on(self.daemon.submit_tx(msgs, memo))?;
+        Ok(BlockingPendingTx::new(pending, self.rt_handle.clone()))
+    }
+
+    /// Same as [`Daemon::submit_tx`] for messages that are already encoded as [`Any`].
+    pub fn submit_tx_any(
+        &self,
+        msgs: Vec<Any>,
+        memo: Option<&str>,
+    ) -> Result<BlockingPendingTx, DaemonError> {
+        let pending = self
+            .rt_handle
+            .block_on(self.daemon.submit_tx_any(msgs, memo))?;
+        Ok(BlockingPendingTx::new(pending, self.rt_handle.clone()))
+    }
+
+    /// Grant a basic fee allowance from the sender to the grantee.
+    pub fn grant_basic_allowance(
+        &self,
+        grantee: &Addr,
+        spend_limit: Option<Vec<Coin>>,
+        expiration: Option<Time>,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        self.rt_handle.block_on(
+            self.daemon
+                .grant_basic_allowance(grantee, spend_limit, expiration),
+        )
+    }
+
+    /// Grant a periodic fee allowance from the sender to the grantee.
+    pub fn grant_periodic_allowance(
+        &self,
+        grantee: &Addr,
+        spend_limit: Option<Vec<Coin>>,
+        expiration: Option<Time>,
+        period: Duration,
+        period_spend_limit: Vec<Coin>,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        self.rt_handle
+            .block_on(self.daemon.grant_periodic_allowance(
+                grantee,
+                spend_limit,
+                expiration,
+                period,
+                period_spend_limit,
+            ))
+    }
+
+    /// Revoke the fee allowance granted by the sender to the grantee.
+    pub fn revoke_allowance(&self, grantee: &Addr) -> Result<CosmTxResponse, DaemonError> {
+        self.rt_handle
+            .block_on(self.daemon.revoke_allowance(grantee))
+    }
+
+    /// Address of the gov module, which signs the messages of passed proposals.
+    pub fn gov_authority(&self) -> Result<Addr, DaemonError> {
+        self.daemon.gov_authority()
+    }
+
+    /// Submit a gov v1 proposal executing `messages`. Returns the id of the proposal.
+    pub fn submit_proposal(
+        &self,
+        messages: Vec<Any>,
+        content: &ProposalContent,
+    ) -> Result<u64, DaemonError> {
+        self.rt_handle
+            .block_on(self.daemon.submit_proposal(messages, content))
+    }
+
+    /// Propose to store the code of the contract, only instantiable as allowed by `permission`.
+    pub fn propose_store_code(
+        &self,
+        uploadable: &impl Uploadable,
+        permission: Option<&AccessConfig>,
+        content: &ProposalContent,
+    ) -> Result<u64, DaemonError> {
+        self.rt_handle.block_on(
+            self.daemon
+                .propose_store_code(uploadable, permission, content),
+        )
+    }
+
+    /// Propose to instantiate a contract.
+    pub fn propose_instantiate<I: Serialize>(
+        &self,
+        code_id: u64,
+        init_msg: &I,
+        label: Option<&str>,
+        admin: Option<&Addr>,
+        coins: &[Coin],
+        content: &ProposalContent,
+    ) -> Result<u64, DaemonError> {
+        self.rt_handle.block_on(
+            self.daemon
+                .propose_instantiate(code_id, init_msg, label, admin, coins, content),
+        )
+    }
+
+    /// Propose to migrate a contract, which must have the gov module as admin.
+    pub fn propose_migrate<M: Serialize>(
+        &self,
+        migrate_msg: &M,
+        new_code_id: u64,
+        contract_address: &Addr,
+        content: &ProposalContent,
+    ) -> Result<u64, DaemonError> {
+        self.rt_handle.block_on(self.daemon.propose_migrate(
+            migrate_msg,
+            new_code_id,
+            contract_address,
+            content,
+        ))
+    }
+
+    /// Add a deposit to a proposal.
+    pub fn deposit(
+        &self,
+        proposal_id: u64,
+        amount: &[Coin],
+    ) -> Result<CosmTxResponse, DaemonError> {
+        self.rt_handle
+            .block_on(self.daemon.deposit(proposal_id, amount))
+    }
+
+    /// Vote on a proposal.
+    pub fn vote(
+        &self,
+        proposal_id: u64,
+        option: VoteOption,
+    ) -> Result<CosmTxResponse, DaemonError> {
+        self.rt_handle
+            .block_on(self.daemon.vote(proposal_id, option))
+    }
+
+    /// Current status of a gov v1 proposal.
+    pub fn proposal_status(&self, proposal_id: u64) -> Result<GovProposalStatus, DaemonError> {
+        self.rt_handle
+            .block_on(self.daemon.proposal_status(proposal_id))
+    }
+
+    /// Wait until the proposal passed.
+    /// Errors if the proposal is rejected or fails, or if it is still pending after `timeout`.
+    pub fn wait_for_proposal(
+        &self,
+        proposal_id: u64,
+        timeout: Duration,
+    ) -> Result<(), DaemonError> {
+        self.rt_handle
+            .block_on(self.daemon.wait_for_proposal(proposal_id, timeout))
+    }
+
+    /// Register the code stored by a passed store-code proposal for the contract id.
+    pub fn resolve_proposal_code_id(
+        &self,
+        contract_id: &str,
+        uploadable: &impl Uploadable,
+    ) -> Result<u64, CwOrchError> {
+        self.rt_handle.block_on(
+            self.daemon
+                .resolve_proposal_code_id(contract_id, uploadable),
+        )
+    }
+
+    /// Propose to store the code of the contract, wait for the proposal to pass and register the stored code id for the contract id.
+    pub fn store_code_via_proposal(
+        &self,
+        contract_id: &str,
+        uploadable: &impl Uploadable,
+        content: &ProposalContent,
+        timeout: Duration,
+    ) -> Result<u64, CwOrchError> {
+        self.rt_handle.block_on(self.daemon.store_code_via_proposal(
+            contract_id,
+            uploadable,
+            content,
+            timeout,
+        ))
+    }
 }
 
 impl ChainState for Daemon {
@@ -95,22 +317,15 @@ impl TxHandler for Daemon {
 
     fn upload(&self, uploadable: &impl Uploadable) -> Result<Self::Response, DaemonError> {
         let sender = &self.daemon.sender;
-        let wasm_path = uploadable.wasm();
-
-        log::debug!("Uploading file at {:?}", wasm_path);
-
-        let file_contents = std::fs::read(wasm_path.path())?;
-        let mut e = write::GzEncoder::new(Vec::new(), Compression::default());
-        e.write_all(&file_contents)?;
-        let wasm_byte_code = e.finish()?;
-        let store_msg = cosmrs::cosmwasm::MsgStoreCode {
-            sender: sender.pub_addr()?,
-            wasm_byte_code,
-            instantiate_permission: None,
-        };
+        let store_msg = store_code_msg(
+            sender.pub_addr_str()?,
+            &uploadable.wasm(),
+            uploadable.instantiate_permission().as_ref(),
+            &self.daemon.state.wasm_limits,
+        )?;
         let result = self
             .rt_handle
-            .block_on(sender.commit_tx(vec![store_msg], None))?;
+            .block_on(sender.commit_tx_any(vec![store_msg], None))?;
 
         log::info!("Uploaded: {:?}", result.txhash);
 
@@ -126,6 +341,10 @@ impl TxHandler for Daemon {
         Ok(result)
     }
 
+    fn upload_checksum(&self, uploadable: &impl Uploadable) -> Option<String> {
+        uploadable.wasm().checksum().ok()
+    }
+
     fn execute<E: Serialize>(
         &self,
         exec_msg: &E,
@@ -230,6 +449,30 @@ impl TxHandler for Daemon {
     }
 }
 
+impl WasmQuerier for Daemon {
+    fn code_checksum(&self, code_id: u64) -> Result<String, CwOrchError> {
+        Ok(self
+            .rt_handle
+            .block_on(self.daemon.code_checksum(code_id))?)
+    }
+
+    fn contract_info(&self, address: &Addr) -> Result<ContractInfo, CwOrchError> {
+        Ok(self
+            .rt_handle
+            .block_on(self.daemon.contract_info(address))?)
+    }
+
+    fn find_code(
+        &self,
+        checksum: &str,
+        uploader: Option<&Addr>,
+    ) -> Result<Option<u64>, CwOrchError> {
+        Ok(self
+            .rt_handle
+            .block_on(self.daemon.find_code(checksum, uploader))?)
+    }
+}
+
 impl<T: CwOrchExecute<Daemon> + ContractInstance<Daemon> + Clone> CallAs<Daemon> for T {
     type Sender = Wallet;
 
@@ -1,4 +1,5 @@
 mod builder;
 mod core;
+mod pending_tx;
 
-pub use self::{builder::*, core::*};
+pub use self::{builder::*, core::*, pending_tx::*};
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use tokio::runtime::Handle;
+
+use super::super::{
+    error::DaemonError,
+    pending_tx::{CancelHandle, ConfirmationPolicy, PendingTx},
+    tx_resp::CosmTxResponse,
+};
+
+/// A tx broadcasted by the [`Daemon`](super::Daemon) that might not be included in a block yet.
+/// Blocking counterpart of [`PendingTx`].
+#[derive(Clone, Debug)]
+pub struct BlockingPendingTx {
+    pending: PendingTx,
+    rt_handle: Handle,
+}
+
+impl BlockingPendingTx {
+    pub(crate) fn new(pending: PendingTx, rt_handle: Handle) -> Self {
+        Self { pending, rt_handle }
+    }
+
+    /// Hash of the tx
+    pub fn hash(&self) -> &str {
+        self.pending.hash()
+    }
+
+    /// Policy used to decide when the tx is confirmed
+    pub fn policy(&self) -> &ConfirmationPolicy {
+        self.pending.policy()
+    }
+
+    /// Replace the confirmation policy of this tx
+    pub fn with_policy(mut self, policy: ConfirmationPolicy) -> Self {
+        self.pending = self.pending.with_policy(policy);
+        self
+    }
+
+    /// Wait at most `timeout` for the confirmation
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.pending = self.pending.with_timeout(timeout);
+        self
+    }
+
+    /// Require `confirmations` blocks on top of the block including the tx
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.pending = self.pending.with_confirmations(confirmations);
+        self
+    }
+
+    /// Handle to cancel waiting for this tx, e.g. from another thread
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.pending.cancel_handle()
+    }
+
+    /// Block until the tx is confirmed according to the confirmation policy.
+    /// Returns an error if the tx failed, the timeout is reached or the wait is cancelled.
+    pub fn wait(&self) -> Result<CosmTxResponse, DaemonError> {
+        self.rt_handle.block_on(self.pending.wait())
+    }
+}
@@ -1,15 +1,15 @@
-use cosmrs::tx::{ModeInfo, SignMode};
+use std::str::FromStr;
+
 use cosmrs::{
-    proto::cosmos::auth::v1beta1::BaseAccount,
-    tendermint::chain::Id,
-    tx::{self, Body, Fee, Msg, Raw, SequenceNumber, SignDoc, SignerInfo},
-    Any, Coin,
+    tx::{self, Body, Fee, Msg, Raw, SequenceNumber},
+    AccountId, Any, Coin,
 };
 use secp256k1::All;
 
 use super::{sender::Sender, DaemonError};
 
-const GAS_BUFFER: f64 = 1.3;
+/// Default multiplier applied to the simulated gas.
+pub const GAS_BUFFER: f64 = 1.3;
 
 /// Struct used to build a raw transaction and broadcast it with a sender.
 #[derive(Clone, Debug)]
@@ -21,6 +21,13 @@ pub struct TxBuilder {
     pub(crate) gas_limit: Option<u64>,
     // if defined, use this sequence, else get it from the node
     pub(crate) sequence: Option<SequenceNumber>,
+    // if defined, use this instead of the sender's fee granter
+    pub(crate) fee_granter: Option<String>,
+    // if defined, use these instead of the sender's gas configuration
+    pub(crate) fee_denom: Option<String>,
+    pub(crate) gas_price: Option<f64>,
+    pub(crate) gas_adjustment: Option<f64>,
+    pub(crate) max_fee: Option<u128>,
 }
 
 impl TxBuilder {
@@ -31,6 +38,11 @@ impl TxBuilder {
             fee_amount: None,
             gas_limit: None,
             sequence: None,
+            fee_granter: None,
+            fee_denom: None,
+            gas_price: None,
+            gas_adjustment: None,
+            max_fee: None,
         }
     }
     /// Set a fixed fee amount for the tx
@@ -49,6 +61,33 @@ impl TxBuilder {
         self
     }
 
+    /// Set the fee granter for the tx.
+    /// The fees are deducted from the granter's allowance to the signer.
+    pub fn fee_granter(&mut self, granter: impl Into<String>) -> &mut Self {
+        self.fee_granter = Some(granter.into());
+        self
+    }
+    /// Set the denom the fee of the tx is paid in
+    pub fn fee_denom(&mut self, denom: impl Into<String>) -> &mut Self {
+        self.fee_denom = Some(denom.into());
+        self
+    }
+    /// Set the gas price used to calculate the fee of the tx
+    pub fn gas_price(&mut self, gas_price: f64) -> &mut Self {
+        self.gas_price = Some(gas_price);
+        self
+    }
+    /// Set the multiplier applied to the simulated gas of the tx
+    pub fn gas_adjustment(&mut self, gas_adjustment: f64) -> &mut Self {
+        self.gas_adjustment = Some(gas_adjustment);
+        self
+    }
+    /// Set the maximum fee amount that can be paid for the tx
+    pub fn max_fee(&mut self, max_fee: u128) -> &mut Self {
+        self.max_fee = Some(max_fee);
+        self
+    }
+
     /// Builds the body of the tx with a given memo and timeout.
     pub fn build_body<T: cosmrs::tx::Msg>(
         msgs: Vec<T>,
@@ -61,59 +100,106 @@ impl TxBuilder {
             .collect::<Result<Vec<Any>, _>>()
             .unwrap();
 
+        Self::build_body_any(msgs, memo, timeout)
+    }
+
+    /// Builds the body of the tx from already encoded messages with a given memo and timeout.
+    pub fn build_body_any(msgs: Vec<Any>, memo: Option<&str>, timeout: u64) -> tx::Body {
         tx::Body::new(msgs, memo.unwrap_or_default(), timeout as u32)
     }
 
-    pub(crate) fn build_fee(amount: impl Into<u128>, denom: &str, gas_limit: u64) -> Fee {
+    pub(crate) fn build_fee(
+        amount: impl Into<u128>,
+        denom: &str,
+        gas_limit: u64,
+        granter: Option<AccountId>,
+        payer: Option<AccountId>,
+    ) -> Fee {
         let fee = Coin::new(amount.into(), denom).unwrap();
-        Fee::from_amount_and_gas(fee, gas_limit)
+        let mut fee = Fee::from_amount_and_gas(fee, gas_limit);
+        fee.granter = granter;
+        fee.payer = payer;
+        fee
+    }
+
+    /// Calculates the gas limit and fee amount from the simulated gas usage.
+    pub(crate) fn calculate_fee(
+        sim_gas_used: u64,
+        gas_adjustment: f64,
+        gas_price: f64,
+    ) -> (u128, u64) {
+        let gas_expected = sim_gas_used as f64 * gas_adjustment;
+        // round up so we never pay less than the min gas price
+        let fee_amount = (gas_expected * gas_price).ceil();
+        (fee_amount as u128, gas_expected as u64)
     }
 
     /// Builds the raw tx with a given body and fee and signs it.
     /// Sets the TxBuilder's gas limit to its simulated amount for later use.
     pub async fn build(&mut self, wallet: &Sender<All>) -> Result<Raw, DaemonError> {
-        // get the account number of the wallet
-        let BaseAccount {
-            account_number,
-            sequence,
-            ..
-        } = wallet.base_account().await?;
+        // get the account number and next sequence of the wallet
+        let (account_number, sequence) = wallet.account_sequence().await?;
 
         // overwrite sequence if set (can be used for concurrent txs)
         let sequence = self.sequence.unwrap_or(sequence);
 
-        //
-        let (tx_fee, gas_limit) = if let (Some(fee), Some(gas_limit)) =
-            (self.fee_amount, self.gas_limit)
-        {
-            log::debug!(
-                "Using pre-defined fee and gas limits: {}, {}",
-                fee,
-                gas_limit
-            );
-            (fee, gas_limit)
-        } else {
-            let sim_gas_used = wallet
-                .calculate_gas(&self.body, sequence, account_number)
-                .await?;
-            log::debug!("Simulated gas needed {:?}", sim_gas_used);
-
-            let gas_expected = sim_gas_used as f64 * GAS_BUFFER;
-            let fee_amount = gas_expected
-                * (wallet.daemon_state.chain_data.fees.fee_tokens[0].fixed_min_gas_price + 0.00001);
-
-            log::debug!("Calculated fee needed: {:?}", fee_amount);
-            // set the gas limit of self for future txs
-            // there's no way to change the tx_builder body so simulation gas should remain the same as well
-            self.gas_limit = Some(gas_expected as u64);
-
-            (fee_amount as u128, gas_expected as u64)
+        let fee_denom = self.fee_denom.clone().unwrap_or_else(|| wallet.fee_denom());
+
+        let (tx_fee, gas_limit) =
+            if let (Some(fee), Some(gas_limit)) = (self.fee_amount, self.gas_limit) {
+                log::debug!(
+                    "Using pre-defined fee and gas limits: {}, {}",
+                    fee,
+                    gas_limit
+                );
+                (fee, gas_limit)
+            } else {
+                let sim_gas_used = wallet
+                    .calculate_gas(&self.body, sequence, account_number)
+                    .await?;
+                log::debug!("Simulated gas needed {:?}", sim_gas_used);
+
+                let gas_adjustment = self
+                    .gas_adjustment
+                    .unwrap_or_else(|| wallet.gas_adjustment());
+                let gas_price = match self.gas_price {
+                    Some(gas_price) => gas_price,
+                    None => wallet.gas_price(&fee_denom).await?,
+                };
+
+                let (fee_amount, gas_expected) =
+                    Self::calculate_fee(sim_gas_used, gas_adjustment, gas_price);
+
+                log::debug!("Calculated fee needed: {:?}", fee_amount);
+                // set the gas limit of self for future txs
+                // there's no way to change the tx_builder body so simulation gas should remain the same as well
+                self.gas_limit = Some(gas_expected);
+
+                (fee_amount, gas_expected)
+            };
+
+        if let Some(max_fee) = self.max_fee.or(wallet.options.max_fee) {
+            if tx_fee > max_fee {
+                return Err(DaemonError::MaxFeeExceeded {
+                    fee: tx_fee,
+                    max_fee,
+                    denom: fee_denom,
+                });
+            }
+        }
+
+        // a tx specific granter takes precedence over the one of the wallet
+        let fee_granter = match &self.fee_granter {
+            Some(granter) => Some(AccountId::from_str(granter)?),
+            None => wallet.fee_granter()?,
         };
 
         let fee = Self::build_fee(
             tx_fee,
-            &wallet.daemon_state.chain_data.fees.fee_tokens[0].denom,
+            &fee_denom,
             gas_limit,
+            fee_granter,
+            wallet.fee_payer()?,
         );
 
         log::debug!(
@@ -123,19 +209,50 @@ impl TxBuilder {
             sequence
         );
 
-        let auth_info = SignerInfo {
-            public_key: wallet.private_key.get_signer_public_key(&wallet.secp),
-            mode_info: ModeInfo::single(SignMode::Direct),
-            sequence,
-        }
-        .auth_info(fee);
+        wallet
+            .sign_tx(&self.body, fee, account_number, sequence)
+            .await
+    }
+}
 
-        let sign_doc = SignDoc::new(
-            &self.body,
-            &auth_info,
-            &Id::try_from(wallet.daemon_state.chain_data.chain_id.to_string())?,
-            account_number,
-        )?;
-        wallet.sign(sign_doc).map_err(Into::into)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_fee_with_granter_and_payer() {
+        let granter = AccountId::new("juno", &[1u8; 20]).unwrap();
+        let payer = AccountId::new("juno", &[2u8; 20]).unwrap();
+
+        let fee = TxBuilder::build_fee(
+            100u128,
+            "ujuno",
+            200_000,
+            Some(granter.clone()),
+            Some(payer.clone()),
+        );
+
+        assert_eq!(fee.amount[0].amount, 100u128);
+        assert_eq!(fee.gas_limit, 200_000);
+        assert_eq!(fee.granter, Some(granter));
+        assert_eq!(fee.payer, Some(payer));
+
+        let fee = TxBuilder::build_fee(100u128, "ujuno", 200_000, None, None);
+        assert!(fee.granter.is_none());
+        assert!(fee.payer.is_none());
+    }
+
+    #[test]
+    fn calculate_fee_rounds_up() {
+        let (fee, gas_limit) = TxBuilder::calculate_fee(100_000, GAS_BUFFER, 0.025);
+        assert_eq!(gas_limit, 130_000);
+        assert_eq!(fee, 3250);
+
+        let (fee, gas_limit) = TxBuilder::calculate_fee(100_001, 1.0, 0.0026);
+        assert_eq!(gas_limit, 100_001);
+        assert_eq!(fee, 261);
+
+        let (fee, _) = TxBuilder::calculate_fee(100_000, 1.5, 0.0);
+        assert_eq!(fee, 0);
     }
 }
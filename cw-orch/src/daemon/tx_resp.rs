@@ -151,6 +151,10 @@ impl IndexResponse for CosmTxResponse {
         parsed_events
     }
 
+    fn tx_hash(&self) -> Option<String> {
+        Some(self.txhash.clone())
+    }
+
     fn data(&self) -> Option<Binary> {
         if self.data.is_empty() {
             None
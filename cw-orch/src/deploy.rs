@@ -1,9 +1,19 @@
-//! Introduces the Deploy trait only
+//! Introduces the Deploy trait and deployment plans to orchestrate its steps
+mod multi_chain;
+mod plan;
+mod verify;
+
+pub use multi_chain::{ChainDeployment, DeploymentSummary, MultiChainDeployment};
+pub use plan::{DeploymentPlan, PlanReport};
+pub use verify::{
+    Check, CheckKind, CheckStatus, ContractVerification, VerifiableContract, VerificationReport,
+    VerifyOptions,
+};
+
+use crate::environment::WasmQuerier;
 use crate::prelude::*;
+use crate::state_file::StateFile;
 use cosmwasm_std::Addr;
-use serde_json::from_reader;
-use serde_json::Value;
-use std::fs::File;
 
 /// Indicates the ability to deploy an application to a mock chain.
 ///
@@ -65,94 +75,122 @@ pub trait Deploy<Chain: CwEnv>: Sized {
         Self::store_on(chain)
     }
 
+    /// Store the application on every chain, see [`Deploy::store_on`].
+    /// A failure on one chain doesn't stop the others, the outcome of every chain is returned.
+    fn store_on_chains(
+        chains: impl IntoIterator<Item = Chain>,
+    ) -> MultiChainDeployment<Self, Self::Error> {
+        MultiChainDeployment {
+            chains: chains
+                .into_iter()
+                .map(|chain| ChainDeployment::run(chain, Self::store_on))
+                .collect(),
+        }
+    }
+
+    /// Deploy the application on every chain with the same data, see [`Deploy::deploy_on`].
+    /// A failure on one chain doesn't stop the others, the outcome of every chain is returned.
+    fn deploy_on_chains(
+        chains: impl IntoIterator<Item = Chain>,
+        data: Self::DeployData,
+    ) -> MultiChainDeployment<Self, Self::Error>
+    where
+        Self::DeployData: Clone,
+    {
+        MultiChainDeployment {
+            chains: chains
+                .into_iter()
+                .map(|chain| {
+                    ChainDeployment::run(chain, |chain| Self::deploy_on(chain, data.clone()))
+                })
+                .collect(),
+        }
+    }
+
     /// Set the default contract state for a contract, so that users can retrieve it in their application when importing the library
     fn set_contracts_state(&mut self) {
-        let state_file = self.deployed_state_file_path();
-        let all_contracts = self.get_contracts_mut();
-        if let Some(state_file) = state_file {
-            if let Ok(module_state_json) = read_json(&state_file) {
-                for contract in all_contracts {
-                    // We set the code_id and/or address of the contract in question if they are not present already
-                    let deploy_details = contract.get_chain().state().deploy_details();
-                    // We load the file
-                    // We try to get the code_id for the contract
-                    if contract.code_id().is_err() {
-                        let code_id = module_state_json
-                            .get(deploy_details.chain_name.clone())
-                            .unwrap_or(&Value::Null)
-                            .get(deploy_details.chain_id.to_string())
-                            .unwrap_or(&Value::Null)
-                            .get("code_ids")
-                            .unwrap_or(&Value::Null)
-                            .get(contract.id());
-
-                        if let Some(code_id) = code_id {
-                            if code_id.is_u64() {
-                                contract.set_default_code_id(code_id.as_u64().unwrap())
-                            }
-                        }
-                    }
-                    // We try to get the address for the contract
-                    if contract.address().is_err() {
-                        // Try and get the code id from file
-                        let address = module_state_json
-                            .get(deploy_details.chain_name.clone())
-                            .unwrap_or(&Value::Null)
-                            .get(deploy_details.chain_id.to_string())
-                            .unwrap_or(&Value::Null)
-                            .get(deploy_details.deployment_id)
-                            .unwrap_or(&Value::Null)
-                            .get(contract.id());
+        let Some(state_file) = self.deployed_state_file_path() else {
+            return;
+        };
+        let state = match StateFile::load(&state_file) {
+            Ok(state) => state,
+            Err(e) => {
+                log::warn!(
+                    "Could not load the deployed state file {}: {}",
+                    state_file,
+                    e
+                );
+                return;
+            }
+        };
 
-                        if let Some(address) = address {
-                            if address.is_string() {
-                                contract.set_default_address(&Addr::unchecked(
-                                    address.as_str().unwrap(),
-                                ))
-                            }
-                        }
-                    }
+        for contract in self.get_contracts_mut() {
+            // We set the code_id and/or address of the contract in question if they are not present already
+            let deploy_details = contract.get_chain().state().deploy_details();
+            let Some(chain_state) =
+                state.chain(&deploy_details.chain_name, &deploy_details.chain_id)
+            else {
+                continue;
+            };
+            if contract.code_id().is_err() {
+                if let Some(code_id) = chain_state.code_id(&contract.id()) {
+                    contract.set_default_code_id(code_id)
+                }
+            }
+            if contract.address().is_err() {
+                if let Some(address) =
+                    chain_state.address(&deploy_details.deployment_id, &contract.id())
+                {
+                    contract.set_default_address(&Addr::unchecked(address))
                 }
             }
         }
     }
 
     /// Gets all the chain ids on which the library is deployed on
-    /// This loads all chains that are registered in the crate-local daemon_state file
-    /// The state file should have the following format :
-    /// {
-    ///     "juno":{
-    ///         "juno-1":{
-    ///             ...
-    ///         },
-    ///         "uni-6": {
-
-    ///         }
-    ///     }
-    ///     ...
-    /// }
-    /// So this function actually looks for the second level of indices in the deployed_state_file
+    /// This loads all chains that are registered in the crate-local state file, see [`crate::state_file`] for its layout.
+    /// A state file that can't be read is logged and treated as empty, use [`Deploy::try_get_all_deployed_chains`] to handle it.
     fn get_all_deployed_chains(&self) -> Vec<String> {
-        let deployed_state_file = self.deployed_state_file_path();
-        if let Some(state_file) = deployed_state_file {
-            if let Ok(module_state_json) = read_json(&state_file) {
-                let all_chain_ids: Vec<String> = module_state_json
-                    .as_object()
-                    .unwrap()
-                    .into_iter()
-                    .flat_map(|(_, v)| {
-                        v.as_object()
-                            .unwrap()
-                            .into_iter()
-                            .map(|(chain_id, _)| chain_id.clone())
-                            .collect::<Vec<_>>()
-                    })
-                    .collect();
+        self.try_get_all_deployed_chains().unwrap_or_else(|e| {
+            log::warn!("Could not read the deployed state file: {}", e);
+            vec![]
+        })
+    }
 
-                return all_chain_ids;
-            }
+    /// Gets all the chain ids on which the library is deployed on, see [`Deploy::get_all_deployed_chains`].
+    /// Malformed state files are reported as an error.
+    fn try_get_all_deployed_chains(&self) -> Result<Vec<String>, CwOrchError> {
+        match self.deployed_state_file_path() {
+            Some(state_file) => Ok(StateFile::load(state_file)?.chain_ids()),
+            None => Ok(vec![]),
         }
-        vec![]
+    }
+
+    /// Check that the code ids and addresses in the state of the contracts exist on chain and match the expectations in `options`.
+    /// Failed checks are reported rather than returned as errors, see [`VerificationReport::is_success`].
+    fn verify(&mut self, options: &VerifyOptions) -> VerificationReport
+    where
+        Chain: WasmQuerier,
+    {
+        let contracts = self.get_verifiable_contracts();
+        let contracts: Vec<&dyn VerifiableContract<Chain>> =
+            contracts.iter().map(|contract| &**contract).collect();
+        verify::verify(&contracts, options)
+    }
+
+    /// Returns the contracts checked by [`Deploy::verify`].
+    /// Defaults to [`Deploy::get_contracts_mut`] without expected checksums,
+    /// override it to check the code of [`Uploadable`] contracts against their wasm file.
+    fn get_verifiable_contracts(&mut self) -> Vec<Box<dyn VerifiableContract<Chain> + '_>>
+    where
+        Chain: WasmQuerier,
+    {
+        self.get_contracts_mut()
+            .into_iter()
+            .map(|contract| {
+                Box::new(verify::WithoutWasm(*contract)) as Box<dyn VerifiableContract<Chain> + '_>
+            })
+            .collect()
     }
 
     /// Sets the custom state file path for exporting the state with the package.
@@ -169,9 +207,9 @@ pub trait Deploy<Chain: CwEnv>: Sized {
     fn load_from(chain: Chain) -> Result<Self, Self::Error>;
 }
 
-/// Read a json value from a file (redundant with crate::daemon::json_file, but returns an err instead of panicking)
-pub fn read_json(filename: &String) -> anyhow::Result<Value> {
-    let file = File::open(filename)?;
-    let json: serde_json::Value = from_reader(file)?;
-    Ok(json)
+/// Read the chains of a state file as json, by chain name and chain id.
+#[deprecated(note = "use `StateFile::load`, which migrates and types the state file")]
+pub fn read_json(filename: &String) -> anyhow::Result<serde_json::Value> {
+    let state = StateFile::load(filename)?;
+    Ok(serde_json::to_value(state.chains)?)
 }
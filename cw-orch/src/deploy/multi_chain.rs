@@ -0,0 +1,401 @@
+//! Results of deploying an application to several chains, see [`Deploy::deploy_on_chains`](super::Deploy::deploy_on_chains).
+
+use std::{collections::HashMap, fmt};
+
+use cosmwasm_std::Addr;
+
+use crate::{
+    environment::CwEnv,
+    state::{ChainState, StateInterface},
+};
+
+/// Outcome of the deployment to a single chain
+pub struct ChainDeployment<T, E> {
+    /// E.g. juno-1
+    pub chain_id: String,
+    /// E.g. juno
+    pub chain_name: String,
+    /// Deployment id the application was deployed under
+    pub deployment_id: String,
+    /// The deployed application or the error that stopped the deployment
+    pub result: Result<T, E>,
+    /// Code ids in the state of the chain after the deployment
+    pub code_ids: HashMap<String, u64>,
+    /// Contract addresses in the state of the chain after the deployment
+    pub addresses: HashMap<String, Addr>,
+    /// Checksums recorded in the deployment history for the code ids in the state, by contract id
+    pub checksums: HashMap<String, String>,
+}
+
+impl<T, E> ChainDeployment<T, E> {
+    /// Chain id and deployment id of the deployment
+    pub fn key(&self) -> (&str, &str) {
+        (&self.chain_id, &self.deployment_id)
+    }
+
+    /// Contract ids of `reference` whose checksum on the chain differs, or wasn't recorded, sorted.
+    pub fn outdated(&self, reference: &HashMap<String, String>) -> Vec<String> {
+        let mut outdated: Vec<String> = reference
+            .iter()
+            .filter(|(id, checksum)| self.checksums.get(*id) != Some(*checksum))
+            .map(|(id, _)| id.clone())
+            .collect();
+        outdated.sort();
+        outdated
+    }
+
+    /// Run `deploy` on the chain and record its state afterwards, also when the deployment failed.
+    pub(crate) fn run<Chain: CwEnv>(
+        chain: Chain,
+        deploy: impl FnOnce(Chain) -> Result<T, E>,
+    ) -> Self {
+        let state = chain.state();
+        let details = state.deploy_details();
+        log::info!(
+            "Deploying to {} ({}) under deployment id {}",
+            details.chain_name,
+            details.chain_id,
+            details.deployment_id
+        );
+
+        let result = deploy(chain);
+
+        let code_ids = state.get_all_code_ids().unwrap_or_else(|e| {
+            log::warn!("Could not read the code ids of {}: {}", details.chain_id, e);
+            HashMap::new()
+        });
+        let addresses = state.get_all_addresses().unwrap_or_else(|e| {
+            log::warn!(
+                "Could not read the addresses of {}: {}",
+                details.chain_id,
+                e
+            );
+            HashMap::new()
+        });
+        let checksums = code_ids
+            .iter()
+            .filter_map(|(id, code_id)| {
+                let history = state.get_history(id).ok()?;
+                let record = history
+                    .into_iter()
+                    .rev()
+                    .find(|record| record.code_id == Some(*code_id) && record.checksum.is_some())?;
+                Some((id.clone(), record.checksum?))
+            })
+            .collect();
+        Self {
+            chain_id: details.chain_id,
+            chain_name: details.chain_name,
+            deployment_id: details.deployment_id,
+            result,
+            code_ids,
+            addresses,
+            checksums,
+        }
+    }
+}
+
+/// Outcome of the deployment of an application to several chains, in the order the chains were given.
+/// The same chain id can be deployed to under several deployment ids, so deployments are keyed by both.
+pub struct MultiChainDeployment<T, E> {
+    /// Deployment per chain
+    pub chains: Vec<ChainDeployment<T, E>>,
+}
+
+impl<T, E> MultiChainDeployment<T, E> {
+    /// Whether the deployment succeeded on every chain
+    pub fn is_success(&self) -> bool {
+        self.chains.iter().all(|chain| chain.result.is_ok())
+    }
+
+    /// Deployed applications, by chain id and deployment id
+    pub fn deployed(&self) -> impl Iterator<Item = ((&str, &str), &T)> {
+        self.chains
+            .iter()
+            .filter_map(|chain| chain.result.as_ref().ok().map(|app| (chain.key(), app)))
+    }
+
+    /// Deployment errors, by chain id and deployment id
+    pub fn failed(&self) -> impl Iterator<Item = ((&str, &str), &E)> {
+        self.chains
+            .iter()
+            .filter_map(|chain| chain.result.as_ref().err().map(|err| (chain.key(), err)))
+    }
+
+    /// Get the deployment to a chain under a deployment id
+    pub fn chain(&self, chain_id: &str, deployment_id: &str) -> Option<&ChainDeployment<T, E>> {
+        self.chains
+            .iter()
+            .find(|chain| chain.key() == (chain_id, deployment_id))
+    }
+
+    /// Checksums of the first deployment that succeeded, the reference of [`MultiChainDeployment::summary`]
+    pub fn reference_checksums(&self) -> HashMap<String, String> {
+        self.chains
+            .iter()
+            .find(|chain| chain.result.is_ok())
+            .map(|chain| chain.checksums.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl<T, E: fmt::Display> MultiChainDeployment<T, E> {
+    /// Which deployments succeeded and which failed.
+    /// Succeeded deployments are outdated if their code differs from the first deployment that succeeded.
+    pub fn summary(&self) -> DeploymentSummary {
+        self.summary_against(&self.reference_checksums())
+    }
+
+    /// Which deployments succeeded and which failed.
+    /// Succeeded deployments are outdated if their code differs from `reference`, checksums by contract id,
+    /// e.g. the checksums of the local wasm files.
+    pub fn summary_against(&self, reference: &HashMap<String, String>) -> DeploymentSummary {
+        DeploymentSummary {
+            succeeded: self
+                .deployed()
+                .map(|((chain_id, deployment_id), _)| {
+                    (chain_id.to_string(), deployment_id.to_string())
+                })
+                .collect(),
+            outdated: self
+                .chains
+                .iter()
+                .filter(|chain| chain.result.is_ok())
+                .filter_map(|chain| {
+                    let outdated = chain.outdated(reference);
+                    (!outdated.is_empty()).then(|| {
+                        (
+                            chain.chain_id.clone(),
+                            chain.deployment_id.clone(),
+                            outdated,
+                        )
+                    })
+                })
+                .collect(),
+            failed: self
+                .failed()
+                .map(|((chain_id, deployment_id), err)| {
+                    (
+                        chain_id.to_string(),
+                        deployment_id.to_string(),
+                        err.to_string(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Summary of a [`MultiChainDeployment`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeploymentSummary {
+    /// Chain ids and deployment ids where the deployment succeeded
+    pub succeeded: Vec<(String, String)>,
+    /// Succeeded deployments whose code differs from the reference, with the outdated contract ids
+    pub outdated: Vec<(String, String, Vec<String>)>,
+    /// Chain ids and deployment ids where the deployment failed, with the error
+    pub failed: Vec<(String, String, String)>,
+}
+
+impl fmt::Display for DeploymentSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} deployments succeeded ({} outdated), {} failed",
+            self.succeeded.len(),
+            self.outdated.len(),
+            self.failed.len()
+        )?;
+        for (chain_id, deployment_id) in &self.succeeded {
+            writeln!(f, "  succeeded: {chain_id} ({deployment_id})")?;
+        }
+        for (chain_id, deployment_id, contracts) in &self.outdated {
+            writeln!(
+                f,
+                "  outdated: {chain_id} ({deployment_id}): {}",
+                contracts.join(", ")
+            )?;
+        }
+        for (chain_id, deployment_id, err) in &self.failed {
+            writeln!(f, "  failed: {chain_id} ({deployment_id}): {err}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        deploy::Deploy,
+        mock::{Mock, MockState},
+        prelude::*,
+        state::{DeployDetails, DeploymentAction, DeploymentRecord},
+    };
+
+    /// Mock state of a chain with its own chain id
+    #[derive(Clone)]
+    struct ChainMockState {
+        chain_id: &'static str,
+        inner: MockState,
+    }
+
+    impl StateInterface for ChainMockState {
+        fn get_address(&self, contract_id: &str) -> Result<Addr, CwOrchError> {
+            self.inner.get_address(contract_id)
+        }
+
+        fn set_address(&mut self, contract_id: &str, address: &Addr) {
+            self.inner.set_address(contract_id, address)
+        }
+
+        fn get_code_id(&self, contract_id: &str) -> Result<u64, CwOrchError> {
+            self.inner.get_code_id(contract_id)
+        }
+
+        fn set_code_id(&mut self, contract_id: &str, code_id: u64) {
+            self.inner.set_code_id(contract_id, code_id)
+        }
+
+        fn get_all_addresses(&self) -> Result<HashMap<String, Addr>, CwOrchError> {
+            self.inner.get_all_addresses()
+        }
+
+        fn get_all_code_ids(&self) -> Result<HashMap<String, u64>, CwOrchError> {
+            self.inner.get_all_code_ids()
+        }
+
+        fn deploy_details(&self) -> DeployDetails {
+            DeployDetails {
+                chain_id: self.chain_id.to_string(),
+                chain_name: "mock".to_string(),
+                deployment_id: "default".to_string(),
+            }
+        }
+
+        fn get_history(&self, contract_id: &str) -> Result<Vec<DeploymentRecord>, CwOrchError> {
+            self.inner.get_history(contract_id)
+        }
+
+        fn try_record_deployment(
+            &mut self,
+            contract_id: &str,
+            record: DeploymentRecord,
+        ) -> Result<(), CwOrchError> {
+            self.inner.try_record_deployment(contract_id, record)
+        }
+    }
+
+    type Chain = Mock<ChainMockState>;
+
+    fn chain(chain_id: &'static str, sender: &str) -> Chain {
+        Mock::new_custom(
+            &Addr::unchecked(sender),
+            ChainMockState {
+                chain_id,
+                inner: MockState::new(),
+            },
+        )
+    }
+
+    struct App;
+
+    impl Deploy<Chain> for App {
+        type Error = CwOrchError;
+        type DeployData = String;
+
+        fn store_on(chain: Chain) -> Result<Self, CwOrchError> {
+            // the code of the chain with the old release has another checksum
+            let (code_id, checksum) = match chain.state().deploy_details().chain_id.as_str() {
+                "old-1" => (2, "old"),
+                _ => (1, "new"),
+            };
+            let mut state = chain.state();
+            state.set_code_id("app", code_id);
+            state.try_record_deployment(
+                "app",
+                DeploymentRecord {
+                    code_id: Some(code_id),
+                    checksum: Some(checksum.to_string()),
+                    ..DeploymentRecord::new(DeploymentAction::Upload)
+                },
+            )?;
+            Ok(App)
+        }
+
+        fn deploy_on(chain: Chain, data: String) -> Result<Self, CwOrchError> {
+            if chain.sender().as_str() == "broke" {
+                return Err(CwOrchError::StdErr("insufficient funds".to_string()));
+            }
+            let app = Self::store_on(chain.clone())?;
+            chain.state().set_address("app", &Addr::unchecked(data));
+            Ok(app)
+        }
+
+        fn get_contracts_mut(&mut self) -> Vec<Box<&mut dyn ContractInstance<Chain>>> {
+            vec![]
+        }
+
+        fn load_from(_chain: Chain) -> Result<Self, CwOrchError> {
+            Ok(App)
+        }
+    }
+
+    #[test]
+    fn failures_dont_stop_other_chains() {
+        let chains = vec![
+            chain("juno-1", "sender"),
+            chain("osmosis-1", "broke"),
+            chain("old-1", "sender"),
+        ];
+
+        let deployment = App::deploy_on_chains(chains, "app-address".to_string());
+
+        assert!(!deployment.is_success());
+        assert_eq!(deployment.deployed().count(), 2);
+        assert_eq!(deployment.failed().count(), 1);
+
+        let juno = deployment.chain("juno-1", "default").unwrap();
+        assert!(juno.result.is_ok());
+        assert_eq!(juno.code_ids.get("app"), Some(&1));
+        assert_eq!(
+            juno.addresses.get("app"),
+            Some(&Addr::unchecked("app-address"))
+        );
+        assert_eq!(juno.checksums.get("app").map(String::as_str), Some("new"));
+
+        let osmosis = deployment.chain("osmosis-1", "default").unwrap();
+        assert!(osmosis.result.is_err());
+        assert!(osmosis.addresses.is_empty());
+        assert!(deployment.chain("osmosis-1", "v2").is_none());
+
+        let summary = deployment.summary();
+        assert_eq!(
+            summary.succeeded,
+            vec![
+                ("juno-1".to_string(), "default".to_string()),
+                ("old-1".to_string(), "default".to_string())
+            ]
+        );
+        assert_eq!(
+            summary.outdated,
+            vec![(
+                "old-1".to_string(),
+                "default".to_string(),
+                vec!["app".to_string()]
+            )]
+        );
+        assert_eq!(summary.failed[0].0, "osmosis-1");
+        assert_eq!(
+            summary.failed[0].2,
+            "Generic Error insufficient funds".to_string()
+        );
+        assert!(summary
+            .to_string()
+            .contains("outdated: old-1 (default): app"));
+
+        // compared to the local wasm, every chain is outdated
+        let local = HashMap::from([("app".to_string(), "local".to_string())]);
+        assert_eq!(deployment.summary_against(&local).outdated.len(), 2);
+    }
+}
@@ -0,0 +1,407 @@
+//! Deployment plans: steps with dependencies that are run in order and resumed after a failure.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{
+    environment::CwEnv,
+    error::CwOrchError,
+    state::{ChainState, DeploymentRecord, StateInterface, StepKind, StepRecord, StepStatus},
+};
+
+type StepFn<'a, Chain, E> = Box<dyn FnMut(&Chain) -> Result<(), E> + 'a>;
+
+struct Step<'a, Chain, E> {
+    id: String,
+    kind: StepKind,
+    dependencies: Vec<String>,
+    run: StepFn<'a, Chain, E>,
+}
+
+/// Steps of a deployment with their dependencies.
+///
+/// Steps run after all their dependencies, in declaration order otherwise.
+/// The outcome of every step is recorded in a journal in the state of the chain, under the name of the plan.
+/// Running the plan again after a failure skips the steps that completed and resumes at the step that failed.
+///
+/// ## Example
+/// ```ignore
+/// let mut plan = DeploymentPlan::<_, CwOrchError>::new("my-app");
+/// plan.upload("upload-token", &[], |_| token.upload().map(|_| ()))
+///     .instantiate("instantiate-token", &["upload-token"], |_| {
+///         token.instantiate(&init_msg, None, None).map(|_| ())
+///     });
+/// plan.run(&chain)?;
+/// ```
+pub struct DeploymentPlan<'a, Chain, E = CwOrchError> {
+    name: String,
+    steps: Vec<Step<'a, Chain, E>>,
+}
+
+/// Steps run by [`DeploymentPlan::run`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlanReport {
+    /// Steps that ran, in order
+    pub executed: Vec<String>,
+    /// Steps that were skipped because they completed in an earlier run
+    pub skipped: Vec<String>,
+}
+
+impl<'a, Chain: CwEnv, E: From<CwOrchError> + Display> DeploymentPlan<'a, Chain, E> {
+    /// Create an empty plan. The name identifies its journal, so it should be unique per application.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            steps: vec![],
+        }
+    }
+
+    /// Name of the plan
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Add a step that runs after the steps in `dependencies`.
+    pub fn step(
+        &mut self,
+        id: &str,
+        kind: StepKind,
+        dependencies: &[&str],
+        run: impl FnMut(&Chain) -> Result<(), E> + 'a,
+    ) -> &mut Self {
+        self.steps.push(Step {
+            id: id.to_string(),
+            kind,
+            dependencies: dependencies.iter().map(ToString::to_string).collect(),
+            run: Box::new(run),
+        });
+        self
+    }
+
+    /// Add a step that uploads code
+    pub fn upload(
+        &mut self,
+        id: &str,
+        dependencies: &[&str],
+        run: impl FnMut(&Chain) -> Result<(), E> + 'a,
+    ) -> &mut Self {
+        self.step(id, StepKind::Upload, dependencies, run)
+    }
+
+    /// Add a step that instantiates contracts
+    pub fn instantiate(
+        &mut self,
+        id: &str,
+        dependencies: &[&str],
+        run: impl FnMut(&Chain) -> Result<(), E> + 'a,
+    ) -> &mut Self {
+        self.step(id, StepKind::Instantiate, dependencies, run)
+    }
+
+    /// Add a step that configures instantiated contracts
+    pub fn configure(
+        &mut self,
+        id: &str,
+        dependencies: &[&str],
+        run: impl FnMut(&Chain) -> Result<(), E> + 'a,
+    ) -> &mut Self {
+        self.step(id, StepKind::Configure, dependencies, run)
+    }
+
+    /// Ids of the steps in the order they run.
+    /// Errors if a step id is used twice, a dependency is unknown or the dependencies form a cycle.
+    pub fn order(&self) -> Result<Vec<&str>, CwOrchError> {
+        Ok(self
+            .ordered_steps()?
+            .into_iter()
+            .map(|index| self.steps[index].id.as_str())
+            .collect())
+    }
+
+    /// Run the steps that didn't complete yet, stopping at the first step that fails.
+    /// A completed step runs again if a code id or address it set was removed from the state.
+    /// The journal is cleared once every step completed, so the next run starts from the first step.
+    pub fn run(&mut self, chain: &Chain) -> Result<PlanReport, E> {
+        let order = self.ordered_steps()?;
+        let mut state = chain.state();
+        let journal = state.get_journal(&self.name)?;
+        let mut report = PlanReport::default();
+
+        for index in order {
+            let step = &mut self.steps[index];
+            let code_ids = state.get_all_code_ids()?;
+            let addresses = state.get_all_addresses()?;
+            match journal.get(&step.id) {
+                Some(record)
+                    if record.is_completed()
+                        && record.code_ids.iter().all(|id| code_ids.contains_key(id))
+                        && record.addresses.iter().all(|id| addresses.contains_key(id)) =>
+                {
+                    log::info!("Skipping completed step {} of {}", step.id, self.name);
+                    report.skipped.push(step.id.clone());
+                    continue;
+                }
+                Some(record) if record.is_completed() => log::info!(
+                    "State entries of step {} of {} are missing, running it again",
+                    step.id,
+                    self.name
+                ),
+                _ => {}
+            }
+
+            log::info!("Running step {} of {}", step.id, self.name);
+            let res = (step.run)(chain);
+            let record = StepRecord {
+                kind: step.kind,
+                status: StepStatus::Completed,
+                error: None,
+                timestamp: DeploymentRecord::now(),
+                code_ids: vec![],
+                addresses: vec![],
+            };
+            match res {
+                Ok(()) => {
+                    // the entries the step set, to run it again if they are removed
+                    let record = StepRecord {
+                        code_ids: changed(&code_ids, state.get_all_code_ids()?),
+                        addresses: changed(&addresses, state.get_all_addresses()?),
+                        ..record
+                    };
+                    state.try_record_step(&self.name, &step.id, record)?;
+                    report.executed.push(step.id.clone());
+                }
+                Err(e) => {
+                    log::error!("Step {} of {} failed: {}", step.id, self.name, e);
+                    let record = StepRecord {
+                        status: StepStatus::Failed,
+                        error: Some(e.to_string()),
+                        ..record
+                    };
+                    // the step error is more useful than a failure to record it
+                    if let Err(record_err) = state.try_record_step(&self.name, &step.id, record) {
+                        log::error!(
+                            "Failed to record the failure of {}: {}",
+                            step.id,
+                            record_err
+                        );
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        state.try_clear_journal(&self.name)?;
+        Ok(report)
+    }
+
+    /// Forget the outcome of earlier runs, so that the next run starts from the first step.
+    pub fn reset(&self, chain: &Chain) -> Result<(), CwOrchError> {
+        chain.state().try_clear_journal(&self.name)
+    }
+
+    /// Indexes of the steps in the order they run
+    fn ordered_steps(&self) -> Result<Vec<usize>, CwOrchError> {
+        let mut ids = HashSet::new();
+        for step in &self.steps {
+            if !ids.insert(step.id.as_str()) {
+                return Err(CwOrchError::DuplicateStep(step.id.clone()));
+            }
+        }
+        for step in &self.steps {
+            if let Some(dependency) = step
+                .dependencies
+                .iter()
+                .find(|dependency| !ids.contains(dependency.as_str()))
+            {
+                return Err(CwOrchError::UnknownStepDependency {
+                    step: step.id.clone(),
+                    dependency: dependency.clone(),
+                });
+            }
+        }
+
+        let mut placed = HashSet::new();
+        let mut order = vec![];
+        while order.len() < self.steps.len() {
+            let next = self.steps.iter().enumerate().find(|(_, step)| {
+                !placed.contains(step.id.as_str())
+                    && step
+                        .dependencies
+                        .iter()
+                        .all(|dependency| placed.contains(dependency.as_str()))
+            });
+            match next {
+                Some((index, step)) => {
+                    placed.insert(step.id.as_str());
+                    order.push(index);
+                }
+                None => {
+                    let cycle = self
+                        .steps
+                        .iter()
+                        .filter(|step| !placed.contains(step.id.as_str()))
+                        .map(|step| step.id.clone())
+                        .collect();
+                    return Err(CwOrchError::CyclicStepDependencies(cycle));
+                }
+            }
+        }
+        Ok(order)
+    }
+}
+
+/// Contract ids whose entry was added or changed
+fn changed<T: PartialEq>(before: &HashMap<String, T>, after: HashMap<String, T>) -> Vec<String> {
+    let mut ids: Vec<String> = after
+        .into_iter()
+        .filter(|(id, value)| before.get(id) != Some(value))
+        .map(|(id, _)| id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::Addr;
+
+    use super::*;
+    use crate::mock::Mock;
+
+    #[test]
+    fn steps_run_after_their_dependencies() {
+        let mut plan = DeploymentPlan::<Mock, CwOrchError>::new("app");
+        plan.configure("configure", &["instantiate-b", "instantiate-a"], |_| Ok(()))
+            .instantiate("instantiate-a", &["upload"], |_| Ok(()))
+            .instantiate("instantiate-b", &["instantiate-a"], |_| Ok(()))
+            .upload("upload", &[], |_| Ok(()));
+
+        assert_eq!(
+            plan.order().unwrap(),
+            vec!["upload", "instantiate-a", "instantiate-b", "configure"]
+        );
+    }
+
+    #[test]
+    fn invalid_dependencies_are_errors() {
+        let mut plan = DeploymentPlan::<Mock, CwOrchError>::new("app");
+        plan.upload("a", &["b"], |_| Ok(()))
+            .upload("b", &["a"], |_| Ok(()))
+            .upload("c", &[], |_| Ok(()));
+        assert!(matches!(
+            plan.order(),
+            Err(CwOrchError::CyclicStepDependencies(steps)) if steps == vec!["a", "b"]
+        ));
+
+        plan.upload("d", &["unknown"], |_| Ok(()));
+        assert!(matches!(
+            plan.order(),
+            Err(CwOrchError::UnknownStepDependency { step, .. }) if step == "d"
+        ));
+
+        plan.upload("c", &[], |_| Ok(()));
+        assert!(matches!(plan.order(), Err(CwOrchError::DuplicateStep(step)) if step == "c"));
+    }
+
+    #[test]
+    fn rerun_resumes_at_failed_step() {
+        let chain = Mock::new(&Addr::unchecked("sender"));
+        let runs = RefCell::new(vec![]);
+        let fail = RefCell::new(true);
+
+        let mut plan = DeploymentPlan::<Mock, CwOrchError>::new("app");
+        plan.upload("upload", &[], |_| {
+            runs.borrow_mut().push("upload");
+            Ok(())
+        })
+        .instantiate("instantiate", &["upload"], |_| {
+            runs.borrow_mut().push("instantiate");
+            if *fail.borrow() {
+                return Err(CwOrchError::StdErr("out of gas".to_string()));
+            }
+            Ok(())
+        })
+        .configure("configure", &["instantiate"], |_| {
+            runs.borrow_mut().push("configure");
+            Ok(())
+        });
+
+        assert!(plan.run(&chain).is_err());
+        let journal = chain.state().get_journal("app").unwrap();
+        assert!(journal["upload"].is_completed());
+        assert_eq!(journal["instantiate"].status, StepStatus::Failed);
+        assert_eq!(
+            journal["instantiate"].error.as_deref(),
+            Some("Generic Error out of gas")
+        );
+        assert!(!journal.contains_key("configure"));
+
+        *fail.borrow_mut() = false;
+        let report = plan.run(&chain).unwrap();
+        assert_eq!(report.skipped, vec!["upload"]);
+        assert_eq!(report.executed, vec!["instantiate", "configure"]);
+        assert_eq!(
+            *runs.borrow(),
+            vec!["upload", "instantiate", "instantiate", "configure"]
+        );
+        // the journal is cleared once the plan completed
+        assert!(chain.state().get_journal("app").unwrap().is_empty());
+
+        // a reset plan runs from the start
+        *fail.borrow_mut() = true;
+        assert!(plan.run(&chain).is_err());
+        plan.reset(&chain).unwrap();
+        *fail.borrow_mut() = false;
+        assert_eq!(plan.run(&chain).unwrap().executed.len(), 3);
+    }
+
+    #[test]
+    fn completed_plan_runs_again() {
+        let chain = Mock::new(&Addr::unchecked("sender"));
+        let mut plan = DeploymentPlan::<Mock, CwOrchError>::new("app");
+        plan.upload("upload", &[], |_| Ok(()))
+            .instantiate("instantiate", &["upload"], |_| Ok(()));
+
+        for _ in 0..2 {
+            let report = plan.run(&chain).unwrap();
+            assert_eq!(report.executed, vec!["upload", "instantiate"]);
+            assert!(report.skipped.is_empty());
+        }
+    }
+
+    #[test]
+    fn completed_step_reruns_if_its_state_is_gone() {
+        let chain = Mock::new(&Addr::unchecked("sender"));
+        let fail = RefCell::new(true);
+
+        let mut plan = DeploymentPlan::<Mock, CwOrchError>::new("app");
+        plan.upload("upload", &[], |chain| {
+            chain.state().set_code_id("token", 1);
+            Ok(())
+        })
+        .instantiate("instantiate", &["upload"], |chain| {
+            if *fail.borrow() {
+                return Err(CwOrchError::StdErr("out of gas".to_string()));
+            }
+            chain
+                .state()
+                .set_address("token", &Addr::unchecked("token"));
+            Ok(())
+        });
+
+        assert!(plan.run(&chain).is_err());
+        let journal = chain.state().get_journal("app").unwrap();
+        assert_eq!(journal["upload"].code_ids, vec!["token"]);
+
+        // the code id of the upload was removed, so the upload runs again
+        chain.state().borrow_mut().code_ids.remove("token");
+        *fail.borrow_mut() = false;
+        let report = plan.run(&chain).unwrap();
+        assert!(report.skipped.is_empty());
+        assert_eq!(report.executed, vec!["upload", "instantiate"]);
+        assert_eq!(chain.state().get_code_id("token").unwrap(), 1);
+    }
+}
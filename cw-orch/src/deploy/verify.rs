@@ -0,0 +1,482 @@
+//! Checks that the deployment in the state matches what is on chain, see [`Deploy::verify`](super::Deploy::verify).
+
+use std::collections::HashMap;
+
+use cosmwasm_std::Addr;
+use serde::Serialize;
+
+use crate::{
+    contract::Contract,
+    environment::{ContractInfo, CwEnv, WasmQuerier},
+    error::CwOrchError,
+    paths::WasmPath,
+    prelude::{ContractInstance, Uploadable},
+    state::{ChainState, StateInterface},
+};
+
+/// Contract checked by [`Deploy::verify`](super::Deploy::verify), with the checksum its code is expected to have.
+/// Implemented for every [`Uploadable`] contract, whose code is expected to match [`Uploadable::wasm`].
+pub trait VerifiableContract<Chain: WasmQuerier>: ContractInstance<Chain> {
+    /// Hex encoded checksum the code of the contract is expected to have on the chain,
+    /// [`CwOrchError::Unsupported`] if it isn't known.
+    fn expected_checksum(&self) -> Result<String, CwOrchError>;
+}
+
+impl<Chain: WasmQuerier, T: Uploadable + ContractInstance<Chain>> VerifiableContract<Chain> for T {
+    /// The [`WasmQuerier::local_checksum`] of the contract.
+    /// The `Mock` uploads wrappers without checksum, so the checksum check is skipped there.
+    fn expected_checksum(&self) -> Result<String, CwOrchError> {
+        self.get_chain().local_checksum(self)
+    }
+}
+
+/// Contract of [`Deploy::get_contracts_mut`](super::Deploy::get_contracts_mut), without expected checksum
+pub(crate) struct WithoutWasm<'a, Chain: CwEnv>(pub(crate) &'a mut dyn ContractInstance<Chain>);
+
+impl<Chain: WasmQuerier> ContractInstance<Chain> for WithoutWasm<'_, Chain> {
+    fn as_instance(&self) -> &Contract<Chain> {
+        self.0.as_instance()
+    }
+
+    fn as_instance_mut(&mut self) -> &mut Contract<Chain> {
+        self.0.as_instance_mut()
+    }
+}
+
+impl<Chain: WasmQuerier> VerifiableContract<Chain> for WithoutWasm<'_, Chain> {
+    fn expected_checksum(&self) -> Result<String, CwOrchError> {
+        Err(CwOrchError::Unsupported(
+            "no wasm file expected".to_string(),
+        ))
+    }
+}
+
+/// What the contracts of a deployment are expected to be, checks without expectations are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct VerifyOptions {
+    wasm: HashMap<String, WasmPath>,
+    admins: HashMap<String, Option<Addr>>,
+    strict: bool,
+}
+
+impl VerifyOptions {
+    /// Expect the code of the contract to match this wasm file instead of [`VerifiableContract::expected_checksum`]
+    pub fn wasm(&mut self, contract_id: impl Into<String>, wasm: WasmPath) -> &mut Self {
+        self.wasm.insert(contract_id.into(), wasm);
+        self
+    }
+
+    /// Fail the checks that can't be performed instead of skipping them
+    pub fn strict(&mut self, strict: bool) -> &mut Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Expect the contract to have this admin, `None` for no admin
+    pub fn admin(&mut self, contract_id: impl Into<String>, admin: Option<Addr>) -> &mut Self {
+        self.admins.insert(contract_id.into(), admin);
+        self
+    }
+}
+
+/// Check performed on every contract
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    /// The code id in the state exists on chain
+    CodeExists,
+    /// The on-chain code matches the local wasm file
+    Checksum,
+    /// The address in the state exists on chain
+    AddressExists,
+    /// The contract runs the code id in the state
+    CodeId,
+    /// The contract has the expected admin
+    Admin,
+}
+
+/// Outcome of a check
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "status", content = "reason")]
+pub enum CheckStatus {
+    /// The check passed
+    Passed,
+    /// The check failed
+    Failed(String),
+    /// The check could not be performed
+    Skipped(String),
+}
+
+/// Outcome of the checks of a single contract
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct ContractVerification {
+    /// Id of the contract
+    pub contract_id: String,
+    /// Code id in the state
+    pub code_id: Option<u64>,
+    /// Address in the state
+    pub address: Option<Addr>,
+    /// Outcome of every check, in the order they were performed
+    pub checks: Vec<Check>,
+}
+
+/// Outcome of a single check of a contract
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct Check {
+    /// What was checked
+    pub kind: CheckKind,
+    /// Outcome
+    #[serde(flatten)]
+    pub status: CheckStatus,
+}
+
+impl ContractVerification {
+    /// Outcome of a check
+    pub fn check(&self, kind: CheckKind) -> Option<&CheckStatus> {
+        self.checks
+            .iter()
+            .find(|check| check.kind == kind)
+            .map(|check| &check.status)
+    }
+
+    /// Whether no check failed
+    pub fn is_success(&self) -> bool {
+        !self
+            .checks
+            .iter()
+            .any(|check| matches!(check.status, CheckStatus::Failed(_)))
+    }
+}
+
+/// Outcome of the verification of a deployment.
+/// Serializes to JSON for CI reports.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct VerificationReport {
+    /// Chain the deployment was verified on
+    pub chain_id: String,
+    /// Deployment id that was verified
+    pub deployment_id: String,
+    /// Outcome per contract
+    pub contracts: Vec<ContractVerification>,
+}
+
+impl VerificationReport {
+    /// Whether no check of any contract failed
+    pub fn is_success(&self) -> bool {
+        self.contracts.iter().all(ContractVerification::is_success)
+    }
+
+    /// Failed checks, by contract id
+    pub fn failures(&self) -> Vec<(&str, CheckKind, &str)> {
+        self.contracts
+            .iter()
+            .flat_map(|contract| {
+                contract
+                    .checks
+                    .iter()
+                    .filter_map(move |check| match &check.status {
+                        CheckStatus::Failed(reason) => {
+                            Some((contract.contract_id.as_str(), check.kind, reason.as_str()))
+                        }
+                        _ => None,
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Run all checks on the contracts, which are expected to be deployed on the same chain
+pub(crate) fn verify<Chain: WasmQuerier>(
+    contracts: &[&dyn VerifiableContract<Chain>],
+    options: &VerifyOptions,
+) -> VerificationReport {
+    let details = contracts
+        .first()
+        .map(|contract| contract.get_chain().state().deploy_details());
+    VerificationReport {
+        chain_id: details
+            .as_ref()
+            .map(|details| details.chain_id.clone())
+            .unwrap_or_default(),
+        deployment_id: details
+            .map(|details| details.deployment_id)
+            .unwrap_or_default(),
+        contracts: contracts
+            .iter()
+            .map(|contract| verify_contract(*contract, options))
+            .collect(),
+    }
+}
+
+/// Run all checks on the contract
+fn verify_contract<Chain: WasmQuerier>(
+    contract: &dyn VerifiableContract<Chain>,
+    options: &VerifyOptions,
+) -> ContractVerification {
+    let chain = contract.get_chain();
+    let contract_id = contract.id();
+    let code_id = contract.code_id().ok();
+    let address = contract.address().ok();
+    let mut checks = vec![];
+    let mut push = |kind: CheckKind, status: CheckStatus| {
+        let status = match status {
+            CheckStatus::Skipped(reason) if options.strict => CheckStatus::Failed(reason),
+            status => status,
+        };
+        checks.push(Check { kind, status });
+    };
+
+    // the on-chain checksum, or why it is unavailable
+    let on_chain_checksum = match code_id {
+        None => {
+            push(CheckKind::CodeExists, failed("no code id in state"));
+            Err("no code id in state".to_string())
+        }
+        Some(code_id) => match chain.code_checksum(code_id) {
+            Ok(checksum) => {
+                push(CheckKind::CodeExists, CheckStatus::Passed);
+                Ok(checksum)
+            }
+            // the code exists, its checksum is unknown
+            Err(CwOrchError::Unsupported(reason)) => {
+                push(CheckKind::CodeExists, CheckStatus::Passed);
+                Err(format!("on-chain checksum unknown: {reason}"))
+            }
+            Err(e) => {
+                push(CheckKind::CodeExists, CheckStatus::Failed(e.to_string()));
+                Err("code not found".to_string())
+            }
+        },
+    };
+
+    let expected_checksum = match options.wasm.get(&contract_id) {
+        Some(wasm) => wasm.checksum(),
+        None => contract.expected_checksum(),
+    };
+    let checksum = match (expected_checksum, on_chain_checksum) {
+        (Err(CwOrchError::Unsupported(reason)), _) => CheckStatus::Skipped(reason),
+        (_, Err(reason)) => CheckStatus::Skipped(reason),
+        (Ok(local), Ok(on_chain)) if local.eq_ignore_ascii_case(&on_chain) => CheckStatus::Passed,
+        (Ok(local), Ok(on_chain)) => CheckStatus::Failed(format!(
+            "on-chain checksum {on_chain} doesn't match local checksum {local}"
+        )),
+        (Err(e), Ok(_)) => CheckStatus::Failed(format!("could not read the wasm file: {e}")),
+    };
+    push(CheckKind::Checksum, checksum);
+
+    let info: Option<ContractInfo> = match &address {
+        None => {
+            push(CheckKind::AddressExists, failed("no address in state"));
+            None
+        }
+        Some(address) => match chain.contract_info(address) {
+            Ok(info) => {
+                push(CheckKind::AddressExists, CheckStatus::Passed);
+                Some(info)
+            }
+            Err(e) => {
+                push(CheckKind::AddressExists, CheckStatus::Failed(e.to_string()));
+                None
+            }
+        },
+    };
+
+    let code_id_check = match (&info, code_id) {
+        (None, _) => skipped("contract not found"),
+        (_, None) => skipped("no code id in state"),
+        (Some(info), Some(code_id)) if info.code_id == code_id => CheckStatus::Passed,
+        (Some(info), Some(code_id)) => CheckStatus::Failed(format!(
+            "contract runs code id {} instead of {code_id}",
+            info.code_id
+        )),
+    };
+    push(CheckKind::CodeId, code_id_check);
+
+    let admin = match (options.admins.get(&contract_id), &info) {
+        (None, _) => skipped("no admin expected"),
+        (_, None) => skipped("contract not found"),
+        (Some(expected), Some(info)) if *expected == info.admin => CheckStatus::Passed,
+        (Some(expected), Some(info)) => CheckStatus::Failed(format!(
+            "admin is {} instead of {}",
+            display_admin(&info.admin),
+            display_admin(expected)
+        )),
+    };
+    push(CheckKind::Admin, admin);
+
+    ContractVerification {
+        contract_id,
+        code_id,
+        address,
+        checks,
+    }
+}
+
+fn failed(reason: &str) -> CheckStatus {
+    CheckStatus::Failed(reason.to_string())
+}
+
+fn skipped(reason: &str) -> CheckStatus {
+    CheckStatus::Skipped(reason.to_string())
+}
+
+fn display_admin(admin: &Option<Addr>) -> &str {
+    admin.as_ref().map_or("none", Addr::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contract::Contract,
+        mock::Mock,
+        prelude::*,
+        state::{DeploymentAction, DeploymentRecord},
+    };
+    use cw20_base::contract::{execute, instantiate, query};
+
+    struct Token(Contract<Mock>);
+
+    impl ContractInstance<Mock> for Token {
+        fn as_instance(&self) -> &Contract<Mock> {
+            &self.0
+        }
+
+        fn as_instance_mut(&mut self) -> &mut Contract<Mock> {
+            &mut self.0
+        }
+    }
+
+    impl Uploadable for Token {}
+
+    #[test]
+    fn reports_every_check() {
+        let sender = Addr::unchecked("sender");
+        let chain = Mock::new(&sender);
+        chain
+            .upload_custom(
+                "token",
+                Box::new(ContractWrapper::new(execute, instantiate, query)),
+            )
+            .unwrap();
+        let init_msg = cw20_base::msg::InstantiateMsg {
+            name: String::from("Token"),
+            symbol: String::from("TOK"),
+            decimals: 6u8,
+            initial_balances: vec![],
+            mint: None,
+            marketing: None,
+        };
+        let res = chain
+            .instantiate(1, &init_msg, None, Some(&sender), &[])
+            .unwrap();
+        let token_contract = Token(Contract::new("token", chain.clone()));
+        token_contract.set_address(&res.instantiated_contract_address().unwrap());
+
+        let missing = Token(Contract::new("missing", chain.clone()));
+        missing.set_address(&Addr::unchecked("unknown"));
+
+        let mut options = VerifyOptions::default();
+        options
+            .admin("token", Some(sender.clone()))
+            .admin("missing", None);
+        let report = verify::<Mock>(&[&token_contract, &missing], &options);
+
+        assert!(!report.is_success());
+        let token = &report.contracts[0];
+        assert!(token.is_success());
+        assert_eq!(token.check(CheckKind::CodeId), Some(&CheckStatus::Passed));
+        assert_eq!(token.check(CheckKind::Admin), Some(&CheckStatus::Passed));
+        // the mock doesn't know the checksum of the wrapper
+        assert!(matches!(
+            token.check(CheckKind::Checksum),
+            Some(CheckStatus::Skipped(_))
+        ));
+
+        let failures = report.failures();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(
+            failures[0],
+            ("missing", CheckKind::CodeExists, "no code id in state")
+        );
+        assert_eq!(failures[1].0, "missing");
+        assert_eq!(failures[1].1, CheckKind::AddressExists);
+
+        let json = serde_json::to_value(&report.contracts[1].checks[0]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "code_exists",
+                "status": "failed",
+                "reason": "no code id in state"
+            })
+        );
+
+        // skipped checks fail in strict mode
+        options.strict(true);
+        let report = verify::<Mock>(&[&token_contract], &options);
+        assert!(!report.is_success());
+        assert!(matches!(
+            report.contracts[0].check(CheckKind::Checksum),
+            Some(CheckStatus::Failed(_))
+        ));
+    }
+
+    #[test]
+    fn checksum_is_compared_to_recorded_code() {
+        let sender = Addr::unchecked("sender");
+        let chain = Mock::new(&sender);
+        chain
+            .upload_custom(
+                "token",
+                Box::new(ContractWrapper::new(execute, instantiate, query)),
+            )
+            .unwrap();
+        let token = Token(Contract::new("token", chain.clone()));
+
+        let dir = std::env::temp_dir().join("cw-orch-verify-checksum");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("token.wasm"), b"token").unwrap();
+        let wasm = WasmPath::new(dir.join("token.wasm")).unwrap();
+        let mut options = VerifyOptions::default();
+        options.wasm("token", wasm.clone());
+
+        // the mock doesn't know the checksum of the code
+        let report = verify::<Mock>(&[&token], &options);
+        let contract = &report.contracts[0];
+        assert_eq!(
+            contract.check(CheckKind::CodeExists),
+            Some(&CheckStatus::Passed)
+        );
+        assert!(matches!(
+            contract.check(CheckKind::Checksum),
+            Some(CheckStatus::Skipped(_))
+        ));
+
+        // the checksum recorded at upload is compared to the wasm file
+        let record = |checksum: String| DeploymentRecord {
+            code_id: Some(1),
+            checksum: Some(checksum),
+            ..DeploymentRecord::new(DeploymentAction::Upload)
+        };
+        chain
+            .state()
+            .try_record_deployment("token", record(wasm.checksum().unwrap()))
+            .unwrap();
+        let report = verify::<Mock>(&[&token], &options);
+        assert_eq!(
+            report.contracts[0].check(CheckKind::Checksum),
+            Some(&CheckStatus::Passed)
+        );
+
+        chain
+            .state()
+            .try_record_deployment("token", record("00".repeat(32)))
+            .unwrap();
+        let report = verify::<Mock>(&[&token], &options);
+        assert!(matches!(
+            report.contracts[0].check(CheckKind::Checksum),
+            Some(CheckStatus::Failed(_))
+        ));
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::{
     error::CwOrchError,
-    prelude::{IndexResponse, Uploadable},
+    prelude::{ContractInstance, IndexResponse, Uploadable},
     state::ChainState,
 };
 use cosmwasm_std::{Addr, BlockInfo, Coin};
@@ -46,6 +46,12 @@ pub trait TxHandler: ChainState + Clone {
     /// Uploads a contract to the chain.
     fn upload(&self, contract_source: &impl Uploadable) -> Result<Self::Response, Self::Error>;
 
+    /// Checksum of the code [`upload`](TxHandler::upload) stores for the contract,
+    /// `None` if the environment doesn't upload wasm files.
+    fn upload_checksum(&self, _contract_source: &impl Uploadable) -> Option<String> {
+        None
+    }
+
     /// Send a InstantiateMsg to a contract.
     fn instantiate<I: Serialize + Debug>(
         &self,
@@ -78,3 +84,41 @@ pub trait TxHandler: ChainState + Clone {
         contract_address: &Addr,
     ) -> Result<Self::Response, Self::Error>;
 }
+
+/// Queries of the code and contracts stored on an environment.
+pub trait WasmQuerier: CwEnv {
+    /// Hex encoded checksum of the code stored under the code id.
+    /// Errors if there is no such code, or with [`CwOrchError::Unsupported`] if the code exists but its checksum isn't known.
+    fn code_checksum(&self, code_id: u64) -> Result<String, CwOrchError>;
+
+    /// Info of the contract at the address.
+    /// Errors if there is no such contract.
+    fn contract_info(&self, address: &Addr) -> Result<ContractInfo, CwOrchError>;
+
+    /// Most recent code id with the hex encoded checksum, optionally restricted to codes uploaded by `uploader`.
+    fn find_code(
+        &self,
+        checksum: &str,
+        uploader: Option<&Addr>,
+    ) -> Result<Option<u64>, CwOrchError>;
+
+    /// Hex encoded checksum the code of the contract has once uploaded to this environment.
+    /// Defaults to the checksum of its wasm file.
+    fn local_checksum<T: Uploadable + ContractInstance<Self>>(
+        &self,
+        contract: &T,
+    ) -> Result<String, CwOrchError> {
+        contract.wasm().checksum()
+    }
+}
+
+/// Information about an instantiated contract
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractInfo {
+    /// Code id the contract runs
+    pub code_id: u64,
+    /// Address that instantiated the contract
+    pub creator: Addr,
+    /// Address that can migrate the contract, if any
+    pub admin: Option<Addr>,
+}
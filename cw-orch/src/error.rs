@@ -22,6 +22,10 @@ pub enum CwOrchError {
     AnyError(#[from] ::anyhow::Error),
     #[error(transparent)]
     IOErr(#[from] ::std::io::Error),
+    #[error(transparent)]
+    StateFile(#[from] crate::state_file::StateFileError),
+    #[error(transparent)]
+    Artifact(#[from] crate::paths::ArtifactError),
     #[error("Contract address for {0} not found in store")]
     AddrNotInStore(String),
     #[error("Code id for {0} not found in store")]
@@ -30,6 +34,14 @@ pub enum CwOrchError {
     NotImplemented,
     #[error("Generic Error {0}")]
     StdErr(String),
+    #[error("not supported by this environment: {0}")]
+    Unsupported(String),
+    #[error("deployment step {0} is declared twice")]
+    DuplicateStep(String),
+    #[error("deployment step {step} depends on unknown step {dependency}")]
+    UnknownStepDependency { step: String, dependency: String },
+    #[error("deployment steps {} depend on each other", .0.join(", "))]
+    CyclicStepDependencies(Vec<String>),
 }
 
 impl CwOrchError {
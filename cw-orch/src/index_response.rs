@@ -4,6 +4,7 @@ use cw_multi_test::AppResponse;
 use snailquote::unescape;
 
 const CODE_ID_UPLOAD_EVENT: (&str, &str) = ("store_code", "code_id");
+const CHECKSUM_UPLOAD_EVENT: (&str, &str) = ("store_code", "code_checksum");
 const ADDRESS_INSTANTIATE_EVENT: (&str, &str) = ("instantiate", "_contract_address");
 
 #[cfg(feature = "eth")]
@@ -26,6 +27,17 @@ pub trait IndexResponse {
     /// Get the data field of the response.
     fn data(&self) -> Option<Binary>;
 
+    /// Hash of the transaction, if the environment has transactions.
+    fn tx_hash(&self) -> Option<String> {
+        None
+    }
+
+    /// Shortcut to get the wasm checksum of an upload response, if reported by the chain.
+    fn uploaded_checksum(&self) -> Option<String> {
+        self.event_attr_value(CHECKSUM_UPLOAD_EVENT.0, CHECKSUM_UPLOAD_EVENT.1)
+            .ok()
+    }
+
     /// Helper to get the contract address of a instantiate response.
     fn instantiated_contract_address(&self) -> StdResult<Addr> {
         if let Ok(code_id) = self
@@ -1,7 +1,9 @@
 use crate::{
     contract::Contract,
+    environment::{TxResponse, WasmQuerier},
     error::CwOrchError,
     prelude::{CwEnv, WasmPath},
+    state::DeploymentRecord,
 };
 use cosmwasm_std::{Addr, Coin, Empty};
 use cw_multi_test::Contract as MockContract;
@@ -61,6 +63,16 @@ pub trait ContractInstance<Chain: CwEnv> {
         Contract::set_default_code_id(self.as_instance_mut(), code_id)
     }
 
+    /// Returns the deployment history of the contract (uploads, instantiations, migrations), oldest first.
+    fn history(&self) -> Result<Vec<DeploymentRecord>, CwOrchError> {
+        Contract::history(self.as_instance())
+    }
+
+    /// Restores the code id and address of the entry at `index` of the contract's history.
+    fn rollback(&self, index: usize) -> Result<DeploymentRecord, CwOrchError> {
+        Contract::rollback(self.as_instance(), index)
+    }
+
     /// Returns the chain that this contract is deployed on.
     fn get_chain(&self) -> &Chain {
         Contract::get_chain(self.as_instance())
@@ -164,6 +176,25 @@ pub trait Uploadable {
     fn wrapper(&self) -> Box<dyn MockContract<Empty, Empty>> {
         unimplemented!("no wrapper function implemented for this contract")
     }
+
+    /// Who can instantiate the code once uploaded, `None` for the chain default.
+    /// Applied by the environments that upload wasm files, the `Mock` ignores it.
+    fn instantiate_permission(&self) -> Option<AccessConfig> {
+        None
+    }
+}
+
+/// Who can instantiate an uploaded code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccessConfig {
+    /// Nobody, only governance can instantiate the code
+    Nobody,
+    /// A single address, deprecated since wasmd v0.31 in favor of [`AccessConfig::AnyOfAddresses`]
+    OnlyAddress(Addr),
+    /// Any of the addresses
+    AnyOfAddresses(Vec<Addr>),
+    /// Everybody
+    Everybody,
 }
 
 /// Trait that indicates that the contract can be uploaded.
@@ -191,3 +222,129 @@ pub trait CallAs<Chain: CwEnv>: CwOrchExecute<Chain> + ContractInstance<Chain> +
     /// Clones the contract interface with a different sender.
     fn call_as(&self, sender: &Self::Sender) -> Self;
 }
+
+/// Helper methods for conditional uploading of a contract.
+pub trait ConditionalUpload<Chain: WasmQuerier>: CwOrchUpload<Chain> {
+    /// Only upload the contract if it is not uploaded yet (checksum does not match).
+    /// The `Mock` can't checksum its wrappers, so it only uploads if there is no code id in the state.
+    fn upload_if_needed(&self) -> Result<Option<TxResponse<Chain>>, CwOrchError> {
+        match self.latest_is_uploaded() {
+            Ok(true) => Ok(None),
+            Ok(false) | Err(CwOrchError::Unsupported(_)) => Some(self.upload()).transpose(),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Upload the contract, or with a reuse [`UploadMode`], register the most recent code on chain with the same checksum instead.
+    /// Returns `None` if existing code was registered.
+    /// Always uploads on environments that don't know checksums, like the `Mock`.
+    fn upload_with_mode(&self, mode: UploadMode) -> Result<Option<TxResponse<Chain>>, CwOrchError> {
+        let chain = self.get_chain();
+        let uploader = match mode {
+            UploadMode::Always => return Some(self.upload()).transpose(),
+            UploadMode::ReuseAny => None,
+            UploadMode::ReuseOwn => Some(chain.sender()),
+        };
+        let checksum = match chain.local_checksum(self) {
+            Ok(checksum) => checksum,
+            Err(CwOrchError::Unsupported(_)) => return Some(self.upload()).transpose(),
+            Err(e) => return Err(e),
+        };
+        match chain.find_code(&checksum, uploader.as_ref())? {
+            Some(code_id) => {
+                self.as_instance().reuse_code(code_id, checksum)?;
+                Ok(None)
+            }
+            None => Some(self.upload()).transpose(),
+        }
+    }
+
+    /// Returns whether the checksum of the WASM file matches the checksum of the latest uploaded code for this contract.
+    /// If the environment can't checksum the local code, like the `Mock`, any uploaded code is considered the latest.
+    fn latest_is_uploaded(&self) -> Result<bool, CwOrchError> {
+        let Some(latest_uploaded_code_id) = self.code_id().ok() else {
+            return Ok(false);
+        };
+
+        let chain = self.get_chain();
+        let local_hash = match chain.local_checksum(self) {
+            Ok(local_hash) => local_hash,
+            Err(CwOrchError::Unsupported(_)) => return Ok(true),
+            Err(e) => return Err(e),
+        };
+        let on_chain_hash = chain.code_checksum(latest_uploaded_code_id)?;
+
+        Ok(local_hash == on_chain_hash)
+    }
+
+    /// Returns whether the contract is running the latest uploaded code for it
+    fn is_running_latest(&self) -> Result<bool, CwOrchError> {
+        let Some(latest_uploaded_code_id) = self.code_id().ok() else {
+            return Ok(false);
+        };
+        let info = self.get_chain().contract_info(&self.address()?)?;
+        Ok(latest_uploaded_code_id == info.code_id)
+    }
+}
+
+impl<T, Chain: WasmQuerier> ConditionalUpload<Chain> for T where T: CwOrchUpload<Chain> {}
+
+/// Whether [`ConditionalUpload::upload_with_mode`] may reuse code that is already on chain
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UploadMode {
+    /// Always upload the code
+    #[default]
+    Always,
+    /// Reuse code with the same checksum, uploaded by anyone
+    ReuseAny,
+    /// Reuse code with the same checksum, uploaded by the sender.
+    /// Code stored through a governance proposal is uploaded by the gov module and never matches,
+    /// use [`UploadMode::ReuseAny`] or [`Contract::reuse_code`] for it.
+    ReuseOwn,
+}
+
+/// Helper methods for conditional migration of a contract.
+pub trait ConditionalMigrate<Chain: WasmQuerier>:
+    CwOrchMigrate<Chain> + ConditionalUpload<Chain>
+{
+    /// Only migrate the contract if it is not on the latest code-id yet
+    fn migrate_if_needed(
+        &self,
+        migrate_msg: &Self::MigrateMsg,
+    ) -> Result<Option<TxResponse<Chain>>, CwOrchError> {
+        if self.is_running_latest()? {
+            log::info!("{} is already running the latest code", self.id());
+            Ok(None)
+        } else {
+            Some(self.migrate(migrate_msg, self.code_id()?)).transpose()
+        }
+    }
+
+    /// Uploads the contract if the local contract hash is different from the latest on-chain code hash.
+    /// Proceeds to migrates the contract if the contract is not running the latest code.
+    fn upload_and_migrate_if_needed(
+        &self,
+        migrate_msg: &Self::MigrateMsg,
+    ) -> Result<Option<Vec<TxResponse<Chain>>>, CwOrchError> {
+        let mut txs = Vec::with_capacity(2);
+
+        if let Some(tx) = self.upload_if_needed()? {
+            txs.push(tx);
+        };
+
+        if let Some(tx) = self.migrate_if_needed(migrate_msg)? {
+            txs.push(tx);
+        };
+
+        if txs.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(txs))
+        }
+    }
+}
+
+impl<T, Chain: WasmQuerier> ConditionalMigrate<Chain> for T where
+    T: CwOrchMigrate<Chain> + CwOrchUpload<Chain>
+{
+}
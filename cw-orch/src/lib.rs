@@ -16,6 +16,8 @@ pub extern crate tokio;
 // prelude
 pub mod prelude;
 
+#[cfg(any(feature = "daemon", feature = "osmosis-test-tube"))]
+mod code_search;
 pub mod contract;
 #[cfg(feature = "daemon")]
 pub mod daemon;
@@ -33,6 +35,7 @@ pub mod osmosis_test_tube;
 mod paths;
 
 pub mod state;
+pub mod state_file;
 
 #[cfg(feature = "daemon")]
 pub mod live_mock;
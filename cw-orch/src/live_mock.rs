@@ -11,13 +11,13 @@ use cosmwasm_std::BalanceResponse;
 use cosmwasm_std::Delegation;
 use cosmwasm_std::{AllDelegationsResponse, BondedDenomResponse};
 
+use crate::daemon::DaemonChannel;
 use cosmwasm_std::BankQuery;
 use cosmwasm_std::Binary;
 use cosmwasm_std::Empty;
 use cosmwasm_std::StakingQuery;
 use ibc_chain_registry::chain::ChainData;
 use tokio::runtime::Runtime;
-use tonic::transport::Channel;
 
 use std::marker::PhantomData;
 use std::str::FromStr;
@@ -57,7 +57,7 @@ pub fn mock_dependencies(
 
 /// Querier struct that fetches queries on-chain directly
 pub struct WasmMockQuerier {
-    channel: Channel,
+    channel: DaemonChannel,
     runtime: Runtime,
 }
 
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, fmt::Debug, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, rc::Rc};
 
 use cosmwasm_std::{Addr, Empty, Event, Uint128};
 use cw_multi_test::{custom_app, next_block, AppResponse, BasicApp, Contract, Executor};
@@ -6,10 +6,12 @@ use cw_utils::NativeBalance;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
-    environment::TxHandler,
+    environment::{ContractInfo, TxHandler, WasmQuerier},
     error::CwOrchError,
     prelude::*,
-    state::{ChainState, DeployDetails, StateInterface},
+    state::{
+        ChainState, DeployDetails, DeploymentAction, DeploymentRecord, StateInterface, StepRecord,
+    },
 };
 
 use super::state::MockState;
@@ -183,6 +185,10 @@ impl<S: StateInterface> StateInterface for Rc<RefCell<S>> {
         self.borrow_mut().set_address(contract_id, address)
     }
 
+    fn try_set_address(&mut self, contract_id: &str, address: &Addr) -> Result<(), CwOrchError> {
+        self.borrow_mut().try_set_address(contract_id, address)
+    }
+
     fn get_code_id(&self, contract_id: &str) -> Result<u64, CwOrchError> {
         self.borrow().get_code_id(contract_id)
     }
@@ -191,17 +197,50 @@ impl<S: StateInterface> StateInterface for Rc<RefCell<S>> {
         self.borrow_mut().set_code_id(contract_id, code_id)
     }
 
-    fn get_all_addresses(&self) -> Result<std::collections::HashMap<String, Addr>, CwOrchError> {
+    fn try_set_code_id(&mut self, contract_id: &str, code_id: u64) -> Result<(), CwOrchError> {
+        self.borrow_mut().try_set_code_id(contract_id, code_id)
+    }
+
+    fn get_all_addresses(&self) -> Result<HashMap<String, Addr>, CwOrchError> {
         self.borrow().get_all_addresses()
     }
 
-    fn get_all_code_ids(&self) -> Result<std::collections::HashMap<String, u64>, CwOrchError> {
+    fn get_all_code_ids(&self) -> Result<HashMap<String, u64>, CwOrchError> {
         self.borrow().get_all_code_ids()
     }
 
     fn deploy_details(&self) -> DeployDetails {
         self.borrow().deploy_details()
     }
+
+    fn get_history(&self, contract_id: &str) -> Result<Vec<DeploymentRecord>, CwOrchError> {
+        self.borrow().get_history(contract_id)
+    }
+
+    fn try_record_deployment(
+        &mut self,
+        contract_id: &str,
+        record: DeploymentRecord,
+    ) -> Result<(), CwOrchError> {
+        self.borrow_mut().try_record_deployment(contract_id, record)
+    }
+
+    fn get_journal(&self, plan: &str) -> Result<HashMap<String, StepRecord>, CwOrchError> {
+        self.borrow().get_journal(plan)
+    }
+
+    fn try_record_step(
+        &mut self,
+        plan: &str,
+        step: &str,
+        record: StepRecord,
+    ) -> Result<(), CwOrchError> {
+        self.borrow_mut().try_record_step(plan, step, record)
+    }
+
+    fn try_clear_journal(&mut self, plan: &str) -> Result<(), CwOrchError> {
+        self.borrow_mut().try_clear_journal(plan)
+    }
 }
 
 // Execute on the test chain, returns test response type
@@ -324,6 +363,84 @@ impl<S: StateInterface> TxHandler for Mock<S> {
     }
 }
 
+impl<S: StateInterface> WasmQuerier for Mock<S> {
+    /// The mock doesn't store wasm code, so codes are looked up in the state:
+    /// the checksum is the one recorded in the deployment history of the contracts registered with the code id.
+    fn code_checksum(&self, code_id: u64) -> Result<String, CwOrchError> {
+        let state = self.state.borrow();
+        let contract_ids: Vec<String> = state
+            .get_all_code_ids()?
+            .into_iter()
+            .filter(|(_, id)| *id == code_id)
+            .map(|(contract_id, _)| contract_id)
+            .collect();
+        if contract_ids.is_empty() {
+            return Err(CwOrchError::StdErr(format!("code id {code_id} not found")));
+        }
+        for contract_id in contract_ids {
+            let recorded = state
+                .get_history(&contract_id)?
+                .into_iter()
+                .rev()
+                .find(|record| record.code_id == Some(code_id) && record.checksum.is_some());
+            if let Some(checksum) = recorded.and_then(|record| record.checksum) {
+                return Ok(checksum);
+            }
+        }
+        Err(CwOrchError::Unsupported(format!(
+            "no checksum was recorded for code id {code_id}"
+        )))
+    }
+
+    fn contract_info(&self, address: &Addr) -> Result<ContractInfo, CwOrchError> {
+        let info = self.app.borrow().wrap().query_wasm_contract_info(address)?;
+        Ok(ContractInfo {
+            code_id: info.code_id,
+            creator: Addr::unchecked(info.creator),
+            admin: info.admin.map(Addr::unchecked),
+        })
+    }
+
+    /// Codes are looked up in the deployment history of the contracts in the state,
+    /// only uploads recorded with a checksum can be found.
+    fn find_code(
+        &self,
+        checksum: &str,
+        uploader: Option<&Addr>,
+    ) -> Result<Option<u64>, CwOrchError> {
+        let state = self.state.borrow();
+        let mut found = None;
+        for contract_id in state.get_all_code_ids()?.keys() {
+            for record in state.get_history(contract_id)? {
+                let uploaded = match uploader {
+                    Some(uploader) => {
+                        record.action == DeploymentAction::Upload
+                            && record.sender.as_deref() == Some(uploader.as_str())
+                    }
+                    None => matches!(
+                        record.action,
+                        DeploymentAction::Upload | DeploymentAction::Reuse
+                    ),
+                };
+                if uploaded && record.checksum.as_deref() == Some(checksum) {
+                    found = found.max(record.code_id);
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// The mock uploads contract wrappers, not wasm files, so the checksum of the code isn't known.
+    fn local_checksum<T: Uploadable + ContractInstance<Self>>(
+        &self,
+        _contract: &T,
+    ) -> Result<String, CwOrchError> {
+        Err(CwOrchError::Unsupported(
+            "the mock uploads contract wrappers, which have no checksum".to_string(),
+        ))
+    }
+}
+
 impl<T: CwOrchExecute<Mock> + ContractInstance<Mock> + Clone> CallAs<Mock> for T {
     type Sender = Addr;
 
@@ -348,7 +465,7 @@ mod test {
     use serde::Serialize;
     use speculoos::prelude::*;
 
-    use crate::mock::core::*;
+    use crate::{contract::Contract as CwOrchContract, mock::core::*};
 
     const SENDER: &str = "cosmos123";
     const BALANCE_ADDR: &str = "cosmos456";
@@ -532,4 +649,65 @@ mod test {
             .that(&balances)
             .contains_all_of(&[&Coin::new(amount, denom_1), &Coin::new(amount, denom_2)])
     }
+    struct Token(CwOrchContract<Mock>);
+
+    impl ContractInstance<Mock> for Token {
+        fn as_instance(&self) -> &CwOrchContract<Mock> {
+            &self.0
+        }
+
+        fn as_instance_mut(&mut self) -> &mut CwOrchContract<Mock> {
+            &mut self.0
+        }
+    }
+
+    impl Uploadable for Token {
+        fn wrapper(&self) -> Box<dyn Contract<Empty, Empty>> {
+            Box::new(ContractWrapper::new(
+                execute,
+                cw20_base::contract::instantiate,
+                query,
+            ))
+        }
+    }
+
+    #[test]
+    fn conditional_upload() {
+        let chain = Mock::new(&Addr::unchecked(SENDER));
+        let token = Token(CwOrchContract::new("token", chain.clone()));
+
+        assert!(!token.latest_is_uploaded().unwrap());
+        assert!(token.upload_if_needed().unwrap().is_some());
+        assert_eq!(token.code_id().unwrap(), 1);
+
+        // the mock can't compare checksums, so the uploaded code is considered the latest
+        assert!(token.latest_is_uploaded().unwrap());
+        assert!(token.upload_if_needed().unwrap().is_none());
+        assert_eq!(token.code_id().unwrap(), 1);
+        // nor find code with the same checksum, so it is uploaded again
+        assert!(token
+            .upload_with_mode(UploadMode::ReuseAny)
+            .unwrap()
+            .is_some());
+        assert_eq!(token.code_id().unwrap(), 2);
+    }
+
+    #[test]
+    fn finds_code_with_recorded_checksum() {
+        let chain = Mock::new(&Addr::unchecked(SENDER));
+        let token = Token(CwOrchContract::new("token", chain.clone()));
+        token.upload().unwrap();
+        assert_eq!(chain.find_code("abcd", None).unwrap(), None);
+
+        token.as_instance().reuse_code(1, "abcd").unwrap();
+        assert_eq!(chain.code_checksum(1).unwrap(), "abcd");
+        assert_eq!(chain.find_code("abcd", None).unwrap(), Some(1));
+        // reused code wasn't uploaded by the sender
+        assert_eq!(
+            chain
+                .find_code("abcd", Some(&Addr::unchecked(SENDER)))
+                .unwrap(),
+            None
+        );
+    }
 }
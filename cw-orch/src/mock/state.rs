@@ -1,6 +1,6 @@
 use crate::{
     error::CwOrchError,
-    state::{DeployDetails, StateInterface},
+    state::{DeployDetails, DeploymentRecord, StateInterface, StepKind, StepRecord, StepStatus},
 };
 use cosmwasm_std::{testing::mock_env, Addr};
 
@@ -13,6 +13,10 @@ pub struct MockState {
     pub code_ids: HashMap<String, u64>,
     /// Deployed contract addresses
     pub addresses: HashMap<String, Addr>,
+    /// Deployment history of the contracts
+    pub history: HashMap<String, Vec<DeploymentRecord>>,
+    /// Journals of the deployment plans, by plan, deployment id and step id
+    pub journal: HashMap<String, HashMap<String, HashMap<String, StepRecord>>>,
 }
 
 impl MockState {
@@ -21,6 +25,8 @@ impl MockState {
         Self {
             addresses: HashMap::new(),
             code_ids: HashMap::new(),
+            history: HashMap::new(),
+            journal: HashMap::new(),
         }
     }
 }
@@ -74,11 +80,66 @@ impl StateInterface for MockState {
             deployment_id: "default".to_string(),
         }
     }
+
+    fn get_history(&self, contract_id: &str) -> Result<Vec<DeploymentRecord>, CwOrchError> {
+        Ok(self.history.get(contract_id).cloned().unwrap_or_default())
+    }
+
+    fn try_record_deployment(
+        &mut self,
+        contract_id: &str,
+        record: DeploymentRecord,
+    ) -> Result<(), CwOrchError> {
+        self.history
+            .entry(contract_id.to_string())
+            .or_default()
+            .push(record);
+        Ok(())
+    }
+
+    fn get_journal(&self, plan: &str) -> Result<HashMap<String, StepRecord>, CwOrchError> {
+        let deployment_id = self.deploy_details().deployment_id;
+        Ok(self
+            .journal
+            .get(plan)
+            .and_then(|journals| journals.get(&deployment_id))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn try_record_step(
+        &mut self,
+        plan: &str,
+        step: &str,
+        record: StepRecord,
+    ) -> Result<(), CwOrchError> {
+        let deployment_id = self.deploy_details().deployment_id;
+        self.journal
+            .entry(plan.to_string())
+            .or_default()
+            .entry(deployment_id)
+            .or_default()
+            .insert(step.to_string(), record);
+        Ok(())
+    }
+
+    fn try_clear_journal(&mut self, plan: &str) -> Result<(), CwOrchError> {
+        let deployment_id = self.deploy_details().deployment_id;
+        if let Some(journals) = self.journal.get_mut(plan) {
+            journals.remove(&deployment_id);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{prelude::CwOrchError, state::StateInterface};
+    use crate::{
+        prelude::CwOrchError,
+        state::{
+            DeploymentAction, DeploymentRecord, StateInterface, StepKind, StepRecord, StepStatus,
+        },
+    };
     use cosmwasm_std::Addr;
     use speculoos::prelude::*;
 
@@ -136,4 +197,69 @@ mod test {
             .that(&total)
             .is_equal_to(1)
     }
+
+    #[test]
+    fn history_rollback() {
+        let mut mock = MockState::default();
+        for code_id in [1, 2] {
+            mock.set_code_id(CONTRACT_ID, code_id);
+            mock.set_address(CONTRACT_ID, &Addr::unchecked(format!("cosmos{code_id}")));
+            mock.try_record_deployment(
+                CONTRACT_ID,
+                DeploymentRecord {
+                    code_id: Some(code_id),
+                    address: Some(format!("cosmos{code_id}")),
+                    ..DeploymentRecord::new(DeploymentAction::Migrate)
+                },
+            )
+            .unwrap();
+        }
+
+        let restored = mock.rollback(CONTRACT_ID, 0).unwrap();
+
+        assert_eq!(restored.code_id, Some(1));
+        assert_eq!(mock.get_code_id(CONTRACT_ID).unwrap(), 1);
+        assert_eq!(
+            mock.get_address(CONTRACT_ID).unwrap(),
+            Addr::unchecked("cosmos1")
+        );
+
+        // the rollback is recorded, earlier entries are untouched
+        let history = mock.get_history(CONTRACT_ID).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[2].action, DeploymentAction::Rollback);
+        assert_eq!(history[2].code_id, Some(1));
+
+        assert!(mock.rollback(CONTRACT_ID, 5).is_err());
+    }
+
+    #[test]
+    fn journal_is_kept_per_deployment_id() {
+        let mut mock = MockState::default();
+        mock.try_record_step(
+            "app",
+            "upload",
+            StepRecord {
+                kind: StepKind::Upload,
+                status: StepStatus::Completed,
+                error: None,
+                timestamp: 0,
+                code_ids: vec![],
+                addresses: vec![],
+            },
+        )
+        .unwrap();
+        let journal = mock.get_journal("app").unwrap();
+        mock.journal
+            .get_mut("app")
+            .unwrap()
+            .insert("other".to_string(), journal);
+
+        assert!(mock.journal["app"]["default"]["upload"].is_completed());
+
+        mock.try_clear_journal("app").unwrap();
+        assert!(mock.get_journal("app").unwrap().is_empty());
+        // the journal of the other deployment id is untouched
+        assert!(mock.journal["app"]["other"]["upload"].is_completed());
+    }
 }
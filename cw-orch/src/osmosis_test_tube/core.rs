@@ -1,3 +1,4 @@
+use crate::interface_traits::AccessConfig;
 use crate::interface_traits::CallAs;
 use crate::interface_traits::ContractInstance;
 use crate::interface_traits::CwOrchExecute;
@@ -11,10 +12,16 @@ use cosmwasm_std::Uint128;
 use cw_multi_test::AppResponse;
 use osmosis_test_tube::osmosis_std::cosmwasm_to_proto_coins;
 use osmosis_test_tube::osmosis_std::types::cosmos::bank::v1beta1::MsgSend;
+use osmosis_test_tube::osmosis_std::types::cosmos::base::query::v1beta1::PageRequest;
+use osmosis_test_tube::osmosis_std::types::cosmwasm::wasm::v1::{
+    AccessConfig as ProtoAccessConfig, AccessType, QueryCodeRequest, QueryCodeResponse,
+    QueryCodesRequest, QueryCodesResponse, QueryContractInfoRequest, QueryContractInfoResponse,
+};
 use osmosis_test_tube::Account;
 use osmosis_test_tube::Bank;
 use osmosis_test_tube::Gamm;
 use osmosis_test_tube::Module;
+use osmosis_test_tube::Runner;
 use osmosis_test_tube::SigningAccount;
 use osmosis_test_tube::Wasm;
 use std::str::FromStr;
@@ -29,7 +36,8 @@ use cosmwasm_std::Addr;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
-    environment::TxHandler,
+    code_search::{CodeSearch, CODES_PAGE_SIZE},
+    environment::{ContractInfo, TxHandler, WasmQuerier},
     error::CwOrchError,
     state::{ChainState, StateInterface},
 };
@@ -198,6 +206,27 @@ impl<S: StateInterface> ChainState for OsmosisTestTube<S> {
     }
 }
 
+/// Instantiate permission in the layout of the test tube's wasmd
+fn access_config(config: &AccessConfig) -> ProtoAccessConfig {
+    let (permission, address, addresses) = match config {
+        AccessConfig::Nobody => (AccessType::Nobody, String::new(), vec![]),
+        AccessConfig::OnlyAddress(address) => {
+            (AccessType::OnlyAddress, address.to_string(), vec![])
+        }
+        AccessConfig::AnyOfAddresses(addresses) => (
+            AccessType::AnyOfAddresses,
+            String::new(),
+            addresses.iter().map(ToString::to_string).collect(),
+        ),
+        AccessConfig::Everybody => (AccessType::Everybody, String::new(), vec![]),
+    };
+    ProtoAccessConfig {
+        permission: permission as i32,
+        address,
+        addresses,
+    }
+}
+
 // Execute on the test chain, returns test response type
 impl<S: StateInterface> TxHandler for OsmosisTestTube<S> {
     type Error = CwOrchError;
@@ -209,10 +238,15 @@ impl<S: StateInterface> TxHandler for OsmosisTestTube<S> {
     }
 
     fn upload(&self, contract: &impl Uploadable) -> Result<Self::Response, CwOrchError> {
-        let wasm_contents = std::fs::read(contract.wasm().path())?;
+        let wasm = contract.wasm();
+        wasm.verify_checksum()?;
+        let wasm_contents = std::fs::read(wasm.path())?;
         let upload_response = Wasm::new(&*self.app.borrow()).store_code(
             &wasm_contents,
-            None,
+            contract
+                .instantiate_permission()
+                .as_ref()
+                .map(access_config),
             &self.sender.borrow(),
         )?;
 
@@ -222,6 +256,10 @@ impl<S: StateInterface> TxHandler for OsmosisTestTube<S> {
         })
     }
 
+    fn upload_checksum(&self, contract: &impl Uploadable) -> Option<String> {
+        contract.wasm().checksum().ok()
+    }
+
     fn execute<E: Serialize + Debug>(
         &self,
         exec_msg: &E,
@@ -307,6 +345,73 @@ impl<S: StateInterface> TxHandler for OsmosisTestTube<S> {
     }
 }
 
+impl<S: StateInterface> WasmQuerier for OsmosisTestTube<S> {
+    fn code_checksum(&self, code_id: u64) -> Result<String, CwOrchError> {
+        let code_info = self
+            .app
+            .borrow()
+            .query::<_, QueryCodeResponse>(
+                "/cosmwasm.wasm.v1.Query/Code",
+                &QueryCodeRequest { code_id },
+            )?
+            .code_info
+            .ok_or_else(|| CwOrchError::StdErr(format!("code id {code_id} not found")))?;
+        Ok(hex::encode(code_info.data_hash))
+    }
+
+    fn contract_info(&self, address: &Addr) -> Result<ContractInfo, CwOrchError> {
+        let info = self
+            .app
+            .borrow()
+            .query::<_, QueryContractInfoResponse>(
+                "/cosmwasm.wasm.v1.Query/ContractInfo",
+                &QueryContractInfoRequest {
+                    address: address.to_string(),
+                },
+            )?
+            .contract_info
+            .ok_or_else(|| CwOrchError::StdErr(format!("contract {address} not found")))?;
+        Ok(ContractInfo {
+            code_id: info.code_id,
+            creator: Addr::unchecked(info.creator),
+            admin: (!info.admin.is_empty()).then(|| Addr::unchecked(info.admin)),
+        })
+    }
+
+    fn find_code(
+        &self,
+        checksum: &str,
+        uploader: Option<&Addr>,
+    ) -> Result<Option<u64>, CwOrchError> {
+        let mut search = CodeSearch::new(checksum, uploader.map(Addr::as_str));
+        while let Some(key) = search.next_page() {
+            let resp = self.app.borrow().query::<_, QueryCodesResponse>(
+                "/cosmwasm.wasm.v1.Query/Codes",
+                &QueryCodesRequest {
+                    pagination: Some(PageRequest {
+                        key,
+                        offset: 0,
+                        limit: CODES_PAGE_SIZE,
+                        count_total: false,
+                        reverse: true,
+                    }),
+                },
+            )?;
+            let codes = resp.code_infos.iter().map(|code| {
+                (
+                    code.code_id,
+                    code.creator.as_str(),
+                    code.data_hash.as_slice(),
+                )
+            });
+            if let Some(code_id) = search.page(codes, resp.pagination.map(|page| page.next_key)) {
+                return Ok(Some(code_id));
+            }
+        }
+        Ok(None)
+    }
+}
+
 impl<T: CwOrchExecute<OsmosisTestTube> + ContractInstance<OsmosisTestTube> + Clone>
     CallAs<OsmosisTestTube> for T
 {
@@ -1,8 +1,324 @@
 pub use artifacts_dir::from_workspace;
-pub use artifacts_dir::ArtifactsDir;
+pub use artifacts_dir::{ArtifactsDir, OptimizerArch};
+pub use error::ArtifactError;
+pub use wasm_analysis::{WasmAnalysis, WasmLimits};
 pub use wasm_path::WasmPath;
 
+mod error {
+    use thiserror::Error;
+
+    /// Errors raised when checking the artifacts built by the optimizer
+    #[derive(Error, Debug)]
+    pub enum ArtifactError {
+        #[allow(missing_docs)]
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        /// A line of `checksums.txt` isn't `<checksum>  <file name>`
+        #[error("malformed line {line} in {path}: {content}")]
+        MalformedChecksums {
+            /// Path of the checksums file
+            path: String,
+            /// Line number, starting at 1
+            line: usize,
+            /// Content of the line
+            content: String,
+        },
+        /// No artifact matches the name
+        #[error("no wasm file matching {name} in {dir}, available: {}", .available.join(", "))]
+        NotFound {
+            /// Name that was looked up
+            name: String,
+            /// Artifacts directory
+            dir: String,
+            /// Wasm files in the directory
+            available: Vec<String>,
+        },
+        /// Artifacts of several contracts match the name
+        #[error("several wasm files match {name}: {}, use the exact contract name", .candidates.join(", "))]
+        Ambiguous {
+            /// Name that was looked up
+            name: String,
+            /// Matching wasm files
+            candidates: Vec<String>,
+        },
+        /// The artifact changed since the optimizer built it
+        #[error("{file} has checksum {actual} but checksums.txt lists {expected}, the artifact is stale or was modified")]
+        ChecksumMismatch {
+            /// File name of the artifact
+            file: String,
+            /// Checksum in `checksums.txt`
+            expected: String,
+            /// Checksum of the file
+            actual: String,
+        },
+        /// The wasm file would be rejected by the chain on upload
+        #[error("{file} can't be uploaded: {}", .problems.join("; "))]
+        InvalidWasm {
+            /// File name of the artifact
+            file: String,
+            /// Everything that makes the chain reject the file
+            problems: Vec<String>,
+        },
+    }
+}
+
+mod wasm_analysis {
+    use wasmparser::{ExternalKind, Operator, Parser, Payload};
+
+    /// Interface version of the `interface_version_*` export expected by CosmWasm 1.x chains
+    const SUPPORTED_INTERFACE_VERSION: u32 = 8;
+    /// Exports the CosmWasm VM calls to pass data to the contract
+    const REQUIRED_EXPORTS: [&str; 2] = ["allocate", "deallocate"];
+    /// Module all the contract's imports must come from
+    const IMPORT_MODULE: &str = "env";
+    const ENTRY_POINTS: [&str; 12] = [
+        "instantiate",
+        "execute",
+        "query",
+        "migrate",
+        "sudo",
+        "reply",
+        "ibc_channel_open",
+        "ibc_channel_connect",
+        "ibc_channel_close",
+        "ibc_packet_receive",
+        "ibc_packet_ack",
+        "ibc_packet_timeout",
+    ];
+    const INTERFACE_VERSION_PREFIX: &str = "interface_version_";
+    const CAPABILITY_PREFIX: &str = "requires_";
+
+    /// Limits of the chain the wasm file is uploaded to
+    #[derive(Clone, Debug)]
+    pub struct WasmLimits {
+        /// Maximum size of the uncompressed wasm file in bytes.
+        /// Defaults to 800 KiB, the `MaxWasmSize` of wasmd. It is set in the node config, not in the chain params, so it can't be queried.
+        pub max_size: u64,
+        /// Capabilities the chain supports, like `iterator`, `stargate` or `cosmwasm_1_2`.
+        /// `None` skips the check, the supported capabilities can't be queried either.
+        pub supported_capabilities: Option<Vec<String>>,
+        /// Reject modules with float operations, which chains before CosmWasm 1.5 don't accept.
+        /// Defaults to `false`, float operations are only reported with a warning.
+        pub reject_floats: bool,
+    }
+
+    impl Default for WasmLimits {
+        fn default() -> Self {
+            Self {
+                max_size: 800 * 1024,
+                supported_capabilities: None,
+                reject_floats: false,
+            }
+        }
+    }
+
+    /// Exports, imports and instructions of a wasm file that decide whether a chain accepts it
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct WasmAnalysis {
+        /// Size of the uncompressed file in bytes
+        pub size: u64,
+        /// Names of the exported functions
+        pub exports: Vec<String>,
+        /// Imported functions as `<module>.<name>`
+        pub imports: Vec<String>,
+        /// Versions of the `interface_version_*` exports, a contract has exactly one
+        pub interface_versions: Vec<u32>,
+        /// Capabilities the contract requires through its `requires_*` exports
+        pub capabilities: Vec<String>,
+        /// Exported CosmWasm entry points, like `instantiate` or `ibc_packet_receive`
+        pub entry_points: Vec<String>,
+        /// Number of scalar float instructions, which chains before CosmWasm 1.5 reject
+        pub float_operations: usize,
+    }
+
+    impl WasmAnalysis {
+        /// Parse the wasm module
+        pub fn parse(wasm: &[u8]) -> Result<Self, wasmparser::BinaryReaderError> {
+            let mut analysis = Self {
+                size: wasm.len() as u64,
+                ..Default::default()
+            };
+            for payload in Parser::new(0).parse_all(wasm) {
+                match payload? {
+                    Payload::ImportSection(reader) => {
+                        for import in reader {
+                            let import = import?;
+                            analysis
+                                .imports
+                                .push(format!("{}.{}", import.module, import.name));
+                        }
+                    }
+                    Payload::ExportSection(reader) => {
+                        for export in reader {
+                            let export = export?;
+                            if export.kind == ExternalKind::Func {
+                                analysis.add_export(export.name);
+                            }
+                        }
+                    }
+                    Payload::CodeSectionEntry(body) => {
+                        for operator in body.get_operators_reader()? {
+                            if is_float(&operator?) {
+                                analysis.float_operations += 1;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(analysis)
+        }
+
+        fn add_export(&mut self, name: &str) {
+            if let Some(version) = name.strip_prefix(INTERFACE_VERSION_PREFIX) {
+                // unparsable versions are reported as unsupported
+                self.interface_versions
+                    .push(version.parse().unwrap_or(u32::MAX));
+            } else if let Some(capability) = name.strip_prefix(CAPABILITY_PREFIX) {
+                self.capabilities.push(capability.to_string());
+            } else if ENTRY_POINTS.contains(&name) {
+                self.entry_points.push(name.to_string());
+            }
+            self.exports.push(name.to_string());
+        }
+
+        /// Everything that makes a chain with these limits reject the module, empty if it can be uploaded
+        pub fn problems(&self, limits: &WasmLimits) -> Vec<String> {
+            let mut problems = vec![];
+            if self.size > limits.max_size {
+                problems.push(format!(
+                    "size of {} bytes exceeds the limit of {} bytes",
+                    self.size, limits.max_size
+                ));
+            }
+            match self.interface_versions[..] {
+                [] => problems.push(format!(
+                    "missing {INTERFACE_VERSION_PREFIX}* export, not a CosmWasm contract"
+                )),
+                [SUPPORTED_INTERFACE_VERSION] => {}
+                [version] => problems.push(format!(
+                    "interface version {version} is not supported, expected {SUPPORTED_INTERFACE_VERSION}"
+                )),
+                _ => problems.push(format!(
+                    "several {INTERFACE_VERSION_PREFIX}* exports"
+                )),
+            }
+            for export in REQUIRED_EXPORTS {
+                if !self.exports.iter().any(|e| e == export) {
+                    problems.push(format!("missing required export {export}"));
+                }
+            }
+            if let Some(supported) = &limits.supported_capabilities {
+                for capability in &self.capabilities {
+                    if !supported.contains(capability) {
+                        problems.push(format!("requires unsupported capability {capability}"));
+                    }
+                }
+            }
+            for import in &self.imports {
+                if !import.starts_with(&format!("{IMPORT_MODULE}.")) {
+                    problems.push(format!(
+                        "imports {import} from outside the {IMPORT_MODULE} module"
+                    ));
+                }
+            }
+            if limits.reject_floats && self.float_operations > 0 {
+                problems.push(format!(
+                    "contains {} float operations",
+                    self.float_operations
+                ));
+            }
+            problems
+        }
+    }
+
+    /// Scalar float operators, SIMD operators are not counted
+    fn is_float(operator: &Operator) -> bool {
+        use Operator::*;
+        matches!(
+            operator,
+            F32Load { .. }
+                | F64Load { .. }
+                | F32Store { .. }
+                | F64Store { .. }
+                | F32Const { .. }
+                | F64Const { .. }
+                | F32Eq
+                | F32Ne
+                | F32Lt
+                | F32Gt
+                | F32Le
+                | F32Ge
+                | F64Eq
+                | F64Ne
+                | F64Lt
+                | F64Gt
+                | F64Le
+                | F64Ge
+                | F32Abs
+                | F32Neg
+                | F32Ceil
+                | F32Floor
+                | F32Trunc
+                | F32Nearest
+                | F32Sqrt
+                | F32Add
+                | F32Sub
+                | F32Mul
+                | F32Div
+                | F32Min
+                | F32Max
+                | F32Copysign
+                | F64Abs
+                | F64Neg
+                | F64Ceil
+                | F64Floor
+                | F64Trunc
+                | F64Nearest
+                | F64Sqrt
+                | F64Add
+                | F64Sub
+                | F64Mul
+                | F64Div
+                | F64Min
+                | F64Max
+                | F64Copysign
+                | I32TruncF32S
+                | I32TruncF32U
+                | I32TruncF64S
+                | I32TruncF64U
+                | I64TruncF32S
+                | I64TruncF32U
+                | I64TruncF64S
+                | I64TruncF64U
+                | F32ConvertI32S
+                | F32ConvertI32U
+                | F32ConvertI64S
+                | F32ConvertI64U
+                | F32DemoteF64
+                | F64ConvertI32S
+                | F64ConvertI32U
+                | F64ConvertI64S
+                | F64ConvertI64U
+                | F64PromoteF32
+                | I32ReinterpretF32
+                | I64ReinterpretF64
+                | F32ReinterpretI32
+                | F64ReinterpretI64
+                | I32TruncSatF32S
+                | I32TruncSatF32U
+                | I32TruncSatF64S
+                | I32TruncSatF64U
+                | I64TruncSatF32S
+                | I64TruncSatF32U
+                | I64TruncSatF64S
+                | I64TruncSatF64U
+        )
+    }
+}
+
 mod wasm_path {
+    use super::{artifacts_dir::read_checksums, ArtifactError, WasmAnalysis, WasmLimits};
     use crate::error::CwOrchError;
     use cosmwasm_std::ensure_eq;
     use sha256::TrySha256Digest;
@@ -52,14 +368,115 @@ mod wasm_path {
             let checksum = self.path().digest()?;
             Ok(checksum)
         }
+
+        /// Check the file against the `checksums.txt` written by the optimizer next to it.
+        /// Files without a `checksums.txt` entry are not checked. Daemons check it before every upload.
+        pub fn verify_checksum(&self) -> Result<(), ArtifactError> {
+            let (Some(dir), Some(file)) = (self.path().parent(), self.path().file_name()) else {
+                return Ok(());
+            };
+            let file = file.to_string_lossy();
+            let Some(expected) = read_checksums(dir)?.remove(file.as_ref()) else {
+                log::debug!("No checksum listed for {}", file);
+                return Ok(());
+            };
+            let actual = self.path().digest()?;
+            if !actual.eq_ignore_ascii_case(&expected) {
+                return Err(ArtifactError::ChecksumMismatch {
+                    file: file.into_owned(),
+                    expected,
+                    actual,
+                });
+            }
+            Ok(())
+        }
+
+        /// Parse the exports, imports and instructions of the WASM file.
+        pub fn analyze(&self) -> Result<WasmAnalysis, ArtifactError> {
+            let wasm = std::fs::read(self.path())?;
+            WasmAnalysis::parse(&wasm).map_err(|e| ArtifactError::InvalidWasm {
+                file: self.file_name(),
+                problems: vec![format!("not a valid wasm module: {e}")],
+            })
+        }
+
+        /// Check that a chain with these limits accepts the WASM file, before paying for a failing upload.
+        pub fn validate(&self, limits: &WasmLimits) -> Result<WasmAnalysis, ArtifactError> {
+            let analysis = self.analyze()?;
+            let problems = analysis.problems(limits);
+            if !problems.is_empty() {
+                return Err(ArtifactError::InvalidWasm {
+                    file: self.file_name(),
+                    problems,
+                });
+            }
+            if analysis.float_operations > 0 {
+                log::warn!(
+                    "{} contains {} float operations, chains before CosmWasm 1.5 reject it",
+                    self.file_name(),
+                    analysis.float_operations
+                );
+            }
+            if !analysis.capabilities.is_empty() {
+                log::debug!(
+                    "{} requires capabilities {:?}",
+                    self.file_name(),
+                    analysis.capabilities
+                );
+            }
+            Ok(analysis)
+        }
+
+        fn file_name(&self) -> String {
+            self.path()
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        }
     }
 }
 
 mod artifacts_dir {
-    use std::{env, fs, path::PathBuf};
+    use std::{
+        collections::{BTreeSet, HashMap},
+        env, fs,
+        io::ErrorKind,
+        path::{Path, PathBuf},
+    };
 
+    use super::ArtifactError;
     use crate::{error::CwOrchError, paths::wasm_path::WasmPath};
 
+    /// File the optimizer writes the checksums of the artifacts to
+    pub(crate) const CHECKSUMS_FILE: &str = "checksums.txt";
+
+    /// Checksums in the `checksums.txt` of the directory, by file name.
+    /// A directory without `checksums.txt` has no checksums.
+    pub(crate) fn read_checksums(dir: &Path) -> Result<HashMap<String, String>, ArtifactError> {
+        let path = dir.join(CHECKSUMS_FILE);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(
+                |(index, line)| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                    [checksum, file] => Ok((file.to_string(), checksum.to_string())),
+                    _ => Err(ArtifactError::MalformedChecksums {
+                        path: path.display().to_string(),
+                        line: index + 1,
+                        content: line.to_string(),
+                    }),
+                },
+            )
+            .collect()
+    }
+
     pub fn find_workspace_dir(start_path: Option<String>) -> ::std::path::PathBuf {
         let crate_path = start_path.unwrap_or(env!("CARGO_MANIFEST_DIR").to_string());
         let mut current_dir = ::std::path::PathBuf::from(crate_path);
@@ -92,6 +509,47 @@ mod artifacts_dir {
     }
     pub use from_workspace;
 
+    /// Architecture the optimizer ran on.
+    /// The optimizer names its outputs `<contract>.wasm` on x86_64 and `<contract>-aarch64.wasm` on aarch64.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub enum OptimizerArch {
+        /// Outputs of the x86_64 optimizer, the reproducible builds usually deployed
+        #[default]
+        X86_64,
+        /// Outputs of the `-arm64` optimizer
+        Aarch64,
+    }
+
+    const AARCH64_SUFFIX: &str = "-aarch64";
+
+    /// Wasm file in the artifacts directory
+    struct Artifact {
+        /// Contract name, the file stem without the architecture suffix
+        contract: String,
+        arch: OptimizerArch,
+        file_name: String,
+    }
+
+    impl Artifact {
+        fn parse(file_name: String) -> Option<Self> {
+            let stem = file_name.strip_suffix(".wasm")?;
+            let (contract, arch) = match stem.strip_suffix(AARCH64_SUFFIX) {
+                Some(contract) => (contract, OptimizerArch::Aarch64),
+                None => (stem, OptimizerArch::X86_64),
+            };
+            Some(Self {
+                contract: normalize(contract),
+                arch,
+                file_name,
+            })
+        }
+    }
+
+    /// Crate names use dashes where the optimizer outputs use underscores
+    fn normalize(name: &str) -> String {
+        name.replace('-', "_")
+    }
+
     /// Points to a directory containing WASM files
     ///
     /// # Example
@@ -103,10 +561,13 @@ mod artifacts_dir {
     /// // Or create a new one.
     /// let artifact_dir = ArtifactsDir::new("path/to/artifacts");
     ///
-    /// // Get a path to a WASM file that contains the string "my_contract".
+    /// // Get a path to the WASM file of "my_contract".
     /// let wasm_path: WasmPath = artifact_dir.find_wasm_path("my_contract").unwrap();
     /// ```
-    pub struct ArtifactsDir(PathBuf);
+    pub struct ArtifactsDir {
+        path: PathBuf,
+        arch: OptimizerArch,
+    }
 
     impl ArtifactsDir {
         /// Get the artifacts directory from the environment variable `ARTIFACTS_DIR`.
@@ -131,36 +592,273 @@ mod artifacts_dir {
                 "provided path {} does not exist",
                 path.display()
             );
-            Self(path)
+            Self {
+                path,
+                arch: OptimizerArch::default(),
+            }
+        }
+
+        /// Prefer the outputs of the optimizer for this architecture when both are present
+        pub fn prefer_arch(&mut self, arch: OptimizerArch) -> &mut Self {
+            self.arch = arch;
+            self
         }
 
         /// Get the path to the artifacts directory
         pub fn path(&self) -> &PathBuf {
-            &self.0
+            &self.path
+        }
+
+        /// Checksums listed in the `checksums.txt` written by the optimizer, by file name.
+        /// Empty if there is no `checksums.txt`.
+        pub fn checksums(&self) -> Result<HashMap<String, String>, CwOrchError> {
+            Ok(read_checksums(self.path())?)
         }
 
-        /// Find a WASM file in the artifacts directory that contains the given name.
+        /// Find the WASM file of a contract in the artifacts directory.
+        ///
+        /// The contract named exactly `name` is preferred, dashes and underscores being equivalent.
+        /// Otherwise the contract whose name contains `name` is used, which errors if there are several.
+        /// The build for the [preferred](Self::prefer_arch) architecture is used.
+        /// A contract only built for the other architecture is used with a warning,
+        /// as its checksum differs from the reproducible build.
         pub fn find_wasm_path(&self, name: &str) -> Result<WasmPath, CwOrchError> {
-            let path_str = fs::read_dir(self.path())?
-                .find_map(|entry| {
+            let mut artifacts = fs::read_dir(self.path())?
+                .filter_map(|entry| {
                     let path = entry.ok()?.path();
-                    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
-                    if path.is_file()
-                        && path.extension().unwrap_or_default() == "wasm"
-                        && file_name.contains(name)
-                    {
-                        Some(file_name.into_owned())
-                    } else {
-                        None
+                    if !path.is_file() {
+                        return None;
                     }
+                    Artifact::parse(path.file_name()?.to_string_lossy().into_owned())
                 })
-                .ok_or_else(|| {
-                    CwOrchError::StdErr(format!(
-                        "Could not find wasm file with name {} in artifacts dir",
-                        name,
-                    ))
+                .collect::<Vec<_>>();
+            artifacts.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+            let normalized = normalize(name);
+            let exact = artifacts
+                .iter()
+                .filter(|artifact| artifact.contract == normalized)
+                .collect::<Vec<_>>();
+            let matches = if exact.is_empty() {
+                artifacts
+                    .iter()
+                    .filter(|artifact| artifact.contract.contains(&normalized))
+                    .collect()
+            } else {
+                exact
+            };
+
+            let contracts = matches
+                .iter()
+                .map(|artifact| artifact.contract.as_str())
+                .collect::<BTreeSet<_>>();
+            if contracts.len() > 1 {
+                return Err(ArtifactError::Ambiguous {
+                    name: name.to_string(),
+                    candidates: matches.iter().map(|a| a.file_name.clone()).collect(),
+                }
+                .into());
+            }
+            let artifact = matches
+                .iter()
+                .find(|artifact| artifact.arch == self.arch)
+                .or_else(|| matches.first())
+                .ok_or_else(|| ArtifactError::NotFound {
+                    name: name.to_string(),
+                    dir: self.path().display().to_string(),
+                    available: artifacts.iter().map(|a| a.file_name.clone()).collect(),
                 })?;
-            WasmPath::new(self.path().join(path_str))
+            if artifact.arch != self.arch {
+                log::warn!(
+                    "Using {} built for {:?}, no {:?} build found. Its checksum won't match the reproducible build, prefer {:?} to silence this warning",
+                    artifact.file_name,
+                    artifact.arch,
+                    self.arch,
+                    artifact.arch
+                );
+            }
+            WasmPath::new(self.path().join(&artifact.file_name))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+    use crate::error::CwOrchError;
+
+    #[test]
+    fn artifacts_are_verified_against_checksums() {
+        let dir = env::temp_dir().join("cw-orch-artifacts-checksums");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("counter.wasm"), b"counter").unwrap();
+        fs::write(dir.join("unlisted.wasm"), b"unlisted").unwrap();
+        let counter_checksum = sha256::digest("counter");
+        fs::write(
+            dir.join("checksums.txt"),
+            format!(
+                "{counter_checksum}  counter.wasm\n{}  cw20.wasm\n",
+                sha256::digest("cw20")
+            ),
+        )
+        .unwrap();
+
+        let artifacts = ArtifactsDir::new(&dir);
+        let checksums = artifacts.checksums().unwrap();
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums["counter.wasm"], counter_checksum);
+
+        let counter = WasmPath::new(dir.join("counter.wasm")).unwrap();
+        counter.verify_checksum().unwrap();
+        WasmPath::new(dir.join("unlisted.wasm"))
+            .unwrap()
+            .verify_checksum()
+            .unwrap();
+
+        // a modified artifact no longer matches
+        fs::write(dir.join("counter.wasm"), b"modified").unwrap();
+        assert!(matches!(
+            counter.verify_checksum(),
+            Err(ArtifactError::ChecksumMismatch { file, .. }) if file == "counter.wasm"
+        ));
+
+        fs::write(dir.join("checksums.txt"), "not a checksum line at all\n").unwrap();
+        assert!(matches!(
+            artifacts.checksums(),
+            Err(CwOrchError::Artifact(ArtifactError::MalformedChecksums {
+                line: 1,
+                ..
+            }))
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn artifact_selection_is_deterministic() {
+        let dir = env::temp_dir().join("cw-orch-artifacts-selection");
+        fs::create_dir_all(&dir).unwrap();
+        for file in [
+            "cw20_base.wasm",
+            "cw20_base-aarch64.wasm",
+            "cw20_ics20.wasm",
+            "counter_contract-aarch64.wasm",
+        ] {
+            fs::write(dir.join(file), file).unwrap();
         }
+        let file_name = |path: WasmPath| {
+            path.path()
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        let mut artifacts = ArtifactsDir::new(&dir);
+        assert_eq!(
+            file_name(artifacts.find_wasm_path("cw20-base").unwrap()),
+            "cw20_base.wasm"
+        );
+        assert_eq!(
+            file_name(artifacts.find_wasm_path("ics20").unwrap()),
+            "cw20_ics20.wasm"
+        );
+        // only built for aarch64
+        assert_eq!(
+            file_name(artifacts.find_wasm_path("counter_contract").unwrap()),
+            "counter_contract-aarch64.wasm"
+        );
+
+        artifacts.prefer_arch(OptimizerArch::Aarch64);
+        assert_eq!(
+            file_name(artifacts.find_wasm_path("cw20_base").unwrap()),
+            "cw20_base-aarch64.wasm"
+        );
+
+        assert!(matches!(
+            artifacts.find_wasm_path("cw20"),
+            Err(CwOrchError::Artifact(ArtifactError::Ambiguous { candidates, .. }))
+                if candidates == vec!["cw20_base-aarch64.wasm", "cw20_base.wasm", "cw20_ics20.wasm"]
+        ));
+        assert!(matches!(
+            artifacts.find_wasm_path("cw721"),
+            Err(CwOrchError::Artifact(ArtifactError::NotFound { available, .. })) if available.len() == 4
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn wasm_is_validated_before_upload() {
+        let dir = env::temp_dir().join("cw-orch-wasm-validation");
+        fs::create_dir_all(&dir).unwrap();
+        let contract = wat::parse_str(
+            r#"(module
+                (import "env" "db_read" (func (param i32) (result i32)))
+                (func $noop)
+                (export "interface_version_8" (func $noop))
+                (export "allocate" (func $noop))
+                (export "deallocate" (func $noop))
+                (export "instantiate" (func $noop))
+                (export "query" (func $noop))
+                (export "requires_iterator" (func $noop)))"#,
+        )
+        .unwrap();
+        fs::write(dir.join("contract.wasm"), &contract).unwrap();
+        let float = wat::parse_str(
+            r#"(module
+                (func $add (result f32) (f32.add (f32.const 1) (f32.const 2)))
+                (export "interface_version_7" (func $add)))"#,
+        )
+        .unwrap();
+        fs::write(dir.join("float.wasm"), float).unwrap();
+        fs::write(dir.join("garbage.wasm"), b"not wasm").unwrap();
+
+        let path = WasmPath::new(dir.join("contract.wasm")).unwrap();
+        let analysis = path.validate(&WasmLimits::default()).unwrap();
+        assert_eq!(analysis.size, contract.len() as u64);
+        assert_eq!(analysis.imports, vec!["env.db_read"]);
+        assert_eq!(analysis.interface_versions, vec![8]);
+        assert_eq!(analysis.entry_points, vec!["instantiate", "query"]);
+        assert_eq!(analysis.capabilities, vec!["iterator"]);
+        assert_eq!(analysis.float_operations, 0);
+
+        let limits = WasmLimits {
+            max_size: 10,
+            supported_capabilities: Some(vec!["staking".into()]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            path.validate(&limits),
+            Err(ArtifactError::InvalidWasm { problems, .. }) if problems.len() == 2
+        ));
+
+        let float = WasmPath::new(dir.join("float.wasm")).unwrap();
+        let analysis = float.analyze().unwrap();
+        assert_eq!(analysis.float_operations, 3);
+        // floats are only rejected on request
+        let problems = analysis.problems(&WasmLimits::default());
+        assert_eq!(
+            problems,
+            vec![
+                "interface version 7 is not supported, expected 8",
+                "missing required export allocate",
+                "missing required export deallocate",
+            ]
+        );
+        let limits = WasmLimits {
+            reject_floats: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            analysis.problems(&limits).last().unwrap(),
+            "contains 3 float operations"
+        );
+
+        assert!(matches!(
+            WasmPath::new(dir.join("garbage.wasm")).unwrap().analyze(),
+            Err(ArtifactError::InvalidWasm { file, .. }) if file == "garbage.wasm"
+        ));
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
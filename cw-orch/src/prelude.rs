@@ -12,9 +12,9 @@
 
 // Contract traits
 pub use crate::interface_traits::{
-    CallAs, ContractInstance, CwOrchExecute, CwOrchInstantiate, CwOrchMigrate, CwOrchQuery,
-    CwOrchUpload, ExecutableContract, InstantiableContract, MigratableContract, QueryableContract,
-    Uploadable,
+    AccessConfig, CallAs, ConditionalMigrate, ConditionalUpload, ContractInstance, CwOrchExecute,
+    CwOrchInstantiate, CwOrchMigrate, CwOrchQuery, CwOrchUpload, ExecutableContract,
+    InstantiableContract, MigratableContract, QueryableContract, UploadMode, Uploadable,
 };
 
 pub use crate::state::StateInterface;
@@ -23,7 +23,7 @@ pub use crate::state::StateInterface;
 pub use crate::index_response::IndexResponse;
 
 // Environment
-pub use crate::environment::{CwEnv, TxHandler, TxResponse};
+pub use crate::environment::{CwEnv, TxHandler, TxResponse, WasmQuerier};
 
 // Mock for testing
 pub use crate::mock::Mock;
@@ -36,7 +36,9 @@ pub use crate::osmosis_test_tube::OsmosisTestTube;
 pub use crate::error::CwOrchError;
 
 // Paths for implementing `Uploadable`
-pub use crate::paths::{ArtifactsDir, WasmPath};
+pub use crate::paths::{
+    ArtifactError, ArtifactsDir, OptimizerArch, WasmAnalysis, WasmLimits, WasmPath,
+};
 
 // re-export as it is used in the public API
 pub use cosmwasm_std::{Addr, Coin, Empty};
@@ -47,9 +49,6 @@ pub use cw_multi_test::{Contract as MockContract, ContractWrapper};
 pub use crate::daemon::{
     networks,
     queriers,
-    // sync helpers
-    ConditionalMigrate,
-    ConditionalUpload,
     Daemon,
     DaemonAsync,
     DaemonAsyncBuilder,
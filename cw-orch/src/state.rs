@@ -2,7 +2,11 @@
 
 use crate::error::CwOrchError;
 use cosmwasm_std::Addr;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 /// State accessor trait.
 /// Indicates that the type has access to an underlying state.
@@ -21,12 +25,24 @@ pub trait StateInterface: Clone {
     /// Set the address of a contract using the specified contract id.
     fn set_address(&mut self, contract_id: &str, address: &Addr);
 
+    /// Set the address of a contract, returning an error if the state can't be written.
+    fn try_set_address(&mut self, contract_id: &str, address: &Addr) -> Result<(), CwOrchError> {
+        self.set_address(contract_id, address);
+        Ok(())
+    }
+
     /// Get the code id for a contract with the specified contract id.
     fn get_code_id(&self, contract_id: &str) -> Result<u64, CwOrchError>;
 
     /// Set the code id for a contract with the specified contract id.
     fn set_code_id(&mut self, contract_id: &str, code_id: u64);
 
+    /// Set the code id of a contract, returning an error if the state can't be written.
+    fn try_set_code_id(&mut self, contract_id: &str, code_id: u64) -> Result<(), CwOrchError> {
+        self.set_code_id(contract_id, code_id);
+        Ok(())
+    }
+
     /// Get all addresses related to this deployment.
     fn get_all_addresses(&self) -> Result<HashMap<String, Addr>, CwOrchError>;
 
@@ -36,6 +52,193 @@ pub trait StateInterface: Clone {
     /// Get some details used for deployment on the current chain
     /// This is used for
     fn deploy_details(&self) -> DeployDetails;
+
+    /// Get the deployment history of a contract in this deployment, oldest first.
+    /// Environments that don't keep a history return an empty list.
+    fn get_history(&self, contract_id: &str) -> Result<Vec<DeploymentRecord>, CwOrchError> {
+        let _ = contract_id;
+        Ok(vec![])
+    }
+
+    /// Add a record to the deployment history of a contract.
+    /// Environments that don't keep a history ignore it.
+    fn try_record_deployment(
+        &mut self,
+        contract_id: &str,
+        record: DeploymentRecord,
+    ) -> Result<(), CwOrchError> {
+        let _ = (contract_id, record);
+        Ok(())
+    }
+
+    /// Make the record at `index` of the contract's history the active code id and address.
+    /// The rollback is added to the history as well, so the history is never rewritten.
+    fn rollback(
+        &mut self,
+        contract_id: &str,
+        index: usize,
+    ) -> Result<DeploymentRecord, CwOrchError> {
+        let history = self.get_history(contract_id)?;
+        let record = history.get(index).cloned().ok_or_else(|| {
+            CwOrchError::StdErr(format!(
+                "{contract_id} has no history entry {index}, it has {} entries",
+                history.len()
+            ))
+        })?;
+
+        if let Some(code_id) = record.code_id {
+            self.try_set_code_id(contract_id, code_id)?;
+        }
+        if let Some(address) = &record.address {
+            self.try_set_address(contract_id, &Addr::unchecked(address))?;
+        }
+
+        let rollback = DeploymentRecord {
+            action: DeploymentAction::Rollback,
+            tx_hash: None,
+            timestamp: DeploymentRecord::now(),
+            cw_orch_version: DeploymentRecord::CW_ORCH_VERSION.to_string(),
+            ..record.clone()
+        };
+        self.try_record_deployment(contract_id, rollback)?;
+        Ok(record)
+    }
+
+    /// Get the journal of a [`DeploymentPlan`](crate::deploy::DeploymentPlan) in this deployment, by step id.
+    /// Environments that don't keep a journal return an empty journal, so every step is run.
+    fn get_journal(&self, plan: &str) -> Result<HashMap<String, StepRecord>, CwOrchError> {
+        let _ = plan;
+        Ok(HashMap::new())
+    }
+
+    /// Record the outcome of a step of a deployment plan.
+    /// Environments that don't keep a journal ignore it.
+    fn try_record_step(
+        &mut self,
+        plan: &str,
+        step: &str,
+        record: StepRecord,
+    ) -> Result<(), CwOrchError> {
+        let _ = (plan, step, record);
+        Ok(())
+    }
+
+    /// Remove the journal of a deployment plan, so that the plan runs from the start.
+    fn try_clear_journal(&mut self, plan: &str) -> Result<(), CwOrchError> {
+        let _ = plan;
+        Ok(())
+    }
+}
+
+/// Action that changed the code id or address of a contract
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentAction {
+    /// The code was uploaded
+    Upload,
+    /// Code already on chain was registered instead of uploading it
+    Reuse,
+    /// The contract was instantiated
+    Instantiate,
+    /// The contract was migrated to a new code id
+    Migrate,
+    /// The code id and address were restored from an earlier record
+    Rollback,
+}
+
+/// Entry of the deployment history of a contract
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeploymentRecord {
+    /// What changed
+    pub action: DeploymentAction,
+    /// Code id of the contract after the action
+    pub code_id: Option<u64>,
+    /// Checksum of the uploaded wasm, if reported by the chain
+    pub checksum: Option<String>,
+    /// Address of the contract after the action
+    pub address: Option<String>,
+    /// Hash of the tx that performed the action
+    pub tx_hash: Option<String>,
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    /// Address that sent the tx
+    pub sender: Option<String>,
+    /// Version of cw-orch that performed the action
+    pub cw_orch_version: String,
+}
+
+impl DeploymentRecord {
+    /// Version of this crate
+    pub const CW_ORCH_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    /// Create a record of an action performed now with this version of cw-orch
+    pub fn new(action: DeploymentAction) -> Self {
+        Self {
+            action,
+            code_id: None,
+            checksum: None,
+            address: None,
+            tx_hash: None,
+            timestamp: Self::now(),
+            sender: None,
+            cw_orch_version: Self::CW_ORCH_VERSION.to_string(),
+        }
+    }
+
+    pub(crate) fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or_default()
+    }
+}
+
+/// What a step of a deployment plan does
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepKind {
+    /// Upload code
+    Upload,
+    /// Instantiate contracts
+    Instantiate,
+    /// Configure instantiated contracts, ex. register them with each other
+    Configure,
+}
+
+/// Outcome of a step of a deployment plan
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// The step completed and is skipped when the plan is run again
+    Completed,
+    /// The step failed and is retried when the plan is run again
+    Failed,
+}
+
+/// Entry of the journal of a deployment plan
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StepRecord {
+    /// What the step does
+    pub kind: StepKind,
+    /// Outcome of the last run of the step
+    pub status: StepStatus,
+    /// Error of the last run, if it failed
+    pub error: Option<String>,
+    /// Unix timestamp in seconds of the last run
+    pub timestamp: u64,
+    /// Contract ids whose code id was set by the step
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub code_ids: Vec<String>,
+    /// Contract ids whose address was set by the step
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<String>,
+}
+
+impl StepRecord {
+    /// Whether the step completed
+    pub fn is_completed(&self) -> bool {
+        self.status == StepStatus::Completed
+    }
 }
 
 /// Details about the chain and env you are deploying on
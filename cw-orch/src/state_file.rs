@@ -0,0 +1,289 @@
+//! Typed layout of the deployment state file.
+//!
+//! The file is laid out as:
+//! ```json
+//! {
+//!     "version": 1,
+//!     "chains": {
+//!         "juno": {
+//!             "juno-1": {
+//!                 "code_ids": { "counter": 12 },
+//!                 "default": { "counter": "juno1..." },
+//!                 "history": { "counter": { "default": [] } },
+//!                 "journal": { "my-app": { "default": {} } }
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//! Files written by older versions of cw-orch don't have a version and keep the chains at the top level.
+//! They are migrated to the current layout when read, and written in the current layout on the next change.
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::state::{DeploymentRecord, StepRecord};
+
+/// Version of the state file layout written by this version of cw-orch
+pub const STATE_FILE_VERSION: u64 = 1;
+
+/// Key of the layout version in the state file
+const VERSION_KEY: &str = "version";
+/// Key of the chains in the state file, since version 1
+pub(crate) const CHAINS_KEY: &str = "chains";
+
+/// Errors raised when reading a state file
+#[derive(Error, Debug)]
+pub enum StateFileError {
+    #[allow(missing_docs)]
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[allow(missing_docs)]
+    #[error("state file is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The file was written by a newer version of cw-orch
+    #[error(
+        "state file version {found} is not supported, the latest supported version is {supported}"
+    )]
+    UnsupportedVersion {
+        /// Version of the file
+        found: u64,
+        /// Latest version this version of cw-orch can read
+        supported: u64,
+    },
+    /// An entry of the file doesn't have the expected shape
+    #[error("malformed state file entry {path}: {reason}")]
+    Malformed {
+        /// Path of the entry, ex. `chains/juno/juno-1`
+        path: String,
+        /// What is wrong with the entry
+        reason: String,
+    },
+}
+
+impl StateFileError {
+    fn malformed(path: impl Into<String>, reason: impl ToString) -> Self {
+        Self::Malformed {
+            path: path.into(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Content of a state file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateFile {
+    /// Layout version, see [`STATE_FILE_VERSION`]
+    pub version: u64,
+    /// State of the chains, by chain name and chain id
+    pub chains: BTreeMap<String, BTreeMap<String, ChainState>>,
+}
+
+impl Default for StateFile {
+    fn default() -> Self {
+        Self {
+            version: STATE_FILE_VERSION,
+            chains: BTreeMap::new(),
+        }
+    }
+}
+
+/// Deployment state of a single chain
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainState {
+    /// Code ids, by contract id
+    #[serde(default)]
+    pub code_ids: BTreeMap<String, u64>,
+    /// Deployment history, by contract id and deployment id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub history: BTreeMap<String, BTreeMap<String, Vec<DeploymentRecord>>>,
+    /// Journals of the deployment plans, by plan, deployment id and step id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub journal: BTreeMap<String, BTreeMap<String, BTreeMap<String, StepRecord>>>,
+    /// Other namespaces, mostly the contract addresses by deployment id and contract id.
+    /// Kept as is, entries that aren't addresses are preserved.
+    #[serde(flatten)]
+    pub namespaces: BTreeMap<String, Value>,
+}
+
+impl ChainState {
+    /// Code id of the contract
+    pub fn code_id(&self, contract_id: &str) -> Option<u64> {
+        self.code_ids.get(contract_id).copied()
+    }
+
+    /// Address of the contract in the deployment
+    pub fn address(&self, deployment_id: &str, contract_id: &str) -> Option<&str> {
+        self.namespaces
+            .get(deployment_id)?
+            .get(contract_id)?
+            .as_str()
+    }
+}
+
+impl StateFile {
+    /// Read the state file at `path`, migrating older layouts.
+    /// An empty file is read as an empty state.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StateFileError> {
+        let content = fs::read(path)?;
+        if content.is_empty() {
+            return Ok(Self::default());
+        }
+        Self::from_json(serde_json::from_slice(&content)?)
+    }
+
+    /// Parse the content of a state file, migrating older layouts.
+    pub fn from_json(json: Value) -> Result<Self, StateFileError> {
+        let json = migrate(json)?;
+        let mut chains = BTreeMap::new();
+        for (chain_name, chain_ids) in entries(&json[CHAINS_KEY], CHAINS_KEY)? {
+            let path = format!("{CHAINS_KEY}/{chain_name}");
+            let mut states = BTreeMap::new();
+            for (chain_id, state) in entries(chain_ids, &path)? {
+                let path = format!("{path}/{chain_id}");
+                let state = serde_json::from_value(state.clone())
+                    .map_err(|e| StateFileError::malformed(path, e))?;
+                states.insert(chain_id.clone(), state);
+            }
+            chains.insert(chain_name.clone(), states);
+        }
+        Ok(Self {
+            version: STATE_FILE_VERSION,
+            chains,
+        })
+    }
+
+    /// State of the chain, if any
+    pub fn chain(&self, chain_name: &str, chain_id: &str) -> Option<&ChainState> {
+        self.chains.get(chain_name)?.get(chain_id)
+    }
+
+    /// Ids of all the chains in the file
+    pub fn chain_ids(&self) -> Vec<String> {
+        self.chains
+            .values()
+            .flat_map(|chain_ids| chain_ids.keys().cloned())
+            .collect()
+    }
+}
+
+/// Migrate the content of a state file to the current layout.
+/// Entries other than the layout itself are left untouched.
+pub fn migrate(json: Value) -> Result<Value, StateFileError> {
+    let mut json = match json {
+        Value::Null => json!({}),
+        Value::Object(_) => json,
+        other => {
+            return Err(StateFileError::malformed(
+                "state",
+                format!("expected an object, found {other}"),
+            ))
+        }
+    };
+
+    let mut version = match &json[VERSION_KEY] {
+        Value::Null => 0,
+        version => version
+            .as_u64()
+            .ok_or_else(|| StateFileError::malformed(VERSION_KEY, version))?,
+    };
+    if version > STATE_FILE_VERSION {
+        return Err(StateFileError::UnsupportedVersion {
+            found: version,
+            supported: STATE_FILE_VERSION,
+        });
+    }
+
+    while version < STATE_FILE_VERSION {
+        json = match version {
+            0 => migrate_v0(json),
+            _ => unreachable!("all versions below the current one have a migration"),
+        };
+        version += 1;
+    }
+    Ok(json)
+}
+
+/// Version 0 has no version and keeps the chains at the top level
+fn migrate_v0(chains: Value) -> Value {
+    json!({ VERSION_KEY: 1, CHAINS_KEY: chains })
+}
+
+/// Entries of the object at `path`, a missing object has no entries
+fn entries<'a>(
+    value: &'a Value,
+    path: &str,
+) -> Result<impl Iterator<Item = (&'a String, &'a Value)>, StateFileError> {
+    if !value.is_null() && !value.is_object() {
+        return Err(StateFileError::malformed(
+            path,
+            format!("expected an object, found {value}"),
+        ));
+    }
+    Ok(value.as_object().into_iter().flatten())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_unversioned_layout() {
+        let legacy = json!({
+            "juno": {
+                "juno-1": {
+                    "code_ids": { "counter": 12 },
+                    "default": { "counter": "juno1counter" },
+                    "metadata": { "counter": { "audited": true } }
+                },
+                "uni-6": {}
+            }
+        });
+
+        let migrated = migrate(legacy.clone()).unwrap();
+        assert_eq!(migrated, json!({ "version": 1, "chains": legacy }));
+        // migrating is idempotent
+        assert_eq!(migrate(migrated.clone()).unwrap(), migrated);
+
+        let state = StateFile::from_json(migrated).unwrap();
+        let juno = state.chain("juno", "juno-1").unwrap();
+        assert_eq!(juno.code_id("counter"), Some(12));
+        assert_eq!(juno.address("default", "counter"), Some("juno1counter"));
+        // unknown entries are kept
+        assert_eq!(juno.address("metadata", "counter"), None);
+        assert_eq!(
+            juno.namespaces["metadata"],
+            json!({ "counter": { "audited": true } })
+        );
+        assert_eq!(state.chain_ids(), vec!["juno-1", "uni-6"]);
+    }
+
+    #[test]
+    fn malformed_entries_are_errors() {
+        let res = StateFile::from_json(json!({
+            "version": 1,
+            "chains": { "juno": { "juno-1": { "code_ids": { "counter": "12" } } } }
+        }));
+        let err = res.unwrap_err().to_string();
+        assert!(
+            err.starts_with("malformed state file entry chains/juno/juno-1:"),
+            "{err}"
+        );
+
+        let res = StateFile::from_json(json!({ "juno": ["juno-1"] }));
+        let err = res.unwrap_err().to_string();
+        assert!(
+            err.starts_with("malformed state file entry chains/juno:"),
+            "{err}"
+        );
+
+        let res = StateFile::from_json(json!({ "version": 2, "chains": {} }));
+        assert!(matches!(
+            res,
+            Err(StateFileError::UnsupportedVersion { found: 2, .. })
+        ));
+    }
+}
@@ -25,7 +25,7 @@ mod queriers {
         AccountId, Denom,
     };
 
-    pub async fn build_channel() -> tonic::transport::Channel {
+    pub async fn build_channel() -> cw_orch::daemon::DaemonChannel {
         let network = networks::LOCAL_JUNO;
 
         let grpcs: Vec<Grpc> = vec![Grpc {